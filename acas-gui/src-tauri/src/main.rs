@@ -3,19 +3,34 @@
     windows_subsystem = "windows"
 )]
 
+use std::time::Duration;
+
+use acas::limits::{with_limits, Limits};
 use acas::parse::parse_into_expression;
+use acas::simplify::SimpleExpr;
+
+// keep the window responsive on expressions that take too long
+fn simplify(expression: &str) -> Result<SimpleExpr, String> {
+    let x = parse_into_expression(expression).map_err(|x| format!("{x:?}"))?;
+    let limits = Limits::new().with_timeout(Duration::from_secs(5));
+    with_limits(limits, || x.simplify()).map_err(|e| e.to_string())
+}
 
 // Learn more about Tauri commands at https://tauri.app/v1/guides/features/command
 #[tauri::command]
 fn parse(expression: &str) -> Result<String, String> {
-    parse_into_expression(expression).map_err(|x| format!("{x:?}")).map(|x| {
-        x.simplify().map(|x| acas::print::to_latex(&x)).unwrap_or_else(|_| "undefined".into())
-    })
+    simplify(expression).map(|x| acas::print::to_latex(&x))
+}
+
+// the simplified expression as a tree, in the JSON format of acas
+#[tauri::command]
+fn parse_tree(expression: &str) -> Result<SimpleExpr, String> {
+    simplify(expression)
 }
 
 fn main() {
     tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![parse])
+        .invoke_handler(tauri::generate_handler![parse, parse_tree])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,268 @@
+// Assumptions about symbols, and what follows from them for expressions.
+//
+// Queries are conservative: `is_positive(x, ctx)` is true only if `x` is known to be
+// positive, and false when it is negative or nothing is known. `Pi` and `E` are positive.
+use std::collections::{BTreeMap, BTreeSet};
+
+use num::{Signed, Zero};
+
+use crate::simplify::SimpleExpr;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum Assumption {
+    Positive,
+    Integer,
+    Real,
+    Nonzero,
+}
+
+impl Assumption {
+    // the assumptions that follow from this one
+    fn implied(self) -> &'static [Assumption] {
+        use Assumption::*;
+        match self {
+            Positive => &[Positive, Real, Nonzero],
+            Integer => &[Integer, Real],
+            Real => &[Real],
+            Nonzero => &[Nonzero],
+        }
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Eq, Debug, Hash)]
+pub struct Assumptions {
+    symbols: BTreeMap<String, BTreeSet<Assumption>>,
+}
+
+impl Assumptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // `Assumptions::new().with("x", Positive).with("n", Integer)`
+    pub fn with(mut self, symbol: &str, assumption: Assumption) -> Self {
+        self.assume(symbol, assumption);
+        self
+    }
+
+    pub fn assume(&mut self, symbol: &str, assumption: Assumption) {
+        self.symbols
+            .entry(symbol.into())
+            .or_default()
+            .extend(assumption.implied());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn holds(&self, symbol: &str, assumption: Assumption) -> bool {
+        matches!(
+            (symbol, assumption),
+            (
+                "Pi" | "E",
+                Assumption::Positive | Assumption::Real | Assumption::Nonzero
+            )
+        ) || self
+            .symbols
+            .get(symbol)
+            .is_some_and(|x| x.contains(&assumption))
+    }
+}
+
+// what is known about the sign of a real expression
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Sign {
+    Zero,
+    Positive,
+    Negative,
+    Nonnegative,
+    Nonpositive,
+}
+
+impl Sign {
+    // whether the sign is negative, and whether zero is excluded
+    fn parts(self) -> Option<(bool, bool)> {
+        match self {
+            Sign::Zero => None,
+            Sign::Positive => Some((false, true)),
+            Sign::Negative => Some((true, true)),
+            Sign::Nonnegative => Some((false, false)),
+            Sign::Nonpositive => Some((true, false)),
+        }
+    }
+
+    fn from_parts(negative: bool, strict: bool) -> Sign {
+        match (negative, strict) {
+            (false, true) => Sign::Positive,
+            (true, true) => Sign::Negative,
+            (false, false) => Sign::Nonnegative,
+            (true, false) => Sign::Nonpositive,
+        }
+    }
+
+    fn add(self, other: Sign) -> Option<Sign> {
+        match (self.parts(), other.parts()) {
+            (None, _) => Some(other),
+            (_, None) => Some(self),
+            (Some((a, s)), Some((b, t))) if a == b => Some(Sign::from_parts(a, s || t)),
+            _ => None,
+        }
+    }
+
+    fn mul(self, other: Sign) -> Sign {
+        match (self.parts(), other.parts()) {
+            (Some((a, s)), Some((b, t))) => Sign::from_parts(a != b, s && t),
+            _ => Sign::Zero,
+        }
+    }
+}
+
+fn sign(expr: &SimpleExpr, ctx: &Assumptions) -> Option<Sign> {
+    match expr {
+        SimpleExpr::Const(c) if c.is_zero() => Some(Sign::Zero),
+        SimpleExpr::Const(c) if c.is_positive() => Some(Sign::Positive),
+        SimpleExpr::Const(_) => Some(Sign::Negative),
+        SimpleExpr::Symbol(s) if ctx.holds(s, Assumption::Positive) => Some(Sign::Positive),
+        SimpleExpr::Sum(terms) => terms
+            .iter()
+            .map(|x| sign(x, ctx))
+            .try_fold(Sign::Zero, |acc, x| acc.add(x?)),
+        SimpleExpr::Product(factors) => factors
+            .iter()
+            .map(|x| sign(x, ctx))
+            .try_fold(Sign::Positive, |acc, x| Some(acc.mul(x?))),
+        SimpleExpr::Pow(p) => {
+            let (base, exponent) = (&p.0, &p.1);
+            let s = sign(base, ctx);
+            if s == Some(Sign::Positive) && is_real(exponent, ctx) {
+                return Some(Sign::Positive);
+            }
+            let nonzero = is_nonzero(base, ctx);
+            match parity(exponent, ctx) {
+                // an even power of a real number
+                Some(false) if is_real(base, ctx) => Some(match nonzero {
+                    true => Sign::Positive,
+                    false => Sign::Nonnegative,
+                }),
+                // an odd power has the sign of the base, a negative one only if it is nonzero
+                Some(true) if is_positive(exponent, ctx) || nonzero => s,
+                _ => match s? {
+                    Sign::Nonnegative | Sign::Zero if is_positive(exponent, ctx) => s,
+                    _ => None,
+                },
+            }
+        }
+        SimpleExpr::Factorial(x) if is_integer(x, ctx) && is_nonnegative(x, ctx) => {
+            Some(Sign::Positive)
+        }
+        SimpleExpr::Function(name, args)
+            if let [x] = args.as_slice()
+                && is_real(x, ctx) =>
+        {
+            match name.as_str() {
+                "Exp" | "Cosh" => Some(Sign::Positive),
+                "Sinh" | "Tanh" | "ArcTan" => sign(x, ctx),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+pub fn is_positive(expr: &SimpleExpr, ctx: &Assumptions) -> bool {
+    sign(expr, ctx) == Some(Sign::Positive)
+}
+
+pub fn is_negative(expr: &SimpleExpr, ctx: &Assumptions) -> bool {
+    sign(expr, ctx) == Some(Sign::Negative)
+}
+
+pub fn is_nonnegative(expr: &SimpleExpr, ctx: &Assumptions) -> bool {
+    matches!(
+        sign(expr, ctx),
+        Some(Sign::Positive | Sign::Nonnegative | Sign::Zero)
+    )
+}
+
+pub fn is_nonzero(expr: &SimpleExpr, ctx: &Assumptions) -> bool {
+    match expr {
+        SimpleExpr::Symbol(s) => ctx.holds(s, Assumption::Nonzero),
+        SimpleExpr::Product(factors) => factors.iter().all(|x| is_nonzero(x, ctx)),
+        SimpleExpr::Pow(p) if is_nonzero(&p.0, ctx) => true,
+        SimpleExpr::Function(name, _) if name == "Exp" => true,
+        x => matches!(sign(x, ctx), Some(Sign::Positive | Sign::Negative)),
+    }
+}
+
+pub fn is_real(expr: &SimpleExpr, ctx: &Assumptions) -> bool {
+    let all = |x: &[SimpleExpr]| x.iter().all(|x| is_real(x, ctx));
+    match expr {
+        SimpleExpr::Const(_) => true,
+        SimpleExpr::Symbol(s) => ctx.holds(s, Assumption::Real),
+        SimpleExpr::Sum(x) | SimpleExpr::Product(x) => all(x),
+        SimpleExpr::Pow(p) => {
+            let (base, exponent) = (&p.0, &p.1);
+            (is_positive(base, ctx) && is_real(exponent, ctx))
+                || (is_integer(exponent, ctx)
+                    && is_real(base, ctx)
+                    && (is_nonnegative(exponent, ctx) || is_nonzero(base, ctx)))
+        }
+        SimpleExpr::Factorial(x) => is_integer(x, ctx) && is_nonnegative(x, ctx),
+        SimpleExpr::Function(name, args) if let [x] = args.as_slice() => match name.as_str() {
+            "Exp" | "Sin" | "Cos" | "Sinh" | "Cosh" | "Tanh" | "ArcTan" => is_real(x, ctx),
+            "Log" | "Sqrt" => is_positive(x, ctx),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+pub fn is_integer(expr: &SimpleExpr, ctx: &Assumptions) -> bool {
+    let all = |x: &[SimpleExpr]| x.iter().all(|x| is_integer(x, ctx));
+    match expr {
+        SimpleExpr::Const(c) => c.is_integer(),
+        SimpleExpr::Symbol(s) => ctx.holds(s, Assumption::Integer),
+        SimpleExpr::Sum(x) | SimpleExpr::Product(x) => all(x),
+        SimpleExpr::Pow(p) => {
+            is_integer(&p.0, ctx) && is_integer(&p.1, ctx) && is_nonnegative(&p.1, ctx)
+        }
+        SimpleExpr::Factorial(x) => is_integer(x, ctx) && is_nonnegative(x, ctx),
+        _ => false,
+    }
+}
+
+// whether an integer expression is odd, `None` if unknown
+fn parity(expr: &SimpleExpr, ctx: &Assumptions) -> Option<bool> {
+    if !is_integer(expr, ctx) {
+        return None;
+    }
+    match expr {
+        SimpleExpr::Const(c) => Some(!(c.numer() % num::BigInt::from(2)).is_zero()),
+        SimpleExpr::Sum(terms) => terms
+            .iter()
+            .map(|x| parity(x, ctx))
+            .try_fold(false, |acc, x| Some(acc != x?)),
+        // even as soon as one factor is
+        SimpleExpr::Product(factors) => {
+            let parities: Vec<_> = factors.iter().map(|x| parity(x, ctx)).collect();
+            if parities.contains(&Some(false)) {
+                Some(false)
+            } else {
+                parities
+                    .into_iter()
+                    .try_fold(true, |acc, x| Some(acc && x?))
+            }
+        }
+        SimpleExpr::Pow(p) if is_positive(&p.1, ctx) => parity(&p.0, ctx),
+        _ => None,
+    }
+}
+
+pub fn is_even(expr: &SimpleExpr, ctx: &Assumptions) -> bool {
+    parity(expr, ctx) == Some(false)
+}
+
+pub fn is_odd(expr: &SimpleExpr, ctx: &Assumptions) -> bool {
+    parity(expr, ctx) == Some(true)
+}
@@ -0,0 +1,43 @@
+// Derivatives, integrals, limits, series, and sums and products of sequences.
+use crate::simplify::{Operation, Product, SimpleExpr, Sum};
+use crate::visit::Node;
+use crate::{BasicAlgebraicExpr, ComputeResult};
+
+mod definite;
+mod derivative;
+mod integrate;
+mod limit;
+mod series;
+mod sum;
+
+pub use definite::{integrate_definite, DefiniteIntegral};
+pub use derivative::derivative;
+pub use integrate::integrate;
+pub use limit::{limit, Direction};
+pub use series::{series, Series};
+pub use sum::{product, sum};
+
+fn is_free_of(expr: &SimpleExpr, var: &str) -> bool {
+    !expr.free_symbols().contains(var)
+}
+
+fn function(name: &str, arg: SimpleExpr) -> SimpleExpr {
+    SimpleExpr::Function(name.into(), vec![arg])
+}
+
+// evaluates a function call with the exact values known to the simplifier
+fn apply(name: &str, x: SimpleExpr) -> ComputeResult {
+    BasicAlgebraicExpr::from(function(name, x)).simplify()
+}
+
+fn add(a: SimpleExpr, b: SimpleExpr) -> ComputeResult {
+    Sum.simplify(vec![a, b])
+}
+
+fn mul(a: SimpleExpr, b: SimpleExpr) -> ComputeResult {
+    Product.simplify(vec![a, b])
+}
+
+fn pow(base: SimpleExpr, exp: SimpleExpr) -> ComputeResult {
+    BasicAlgebraicExpr::simplify_power(base, exp)
+}
@@ -0,0 +1,214 @@
+// Definite integrals.
+//
+// With an antiderivative `F` the result is `F(b) - F(a)`, evaluated exactly. This is only
+// correct if the integrand has no pole in `[a, b]`, so poles are looked for first. When no
+// antiderivative is found and the bounds are numbers, the integral is approximated with
+// adaptive Simpson quadrature.
+use num::{Signed, ToPrimitive};
+
+use super::{integrate, is_free_of};
+use crate::constant::Constant;
+use crate::limits;
+use crate::numeric::{approximate, rationalize, simpson, to_f64};
+use crate::poly::Polynomial;
+use crate::simplify::{Operation, SimpleExpr, Sum};
+use crate::visit::Node;
+use crate::{BasicAlgebraicExpr, ComputeError, ComputeResult};
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum DefiniteIntegral {
+    Exact(SimpleExpr),
+    // from numeric quadrature
+    Approximate(f64),
+}
+
+impl DefiniteIntegral {
+    // Approximate results are wrapped as `Approximate[value]`. Quadrature only gives finite
+    // values, which have a rational within 12 significant digits of any size.
+    pub fn to_expr(&self) -> SimpleExpr {
+        match self {
+            DefiniteIntegral::Exact(x) => x.clone(),
+            DefiniteIntegral::Approximate(x) => SimpleExpr::Function(
+                "Approximate".into(),
+                vec![SimpleExpr::Const(rationalize(*x).expect("finite"))],
+            ),
+        }
+    }
+}
+
+// Bases of powers with a negative exponent that depend on `var`, with the exponent.
+// The integrand is singular where one of them vanishes. `Tan[u]` and `Sec[u]` divide by
+// `Cos[u]`, `Cot[u]` and `Csc[u]` by `Sin[u]`.
+fn denominators(expr: &SimpleExpr, var: &str) -> Vec<(SimpleExpr, Constant)> {
+    expr.pre_order()
+        .filter_map(|x| match x {
+            SimpleExpr::Pow(p)
+                if let SimpleExpr::Const(n) = &p.1
+                    && n.is_negative() =>
+            {
+                Some((p.0.clone(), n.clone()))
+            }
+            SimpleExpr::Function(name, args) if let [u] = args.as_slice() => {
+                let divisor = match name.as_str() {
+                    "Tan" | "Sec" => "Cos",
+                    "Cot" | "Csc" => "Sin",
+                    _ => return None,
+                };
+                Some((
+                    SimpleExpr::Function(divisor.into(), vec![u.clone()]),
+                    (-1).into(),
+                ))
+            }
+            _ => None,
+        })
+        .filter(|(base, _)| !is_free_of(base, var))
+        .collect()
+}
+
+// arguments of logarithms that depend on `var`
+fn logarithms<'a>(expr: &'a SimpleExpr, var: &str) -> Vec<&'a SimpleExpr> {
+    expr.pre_order()
+        .filter_map(|x| match x {
+            SimpleExpr::Function(name, args) if name == "Log" && let [u] = args.as_slice() => {
+                Some(u)
+            }
+            _ => None,
+        })
+        .filter(|u| !is_free_of(u, var))
+        .collect()
+}
+
+fn as_constant(x: &SimpleExpr) -> ComputeResult<Option<Constant>> {
+    Ok(match x {
+        SimpleExpr::Const(c) => Some(c.clone()),
+        x => to_f64(x)?.and_then(rationalize),
+    })
+}
+
+// `n + 1` evenly spaced points of `[a, b]`
+fn samples(a: f64, b: f64, n: usize) -> impl Iterator<Item = f64> {
+    (0..=n).map(move |i| a + (b - a) * i as f64 / n as f64)
+}
+
+const SAMPLES: usize = 1000;
+
+// whether `expr` has a non-integrable singularity in `[a, b]`, or is not real there
+fn has_pole(expr: &SimpleExpr, var: &str, a: &Constant, b: &Constant) -> ComputeResult<bool> {
+    let minus_one = Constant::from(-1);
+    // each sample counts as a step
+    let at = |f: &SimpleExpr, x: f64| {
+        limits::step(|| expr.clone())?;
+        approximate(f, &|s| (s == var).then_some(x))
+    };
+    for (base, exponent) in denominators(expr, var) {
+        if let Some(p) = Polynomial::from_expr(&base.clone().expand()?, var) {
+            // `p^e` with `-1 < e < 0` is integrable at a simple root
+            let is_multiple = p.gcd(&p.derivative()).degree().unwrap_or(0) > 0;
+            if (exponent <= minus_one || is_multiple) && p.count_roots(a, b) > 0 {
+                return Ok(true);
+            }
+            continue;
+        }
+        // look for a sign change
+        let (Some(a), Some(b)) = (a.to_f64(), b.to_f64()) else {
+            return Ok(true);
+        };
+        let mut previous = None;
+        for x in samples(a, b, SAMPLES) {
+            let Some(y) = at(&base, x)? else {
+                return Ok(true);
+            };
+            if y == 0.0 || previous.is_some_and(|p: f64| p.signum() != y.signum()) {
+                return Ok(true);
+            }
+            previous = Some(y);
+        }
+    }
+    // `Log[u]` is integrable where `u` vanishes, but not real where it is negative
+    for u in logarithms(expr, var) {
+        let (Some(a), Some(b)) = (a.to_f64(), b.to_f64()) else {
+            return Ok(true);
+        };
+        for x in samples(a, b, SAMPLES) {
+            if !at(u, x)?.is_some_and(|y| y >= 0.0) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+// `Log[u]` as `Log[-u]` where `negative(u)`. Both differ by a constant, which cancels in
+// `F(b) - F(a)` when `u` is negative at both bounds.
+fn real_logs(
+    expr: SimpleExpr,
+    negative: &dyn Fn(&SimpleExpr) -> ComputeResult<bool>,
+) -> ComputeResult {
+    if let SimpleExpr::Function(name, args) = &expr
+        && name == "Log"
+        && let [u] = args.as_slice()
+        && negative(u)?
+    {
+        return Ok(SimpleExpr::Function(name.clone(), vec![u.clone().negate()?]));
+    }
+    expr.try_map_children(|x| real_logs(x, negative))
+}
+
+// `F(b) - F(a)`
+fn evaluate_bounds(
+    antiderivative: &SimpleExpr,
+    var: &str,
+    lower: &SimpleExpr,
+    upper: &SimpleExpr,
+) -> ComputeResult {
+    // without a pole in between, the logarithms of rational integrands keep their sign
+    let is_negative = |u: &SimpleExpr, x: &SimpleExpr| -> ComputeResult<bool> {
+        Ok(to_f64(&u.substitute(var, x)?)?.is_some_and(|x| x < 0.0))
+    };
+    let antiderivative = real_logs(antiderivative.clone(), &|u| {
+        Ok(is_negative(u, lower)? && is_negative(u, upper)?)
+    })?;
+    let at =
+        |x: &SimpleExpr| BasicAlgebraicExpr::from(antiderivative.substitute(var, x)?).simplify();
+    Sum.simplify(vec![at(upper)?, at(lower)?.negate()?])
+}
+
+// The integral of `expr` over `var` from `lower` to `upper`. Returns a `DomainError` for a pole
+// in the interval and `None` when the integral can be neither found nor approximated.
+pub fn integrate_definite(
+    expr: &SimpleExpr,
+    var: &str,
+    lower: &SimpleExpr,
+    upper: &SimpleExpr,
+) -> ComputeResult<Option<DefiniteIntegral>> {
+    if lower == upper {
+        return Ok(Some(DefiniteIntegral::Exact(0.into())));
+    }
+    let antiderivative = integrate(expr, var)?;
+    let is_closed = !antiderivative
+        .pre_order()
+        .any(|x| matches!(x, SimpleExpr::Function(name, _) if name == "Integrate"));
+
+    let (Some(a), Some(b)) = (as_constant(lower)?, as_constant(upper)?) else {
+        // poles cannot be ruled out with symbolic bounds
+        if is_closed && denominators(expr, var).is_empty() {
+            let value = evaluate_bounds(&antiderivative, var, lower, upper)?;
+            return Ok(Some(DefiniteIntegral::Exact(value)));
+        }
+        return Ok(None);
+    };
+    let (start, end) = if a <= b { (&a, &b) } else { (&b, &a) };
+    if has_pole(expr, var, start, end)? {
+        return Err(ComputeError::domain("Integrate", expr.clone()));
+    }
+    if is_closed {
+        let value = evaluate_bounds(&antiderivative, var, lower, upper)?;
+        return Ok(Some(DefiniteIntegral::Exact(value)));
+    }
+
+    let f = |x: f64| approximate(expr, &|s| (s == var).then_some(x));
+    let (Some(a), Some(b)) = (to_f64(lower)?, to_f64(upper)?) else {
+        return Ok(None);
+    };
+    Ok(simpson(&f, a, b, expr)?.map(DefiniteIntegral::Approximate))
+}
@@ -0,0 +1,100 @@
+use super::{add, function, is_free_of, mul, pow};
+use crate::constant::Constant;
+use crate::simplify::{Operation, Product, SimpleExpr, Sum};
+use crate::ComputeResult;
+
+// d/du f(u) for the elementary functions
+fn outer_derivative(name: &str, u: &SimpleExpr) -> ComputeResult<Option<SimpleExpr>> {
+    let u = u.clone();
+    // 1 - u^2
+    let inverse_root = || {
+        let one_minus_square = add(1.into(), pow(u.clone(), 2.into())?.negate()?)?;
+        pow(
+            one_minus_square,
+            SimpleExpr::Const(Constant::from(-1) / Constant::from(2)),
+        )
+    };
+    Ok(Some(match name {
+        "Sin" => function("Cos", u),
+        "Cos" => function("Sin", u).negate()?,
+        "Tan" => pow(function("Cos", u), (-2).into())?,
+        "Exp" => function("Exp", u),
+        "Log" => pow(u, (-1).into())?,
+        "Sinh" => function("Cosh", u),
+        "Cosh" => function("Sinh", u),
+        "ArcTan" => pow(add(1.into(), pow(u, 2.into())?)?, (-1).into())?,
+        "ArcSin" => inverse_root()?,
+        "ArcCos" => inverse_root()?.negate()?,
+        _ => return Ok(None),
+    }))
+}
+
+// The derivative of `expr` with respect to `var`. Derivatives of unknown functions are
+// kept as an inert `D[f, x]`.
+pub fn derivative(expr: &SimpleExpr, var: &str) -> ComputeResult {
+    let inert = || {
+        SimpleExpr::Function(
+            "D".into(),
+            vec![expr.clone(), SimpleExpr::Symbol(var.into())],
+        )
+    };
+    if is_free_of(expr, var) {
+        return Ok(0.into());
+    }
+    match expr {
+        SimpleExpr::Symbol(_) => Ok(1.into()),
+        SimpleExpr::Sum(terms) => Sum.simplify(
+            terms
+                .iter()
+                .map(|x| derivative(x, var))
+                .collect::<ComputeResult<_>>()?,
+        ),
+        // the product rule, one term per factor that depends on `var`
+        SimpleExpr::Product(factors) => {
+            let mut terms = Vec::new();
+            for (i, factor) in factors.iter().enumerate() {
+                if is_free_of(factor, var) {
+                    continue;
+                }
+                let mut product = factors.clone();
+                product[i] = derivative(factor, var)?;
+                terms.push(Product.simplify(product)?);
+            }
+            Sum.simplify(terms)
+        }
+        SimpleExpr::Pow(x) => {
+            let (u, v) = (&x.0, &x.1);
+            let du = derivative(u, var)?;
+            if is_free_of(v, var) {
+                // v u^(v - 1) u'
+                let power = pow(u.clone(), add(v.clone(), (-1).into())?)?;
+                Product.simplify(vec![v.clone(), power, du])
+            } else {
+                // u^v (v' Log[u] + v u' / u)
+                let dv = derivative(v, var)?;
+                let log = mul(dv, function("Log", u.clone()))?;
+                let quotient =
+                    Product.simplify(vec![v.clone(), du, pow(u.clone(), (-1).into())?])?;
+                mul(expr.clone(), add(log, quotient)?)
+            }
+        }
+        SimpleExpr::Function(name, args) if let [u] = args.as_slice() => {
+            match outer_derivative(name, u)? {
+                // the chain rule
+                Some(outer) => mul(outer, derivative(u, var)?),
+                None => Ok(inert()),
+            }
+        }
+        SimpleExpr::List(x) => Ok(SimpleExpr::List(
+            x.iter()
+                .map(|x| derivative(x, var))
+                .collect::<ComputeResult<_>>()?,
+        )),
+        SimpleExpr::Relation(op, x) => Ok(SimpleExpr::relation(
+            *op,
+            derivative(&x.0, var)?,
+            derivative(&x.1, var)?,
+        )),
+        _ => Ok(inert()),
+    }
+}
@@ -0,0 +1,367 @@
+// Symbolic indefinite integration.
+//
+// Integrals are found by linearity, a table of elementary antiderivatives with linear
+// arguments, rational function integration (Hermite reduction followed by the logarithmic
+// part) and integration by parts of a polynomial times an exponential or trigonometric
+// function. Everything else stays as an inert `Integrate[f, x]`.
+use num::{Signed, Zero};
+
+use super::{add, derivative, function, is_free_of, mul, pow};
+use crate::constant::Constant;
+use crate::poly::{coefficients, rational_function, Polynomial};
+use crate::simplify::{Operation, Product, SimpleExpr, Sum};
+use crate::ComputeResult;
+
+fn inert(expr: SimpleExpr, var: &str) -> SimpleExpr {
+    SimpleExpr::Function(
+        "Integrate".into(),
+        vec![expr, SimpleExpr::Symbol(var.into())],
+    )
+}
+
+fn divide(a: SimpleExpr, b: SimpleExpr) -> ComputeResult {
+    mul(a, pow(b, (-1).into())?)
+}
+
+// The antiderivative of `expr` with respect to `var`, without constant of integration.
+// Terms of a sum that cannot be integrated are collected into an inert `Integrate`.
+pub fn integrate(expr: &SimpleExpr, var: &str) -> ComputeResult {
+    match expr {
+        SimpleExpr::List(x) => Ok(SimpleExpr::List(
+            x.iter()
+                .map(|x| integrate(x, var))
+                .collect::<ComputeResult<_>>()?,
+        )),
+        SimpleExpr::Sum(terms) => {
+            let mut integrals = Vec::new();
+            let mut rest = Vec::new();
+            for term in terms {
+                match try_integrate(term, var)? {
+                    Some(x) => integrals.push(x),
+                    None => rest.push(term.clone()),
+                }
+            }
+            if !rest.is_empty() {
+                let rest = Sum.simplify(rest)?;
+                integrals.push(match try_integrate(&rest, var)? {
+                    Some(x) => x,
+                    None => inert(rest, var),
+                });
+            }
+            Sum.simplify(integrals)
+        }
+        _ => Ok(try_integrate(expr, var)?.unwrap_or_else(|| inert(expr.clone(), var))),
+    }
+}
+
+fn try_integrate(expr: &SimpleExpr, var: &str) -> ComputeResult<Option<SimpleExpr>> {
+    if is_free_of(expr, var) {
+        return Ok(Some(mul(expr.clone(), SimpleExpr::Symbol(var.into()))?));
+    }
+    match expr {
+        SimpleExpr::Product(factors) if factors.iter().any(|x| is_free_of(x, var)) => {
+            let (constant, mut dependent): (Vec<_>, Vec<_>) =
+                factors.iter().cloned().partition(|x| is_free_of(x, var));
+            let dependent = match dependent.len() {
+                1 => dependent.pop().unwrap(),
+                _ => SimpleExpr::Product(dependent),
+            };
+            let Some(integral) = try_integrate(&dependent, var)? else {
+                return Ok(None);
+            };
+            let mut product = constant;
+            product.push(integral);
+            return Ok(Some(Product.simplify(product)?));
+        }
+        SimpleExpr::Sum(terms) => {
+            let Some(integrals) = terms
+                .iter()
+                .map(|x| try_integrate(x, var))
+                .collect::<ComputeResult<Option<Vec<_>>>>()?
+            else {
+                return Ok(None);
+            };
+            return Ok(Some(Sum.simplify(integrals)?));
+        }
+        _ => {}
+    }
+
+    if let Some(x) = elementary(expr, var)? {
+        return Ok(Some(x));
+    }
+    if let Some(x) = polynomial(expr, var)? {
+        return Ok(Some(x));
+    }
+    if let Some(x) = rational(expr, var)? {
+        return Ok(Some(x));
+    }
+    if let Some(x) = by_parts(expr, var)? {
+        return Ok(Some(x));
+    }
+    let expanded = expr.clone().expand()?;
+    if expanded != *expr {
+        return try_integrate(&expanded, var);
+    }
+    Ok(None)
+}
+
+// the slope `a` if `u = a x + b`
+fn linear(u: &SimpleExpr, var: &str) -> ComputeResult<Option<SimpleExpr>> {
+    Ok(match coefficients(&u.clone().expand()?, var)? {
+        Some(mut c) if c.len() == 2 => c.pop(),
+        _ => None,
+    })
+}
+
+// `f(u)` for an elementary function `f` and a linear `u`
+fn elementary(expr: &SimpleExpr, var: &str) -> ComputeResult<Option<SimpleExpr>> {
+    let x = SimpleExpr::Symbol(var.into());
+    let (integral, slope) = match expr {
+        SimpleExpr::Symbol(_) => return Ok(Some(divide(pow(x, 2.into())?, 2.into())?)),
+        SimpleExpr::Pow(p) if is_free_of(&p.1, var) => {
+            let Some(a) = linear(&p.0, var)? else {
+                return Ok(None);
+            };
+            if p.1 == -1 {
+                (function("Log", p.0.clone()), a)
+            } else {
+                let n = add(p.1.clone(), 1.into())?;
+                (divide(pow(p.0.clone(), n.clone())?, n)?, a)
+            }
+        }
+        SimpleExpr::Pow(p) if is_free_of(&p.0, var) => {
+            let Some(a) = linear(&p.1, var)? else {
+                return Ok(None);
+            };
+            (expr.clone(), mul(a, function("Log", p.0.clone()))?)
+        }
+        SimpleExpr::Function(name, args) if let [u] = args.as_slice() => {
+            let Some(a) = linear(u, var)? else {
+                return Ok(None);
+            };
+            let integral = match name.as_str() {
+                "Exp" => function("Exp", u.clone()),
+                "Sin" => function("Cos", u.clone()).negate()?,
+                "Cos" => function("Sin", u.clone()),
+                "Tan" => function("Log", function("Cos", u.clone())).negate()?,
+                "Sinh" => function("Cosh", u.clone()),
+                "Cosh" => function("Sinh", u.clone()),
+                // u Log[u] - u, shifted by a constant
+                "Log" => add(
+                    mul(u.clone(), function("Log", u.clone()))?,
+                    mul(a.clone(), x)?.negate()?,
+                )?,
+                _ => return Ok(None),
+            };
+            (integral, a)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(divide(integral, slope)?))
+}
+
+// a polynomial in `var`, possibly with symbolic coefficients
+fn polynomial(expr: &SimpleExpr, var: &str) -> ComputeResult<Option<SimpleExpr>> {
+    let Some(coefficients) = coefficients(&expr.clone().expand()?, var)? else {
+        return Ok(None);
+    };
+    let x = SimpleExpr::Symbol(var.into());
+    let terms = coefficients
+        .into_iter()
+        .enumerate()
+        .map(|(k, c)| {
+            let n = SimpleExpr::from(k as i128 + 1);
+            Product.simplify(vec![c, pow(x.clone(), n.clone())?, pow(n, (-1).into())?])
+        })
+        .collect::<ComputeResult<Vec<_>>>()?;
+    Ok(Some(Sum.simplify(terms)?))
+}
+
+// a quotient of polynomials with rational coefficients
+fn rational(expr: &SimpleExpr, var: &str) -> ComputeResult<Option<SimpleExpr>> {
+    let Some((numerator, denominator)) = rational_function(expr, var)? else {
+        return Ok(None);
+    };
+    integrate_rational(&numerator, &denominator, var).map(Some)
+}
+
+fn integrate_rational(a: &Polynomial, d: &Polynomial, var: &str) -> ComputeResult {
+    let (quotient, remainder) = a.div_rem(d);
+    let mut terms = vec![quotient.integral().to_expr(var)?];
+    let (reduced, a, d) = hermite_reduce(remainder, d);
+    for (numerator, denominator) in reduced {
+        terms.push(divide(
+            numerator.to_expr(var)?,
+            factored(&denominator, var)?,
+        )?);
+    }
+    terms.push(logarithmic_part(&a, &d, var)?);
+    Sum.simplify(terms)
+}
+
+// The denominator written as a product of its square free factors.
+fn factored(p: &Polynomial, var: &str) -> ComputeResult {
+    let mut factors = vec![SimpleExpr::Const(p.leading())];
+    for (factor, multiplicity) in p.monic().square_free_decomposition() {
+        factors.push(pow(factor.to_expr(var)?, (multiplicity as i128).into())?);
+    }
+    Product.simplify(factors)
+}
+
+// `s` and `t` with `s a + t b = c` and `deg s < deg b`, `gcd(a, b)` must divide `c`
+fn diophantine(a: &Polynomial, b: &Polynomial, c: &Polynomial) -> (Polynomial, Polynomial) {
+    let (g, s, _) = a.extended_gcd(b);
+    let (s, _) = (s * c.clone()).div_rem(&g);
+    let (_, s) = s.div_rem(b);
+    let (t, _) = (c.clone() - s.clone() * a.clone()).div_rem(b);
+    (s, t)
+}
+
+// Mack's linear version of Hermite reduction. For a proper fraction `a / d` returns the
+// rational part of the integral as fractions, and `a* / d*` with a square free `d*` which
+// is left to integrate.
+fn hermite_reduce(
+    mut a: Polynomial,
+    d: &Polynomial,
+) -> (Vec<(Polynomial, Polynomial)>, Polynomial, Polynomial) {
+    let mut reduced = Vec::new();
+    let mut d_minus = d.gcd(&d.derivative());
+    let (d_star, _) = d.div_rem(&d_minus);
+    while d_minus.degree().unwrap_or(0) > 0 {
+        let d_minus2 = d_minus.gcd(&d_minus.derivative());
+        let (d_minus_star, _) = d_minus.div_rem(&d_minus2);
+        let (coefficient, _) = (d_star.clone() * d_minus.derivative()).div_rem(&d_minus);
+        let (b, c) = diophantine(&-coefficient, &d_minus_star, &a);
+        let (correction, _) = (b.derivative() * d_star.clone()).div_rem(&d_minus_star);
+        a = c - correction;
+        if !b.is_zero() {
+            reduced.push((b, d_minus.clone()));
+        }
+        d_minus = d_minus2;
+    }
+    (reduced, a, d_star)
+}
+
+// the integral of `a / d` for a square free `d`
+fn logarithmic_part(a: &Polynomial, d: &Polynomial, var: &str) -> ComputeResult {
+    let x = SimpleExpr::Symbol(var.into());
+    let (quotient, a) = a.div_rem(d);
+    let mut terms = vec![quotient.integral().to_expr(var)?];
+    if a.is_zero() {
+        return Sum.simplify(terms);
+    }
+
+    // residues at the rational poles
+    let derivative = d.derivative();
+    let mut linear = Polynomial::constant(Constant::from(1));
+    for root in d.rational_roots()? {
+        let residue = a.eval(&root) / derivative.eval(&root);
+        let factor = Polynomial::linear_factor(root);
+        terms.push(mul(
+            SimpleExpr::Const(residue),
+            function("Log", factor.to_expr(var)?),
+        )?);
+        linear = linear * factor;
+    }
+    let (e, _) = d.div_rem(&linear);
+    if e.degree().unwrap_or(0) == 0 {
+        return Sum.simplify(terms);
+    }
+    // the part of `a / d` over `e`: with `s linear + t e = 1`, it is `a s / e`
+    let (_, s, _) = linear.extended_gcd(&e);
+    let (_, b) = (a * s).div_rem(&e);
+
+    if e.degree() != Some(2) {
+        let quotient = divide(b.to_expr(var)?, e.to_expr(var)?)?;
+        terms.push(inert(quotient, var));
+        return Sum.simplify(terms);
+    }
+
+    // (b1 x + b0) / (x^2 + p x + q)
+    let b = b.scale(&(Constant::from(1) / e.leading()));
+    let e = e.monic();
+    let coefficient = |p: &Polynomial, i: usize| {
+        p.coeffs()
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| Constant::from(0))
+    };
+    let (b0, b1) = (coefficient(&b, 0), coefficient(&b, 1));
+    let (q, p) = (coefficient(&e, 0), coefficient(&e, 1));
+    let half = Constant::from(1) / Constant::from(2);
+    terms.push(mul(
+        SimpleExpr::Const(b1.clone() * half.clone()),
+        function("Log", e.to_expr(var)?),
+    )?);
+    // the rest is w / ((x + p/2)^2 + k)
+    let w = b0 - b1 * p.clone() * half.clone();
+    let k = q - p.clone() * p.clone() * half.clone() * half.clone();
+    if w.is_zero() {
+        return Sum.simplify(terms);
+    }
+    let shifted = add(x, SimpleExpr::Const(p * half.clone()))?;
+    if k.is_positive() {
+        // w / sqrt(k) ArcTan[(x + p/2) / sqrt(k)]
+        let root = pow(SimpleExpr::Const(k), SimpleExpr::Const(-half))?;
+        let arctan = function("ArcTan", mul(shifted, root.clone())?.expand()?);
+        terms.push(Product.simplify(vec![SimpleExpr::Const(w), root, arctan])?);
+    } else {
+        // w / (2m) (Log[x + p/2 - m] - Log[x + p/2 + m]) with m = sqrt(-k)
+        let m = pow(SimpleExpr::Const(-k), SimpleExpr::Const(half.clone()))?;
+        let lower = function("Log", add(shifted.clone(), m.clone().negate()?)?);
+        let upper = function("Log", add(shifted, m.clone())?);
+        terms.push(
+            Product
+                .simplify(vec![
+                    SimpleExpr::Const(w * half),
+                    pow(m, (-1).into())?,
+                    add(lower, upper.negate()?)?,
+                ])?
+                .expand()?,
+        );
+    }
+    Sum.simplify(terms)
+}
+
+// `p f` for a polynomial `p` and a function `f` whose repeated integrals are known, by
+// tabular integration by parts: `p F1 - p' F2 + p'' F3 - ...`
+fn by_parts(expr: &SimpleExpr, var: &str) -> ComputeResult<Option<SimpleExpr>> {
+    let SimpleExpr::Product(factors) = expr else {
+        return Ok(None);
+    };
+    let is_integrable = |x: &SimpleExpr| match x {
+        SimpleExpr::Function(name, args) => {
+            matches!(name.as_str(), "Exp" | "Sin" | "Cos" | "Sinh" | "Cosh") && args.len() == 1
+        }
+        SimpleExpr::Pow(p) => is_free_of(&p.0, var),
+        _ => false,
+    };
+    let Some(i) = factors.iter().position(is_integrable) else {
+        return Ok(None);
+    };
+    let mut rest = factors.clone();
+    let f = rest.remove(i);
+    let mut p = match rest.len() {
+        1 => rest.pop().unwrap(),
+        _ => SimpleExpr::Product(rest),
+    };
+    let Some(degree) = coefficients(&p.clone().expand()?, var)?.map(|c| c.len()) else {
+        return Ok(None);
+    };
+
+    let mut terms = Vec::new();
+    let mut integral = f;
+    for k in 0..degree {
+        let Some(next) = try_integrate(&integral, var)? else {
+            return Ok(None);
+        };
+        integral = next;
+        let term = mul(p.clone(), integral.clone())?;
+        terms.push(match k % 2 {
+            0 => term,
+            _ => term.negate()?,
+        });
+        p = derivative(&p, var)?;
+    }
+    Ok(Some(Sum.simplify(terms)?.expand()?))
+}
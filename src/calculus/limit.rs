@@ -0,0 +1,449 @@
+// Limits.
+//
+// Every limit is turned into one where the variable goes to zero from above or to infinity,
+// by substituting `a + x`, `a - x` or `-x` for it. Rational functions are then decided by
+// their lowest or highest order terms. Other expressions are taken apart, and `0/0` and
+// `∞/∞` quotients are resolved with L'Hôpital's rule.
+use num::{Signed, Zero};
+
+use super::{apply, derivative, function, is_free_of, mul, pow};
+use crate::constant::Constant;
+use crate::numeric::{approximate, to_f64};
+use crate::poly::{together, Polynomial};
+use crate::simplify::{Operation, Product, SimpleExpr, Sum};
+use crate::ComputeResult;
+
+// how often L'Hôpital's rule or combining a sum may be applied
+const MAX_DEPTH: usize = 8;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    TwoSided,
+    // from larger values
+    FromAbove,
+    FromBelow,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Value {
+    Finite(SimpleExpr),
+    // positive or negative infinity
+    Infinite(bool),
+    // oscillates without a limit, such as `Sin[x]` at infinity
+    Bounded,
+}
+
+impl Value {
+    fn to_expr(&self) -> SimpleExpr {
+        match self {
+            Value::Finite(x) => x.clone(),
+            Value::Infinite(true) => SimpleExpr::infinity(),
+            Value::Infinite(false) => SimpleExpr::negative_infinity(),
+            Value::Bounded => SimpleExpr::indeterminate(),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        matches!(self, Value::Finite(x) if *x == 0)
+    }
+}
+
+// the sign of a nonzero constant, if it is a number
+fn sign(x: &SimpleExpr) -> ComputeResult<Option<bool>> {
+    Ok(to_f64(x)?.filter(|x| *x != 0.0).map(|x| x > 0.0))
+}
+
+// The degree and coefficient of the term of `p` that dominates near zero, or at infinity.
+// `None` for the zero polynomial.
+fn dominant(p: &Polynomial, at_infinity: bool) -> Option<(usize, Constant)> {
+    let mut terms = p.coeffs().iter().enumerate().filter(|(_, c)| !c.is_zero());
+    let (degree, c) = if at_infinity {
+        terms.next_back()?
+    } else {
+        terms.next()?
+    };
+    Some((degree, c.clone()))
+}
+
+// splits off the factors with a negative exponent as the denominator
+fn split_fraction(expr: &SimpleExpr) -> ComputeResult<(SimpleExpr, SimpleExpr)> {
+    let factors = match expr {
+        SimpleExpr::Product(factors) => factors.as_slice(),
+        x => std::slice::from_ref(x),
+    };
+    let mut numerator = Vec::new();
+    let mut denominator = Vec::new();
+    for factor in factors {
+        match factor {
+            SimpleExpr::Pow(p)
+                if let SimpleExpr::Const(n) = &p.1
+                    && n.is_negative() =>
+            {
+                denominator.push(pow(p.0.clone(), SimpleExpr::Const(-n.clone()))?);
+            }
+            x => numerator.push(x.clone()),
+        }
+    }
+    let product = |x: Vec<SimpleExpr>| match x.len() {
+        0 => Ok(1.into()),
+        _ => Product.simplify(x),
+    };
+    Ok((product(numerator)?, product(denominator)?))
+}
+
+// Where the variable goes: to zero from above, or to infinity.
+#[derive(Clone, Copy)]
+struct Approach<'a> {
+    var: &'a str,
+    at_infinity: bool,
+}
+
+impl Approach<'_> {
+    // whether `f` is positive as the variable approaches
+    fn sign_near(&self, f: &SimpleExpr) -> ComputeResult<Option<bool>> {
+        if let Some((numerator, denominator)) = together(f, self.var) {
+            let (Some((_, n)), Some((_, d))) = (
+                dominant(&numerator, self.at_infinity),
+                dominant(&denominator, self.at_infinity),
+            ) else {
+                return Ok(None);
+            };
+            return Ok(Some(n.is_positive() == d.is_positive()));
+        }
+        let samples = if self.at_infinity {
+            [20.0, 200.0]
+        } else {
+            [1e-7, 1e-10]
+        };
+        let at = |x: f64| {
+            approximate(f, &|s| (s == self.var).then_some(x)).map(|y| y.filter(|y| *y != 0.0))
+        };
+        let (Some(a), Some(b)) = (at(samples[0])?, at(samples[1])?) else {
+            return Ok(None);
+        };
+        Ok((a.signum() == b.signum()).then_some(a > 0.0))
+    }
+
+    // the limit of `f`, `None` if it cannot be decided
+    fn limit(&self, f: &SimpleExpr, depth: usize) -> ComputeResult<Option<Value>> {
+        if is_free_of(f, self.var) {
+            return Ok(Some(Value::Finite(f.clone())));
+        }
+        if depth > MAX_DEPTH {
+            return Ok(None);
+        }
+        if let Some((numerator, denominator)) = together(f, self.var) {
+            let Some((m, n)) = dominant(&numerator, self.at_infinity) else {
+                return Ok(Some(Value::Finite(0.into())));
+            };
+            let (k, d) = dominant(&denominator, self.at_infinity).expect("nonzero denominator");
+            // the quotient behaves like `n/d x^(m - k)`
+            let growing = if self.at_infinity { m > k } else { m < k };
+            return Ok(Some(if m == k {
+                Value::Finite(SimpleExpr::Const(n / d))
+            } else if growing {
+                Value::Infinite(n.is_positive() == d.is_positive())
+            } else {
+                Value::Finite(0.into())
+            }));
+        }
+        match f {
+            SimpleExpr::Sum(terms) => self.sum(terms, depth),
+            SimpleExpr::Product(factors) => {
+                let (numerator, denominator) = split_fraction(f)?;
+                if denominator == 1 {
+                    self.product(factors, depth)
+                } else {
+                    self.quotient(&numerator, &denominator, depth)
+                }
+            }
+            SimpleExpr::Pow(p) => self.power(&p.0, &p.1, depth),
+            SimpleExpr::Function(name, args) if let [u] = args.as_slice() => {
+                self.function(name, u, depth)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn sum(&self, terms: &[SimpleExpr], depth: usize) -> ComputeResult<Option<Value>> {
+        let mut finite = Vec::new();
+        let (mut positive, mut negative, mut bounded) = (false, false, false);
+        for term in terms {
+            match self.limit(term, depth)? {
+                Some(Value::Finite(x)) => finite.push(x),
+                Some(Value::Infinite(true)) => positive = true,
+                Some(Value::Infinite(false)) => negative = true,
+                Some(Value::Bounded) => bounded = true,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(match (positive, negative) {
+            // ∞ - ∞, try again over a common denominator
+            (true, true) => {
+                let fractions = terms
+                    .iter()
+                    .map(split_fraction)
+                    .collect::<ComputeResult<Vec<_>>>()?;
+                let mut numerator = Vec::new();
+                for (i, (n, _)) in fractions.iter().enumerate() {
+                    let mut product = vec![n.clone()];
+                    product.extend(
+                        fractions
+                            .iter()
+                            .enumerate()
+                            .filter(|(j, _)| *j != i)
+                            .map(|(_, (_, d))| d.clone()),
+                    );
+                    numerator.push(Product.simplify(product)?);
+                }
+                let numerator = Sum.simplify(numerator)?.expand()?;
+                let denominator =
+                    Product.simplify(fractions.into_iter().map(|(_, d)| d).collect())?;
+                return self.quotient(&numerator, &denominator, depth + 1);
+            }
+            (true, false) => Value::Infinite(true),
+            (false, true) => Value::Infinite(false),
+            _ if bounded => Value::Bounded,
+            _ => Value::Finite(Sum.simplify(finite)?),
+        }))
+    }
+
+    fn product(&self, factors: &[SimpleExpr], depth: usize) -> ComputeResult<Option<Value>> {
+        let mut finite = Vec::new();
+        let mut infinite = Vec::new();
+        let mut positive = true;
+        let mut bounded = false;
+        for factor in factors {
+            match self.limit(factor, depth)? {
+                Some(Value::Finite(x)) => finite.push(x),
+                Some(Value::Infinite(sign)) => {
+                    positive = positive == sign;
+                    infinite.push(factor.clone());
+                }
+                Some(Value::Bounded) => bounded = true,
+                None => return Ok(None),
+            }
+        }
+        let value = match finite.len() {
+            0 => 1.into(),
+            _ => Product.simplify(finite)?,
+        };
+        Ok(match (value == 0, infinite.is_empty(), bounded) {
+            (true, true, _) => Some(Value::Finite(0.into())),
+            (false, true, false) => Some(Value::Finite(value)),
+            (false, true, true) => Some(Value::Bounded),
+            // 0 ∞, written as ∞ / (1 / 0) or else as 0 / (1 / ∞)
+            (true, false, false) => {
+                let rest: Vec<_> = factors
+                    .iter()
+                    .filter(|x| !infinite.contains(x))
+                    .cloned()
+                    .collect();
+                let (infinite, rest) = (Product.simplify(infinite)?, Product.simplify(rest)?);
+                // `1/Exp[u]` as `Exp[-u]`, which L'Hôpital's rule does not make worse
+                let reciprocal = |x: &SimpleExpr| match x {
+                    SimpleExpr::Function(name, args)
+                        if name == "Exp"
+                            && let [u] = args.as_slice() =>
+                    {
+                        apply("Exp", u.clone().negate()?)
+                    }
+                    x => pow(x.clone(), (-1).into()),
+                };
+                match self.quotient(&infinite, &reciprocal(&rest)?, depth + 1)? {
+                    Some(x) => Some(x),
+                    None => self.quotient(&rest, &reciprocal(&infinite)?, depth + 1)?,
+                }
+            }
+            (false, false, false) => sign(&value)?.map(|sign| Value::Infinite(positive == sign)),
+            _ => None,
+        })
+    }
+
+    fn quotient(
+        &self,
+        numerator: &SimpleExpr,
+        denominator: &SimpleExpr,
+        depth: usize,
+    ) -> ComputeResult<Option<Value>> {
+        let (Some(n), Some(d)) = (
+            self.limit(numerator, depth)?,
+            self.limit(denominator, depth)?,
+        ) else {
+            return Ok(None);
+        };
+        // a zero the simplifier does not know about, such as `Cos[Pi/2]`
+        if let Value::Finite(d) = &d
+            && *d != 0
+            && to_f64(d)?.is_some_and(|x| x.abs() < 1e-12)
+        {
+            return Ok(None);
+        }
+        Ok(match (&n, &d) {
+            // L'Hôpital's rule
+            _ if n.is_zero() && d.is_zero()
+                || matches!((&n, &d), (Value::Infinite(_), Value::Infinite(_))) =>
+            {
+                let numerator = derivative(numerator, self.var)?;
+                let denominator = derivative(denominator, self.var)?;
+                let quotient = mul(numerator, pow(denominator, (-1).into())?)?;
+                return self.limit(&quotient, depth + 1);
+            }
+            (Value::Finite(_) | Value::Bounded, Value::Infinite(_)) => {
+                Some(Value::Finite(0.into()))
+            }
+            (Value::Finite(a), Value::Finite(b)) if !d.is_zero() => {
+                Some(Value::Finite(mul(a.clone(), pow(b.clone(), (-1).into())?)?))
+            }
+            (Value::Finite(a), _) if d.is_zero() => sign(a)?
+                .zip(self.sign_near(denominator)?)
+                .map(|(a, b)| Value::Infinite(a == b)),
+            (Value::Infinite(a), Value::Finite(b)) if !d.is_zero() => {
+                sign(b)?.map(|b| Value::Infinite(*a == b))
+            }
+            (Value::Infinite(a), _) if d.is_zero() => self
+                .sign_near(denominator)?
+                .map(|b| Value::Infinite(*a == b)),
+            (Value::Bounded, Value::Finite(_)) if !d.is_zero() => Some(Value::Bounded),
+            _ => None,
+        })
+    }
+
+    fn power(
+        &self,
+        base: &SimpleExpr,
+        exponent: &SimpleExpr,
+        depth: usize,
+    ) -> ComputeResult<Option<Value>> {
+        // b^e = Exp[e Log[b]]
+        if !is_free_of(exponent, self.var) {
+            let log = mul(exponent.clone(), function("Log", base.clone()))?;
+            return Ok(match self.limit(&log, depth)? {
+                Some(Value::Finite(x)) => Some(Value::Finite(apply("Exp", x)?)),
+                Some(Value::Infinite(true)) => Some(Value::Infinite(true)),
+                Some(Value::Infinite(false)) => Some(Value::Finite(0.into())),
+                _ => None,
+            });
+        }
+        let Some(value) = self.limit(base, depth)? else {
+            return Ok(None);
+        };
+        if let Value::Finite(x) = &value
+            && !value.is_zero()
+        {
+            return Ok(Some(Value::Finite(pow(x.clone(), exponent.clone())?)));
+        }
+        let SimpleExpr::Const(n) = exponent else {
+            return Ok(None);
+        };
+        // the sign of `b^n` for a negative `b`
+        let odd = n.as_integer().map(|n| n % 2 != num::BigInt::zero());
+        Ok(match value {
+            _ if n.is_zero() => Some(Value::Finite(1.into())),
+            Value::Finite(_) if n.is_positive() => Some(Value::Finite(0.into())),
+            Value::Finite(_) => match (self.sign_near(base)?, odd) {
+                (Some(true), _) => Some(Value::Infinite(true)),
+                (Some(false), Some(odd)) => Some(Value::Infinite(!odd)),
+                _ => None,
+            },
+            Value::Infinite(_) if n.is_negative() => Some(Value::Finite(0.into())),
+            Value::Infinite(true) => Some(Value::Infinite(true)),
+            Value::Infinite(false) => odd.map(|odd| Value::Infinite(!odd)),
+            Value::Bounded if n.is_positive() => Some(Value::Bounded),
+            Value::Bounded => None,
+        })
+    }
+
+    fn function(&self, name: &str, u: &SimpleExpr, depth: usize) -> ComputeResult<Option<Value>> {
+        if name == "Tan" {
+            let quotient = mul(
+                function("Sin", u.clone()),
+                pow(function("Cos", u.clone()), (-1).into())?,
+            )?;
+            return self.limit(&quotient, depth);
+        }
+        let Some(value) = self.limit(u, depth)? else {
+            return Ok(None);
+        };
+        let pi_over_2 = || {
+            mul(
+                SimpleExpr::Const(Constant::from(1) / 2.into()),
+                SimpleExpr::Symbol("Pi".into()),
+            )
+        };
+        Ok(match (name, value) {
+            ("Log", Value::Finite(x)) if x == 0 => self
+                .sign_near(u)?
+                .filter(|x| *x)
+                .map(|_| Value::Infinite(false)),
+            ("Log", Value::Finite(x)) if to_f64(&x)?.is_some_and(|x| x < 0.0) => None,
+            ("ArcSin" | "ArcCos", Value::Finite(x))
+                if !to_f64(&x)?.is_some_and(|x| x.abs() <= 1.0) =>
+            {
+                None
+            }
+            (
+                "Exp" | "Log" | "Sin" | "Cos" | "Sinh" | "Cosh" | "Tanh" | "ArcTan" | "ArcSin"
+                | "ArcCos",
+                Value::Finite(x),
+            ) => Some(Value::Finite(apply(name, x)?)),
+            ("Exp", Value::Infinite(true)) => Some(Value::Infinite(true)),
+            ("Exp", Value::Infinite(false)) => Some(Value::Finite(0.into())),
+            ("Log", Value::Infinite(true)) => Some(Value::Infinite(true)),
+            ("Sinh", Value::Infinite(sign)) => Some(Value::Infinite(sign)),
+            ("Cosh", Value::Infinite(_)) => Some(Value::Infinite(true)),
+            ("ArcTan", Value::Infinite(true)) => Some(Value::Finite(pi_over_2()?)),
+            ("ArcTan", Value::Infinite(false)) => Some(Value::Finite(pi_over_2()?.negate()?)),
+            ("Tanh", Value::Infinite(sign)) => {
+                Some(Value::Finite(if sign { 1.into() } else { (-1).into() }))
+            }
+            ("Sin" | "Cos", Value::Infinite(_) | Value::Bounded) => Some(Value::Bounded),
+            ("ArcTan" | "Tanh", Value::Bounded) => Some(Value::Bounded),
+            _ => None,
+        })
+    }
+}
+
+// The limit of `expr` as `var` approaches `point`, which may be `Infinity` or `-Infinity`.
+// A two-sided limit whose sides differ is `Indeterminate`, or `ComplexInfinity` when they
+// are infinities of opposite sign. Returns `None` if the limit cannot be found.
+pub fn limit(
+    expr: &SimpleExpr,
+    var: &str,
+    point: &SimpleExpr,
+    direction: Direction,
+) -> ComputeResult<Option<SimpleExpr>> {
+    let x = SimpleExpr::Symbol(var.into());
+    let one_sided = |substitution: SimpleExpr, at_infinity| -> ComputeResult<Option<Value>> {
+        let approach = Approach { var, at_infinity };
+        approach.limit(&expr.substitute(var, &substitution)?, 0)
+    };
+    let value = if *point == SimpleExpr::infinity() {
+        one_sided(x, true)?
+    } else if *point == SimpleExpr::negative_infinity() {
+        one_sided(x.negate()?, true)?
+    } else if is_free_of(point, var) {
+        let above = || one_sided(Sum.simplify(vec![point.clone(), x.clone()])?, false);
+        let below = || {
+            one_sided(
+                Sum.simplify(vec![point.clone(), x.clone().negate()?])?,
+                false,
+            )
+        };
+        match direction {
+            Direction::FromAbove => above()?,
+            Direction::FromBelow => below()?,
+            Direction::TwoSided => match (above()?, below()?) {
+                (Some(a), Some(b)) if a == b => Some(a),
+                (Some(Value::Infinite(_)), Some(Value::Infinite(_))) => {
+                    return Ok(Some(SimpleExpr::complex_infinity()));
+                }
+                (Some(_), Some(_)) => return Ok(Some(SimpleExpr::indeterminate())),
+                _ => None,
+            },
+        }
+    } else {
+        None
+    };
+    Ok(value.map(|x| x.to_expr()))
+}
@@ -0,0 +1,522 @@
+// Truncated power series.
+//
+// A series is kept as the coefficients of `(x - x0)^k` from some `k = start` on, and the
+// exponent of its order term `O((x - x0)^order)`. Expressions are expanded by building the
+// series of their parts at a working precision. Cancellation can lose terms, as in
+// `Sin[x]/x`, so the precision is raised until the result is known to the requested order.
+use num::ToPrimitive;
+
+use super::{add, apply, function, is_free_of, mul, pow};
+use crate::constant::Constant;
+use crate::limits;
+use crate::simplify::{Operation, SimpleExpr, Sum};
+use crate::{ComputeError, ComputeResult};
+
+// how often the working precision may be raised
+const MAX_RETRIES: usize = 8;
+
+// The order of a series about which nothing is known, such as the reciprocal of a series
+// that is all order term.
+const UNKNOWN: i64 = i64::MIN / 4;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Series {
+    var: String,
+    point: SimpleExpr,
+    // the exponent of the first coefficient, which is nonzero
+    start: i64,
+    coeffs: Vec<SimpleExpr>,
+    order: i64,
+}
+
+fn total(terms: Vec<SimpleExpr>) -> ComputeResult {
+    match terms.len() {
+        0 => Ok(0.into()),
+        _ => Sum.simplify(terms),
+    }
+}
+
+fn div(a: SimpleExpr, b: SimpleExpr) -> ComputeResult {
+    mul(a, pow(b, (-1).into())?)
+}
+
+fn int(n: i64) -> SimpleExpr {
+    SimpleExpr::from(n as i128)
+}
+
+fn ratio(n: i64, d: i64) -> SimpleExpr {
+    SimpleExpr::Const(Constant::from(n as i128) / Constant::from(d as i128))
+}
+
+fn as_integer(x: &SimpleExpr) -> Option<i64> {
+    match x {
+        SimpleExpr::Const(c) => c.as_integer()?.to_i64(),
+        _ => None,
+    }
+}
+
+impl Series {
+    // Drops the coefficients at or beyond the order term, and zeros at either end.
+    pub fn new(
+        var: &str,
+        point: SimpleExpr,
+        start: i64,
+        mut coeffs: Vec<SimpleExpr>,
+        order: i64,
+    ) -> Series {
+        coeffs.truncate((order - start).max(0) as usize);
+        while coeffs.last().is_some_and(|c| *c == 0) {
+            coeffs.pop();
+        }
+        let zeros = coeffs.iter().take_while(|c| **c == 0).count();
+        coeffs.drain(..zeros);
+        let start = match coeffs.len() {
+            0 => order,
+            _ => start + zeros as i64,
+        };
+        Series {
+            var: var.into(),
+            point,
+            start,
+            coeffs,
+            order,
+        }
+    }
+
+    // a series with the same variable and point
+    fn with(&self, start: i64, coeffs: Vec<SimpleExpr>, order: i64) -> Series {
+        Series::new(&self.var, self.point.clone(), start, coeffs, order)
+    }
+
+    fn constant(&self, c: SimpleExpr, order: i64) -> Series {
+        self.with(0, vec![c], order)
+    }
+
+    pub fn var(&self) -> &str {
+        &self.var
+    }
+
+    pub fn point(&self) -> &SimpleExpr {
+        &self.point
+    }
+
+    pub fn order(&self) -> i64 {
+        self.order
+    }
+
+    // the exponent of the first nonzero term, or the order for a series without terms
+    pub fn valuation(&self) -> i64 {
+        self.start
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    // the coefficient of `(x - x0)^k`
+    pub fn coefficient(&self, k: i64) -> SimpleExpr {
+        usize::try_from(k - self.start)
+            .ok()
+            .and_then(|i| self.coeffs.get(i))
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+    }
+
+    fn truncate(&self, order: i64) -> Series {
+        self.with(self.start, self.coeffs.clone(), self.order.min(order))
+    }
+
+    fn shift(&self, k: i64) -> Series {
+        self.with(self.start + k, self.coeffs.clone(), self.order + k)
+    }
+
+    fn map(&self, f: impl Fn(SimpleExpr) -> ComputeResult) -> ComputeResult<Series> {
+        let coeffs = self
+            .coeffs
+            .iter()
+            .cloned()
+            .map(f)
+            .collect::<ComputeResult<_>>()?;
+        Ok(self.with(self.start, coeffs, self.order))
+    }
+
+    // series can only be combined if they are in the same variable about the same point
+    fn check(&self, other: &Series) -> ComputeResult<()> {
+        if self.var != other.var || self.point != other.point {
+            return Err(ComputeError::Unsupported(other.to_series_data()));
+        }
+        Ok(())
+    }
+
+    pub fn add(&self, other: &Series) -> ComputeResult<Series> {
+        self.check(other)?;
+        let start = self.start.min(other.start);
+        let order = self.order.min(other.order);
+        let coeffs = (start..order)
+            .map(|k| add(self.coefficient(k), other.coefficient(k)))
+            .collect::<ComputeResult<_>>()?;
+        Ok(self.with(start, coeffs, order))
+    }
+
+    pub fn negate(&self) -> ComputeResult<Series> {
+        self.map(SimpleExpr::negate)
+    }
+
+    pub fn sub(&self, other: &Series) -> ComputeResult<Series> {
+        self.add(&other.negate()?)
+    }
+
+    pub fn mul(&self, other: &Series) -> ComputeResult<Series> {
+        self.check(other)?;
+        let start = self.start + other.start;
+        let order = (self.order + other.start).min(other.order + self.start);
+        let len = (order - start).max(0) as usize;
+        let mut terms = vec![Vec::new(); len];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            limits::step(|| self.to_series_data())?;
+            for (j, b) in other.coeffs.iter().enumerate().take(len.saturating_sub(i)) {
+                terms[i + j].push(mul(a.clone(), b.clone())?);
+            }
+        }
+        let coeffs = terms.into_iter().map(total).collect::<ComputeResult<_>>()?;
+        Ok(self.with(start, coeffs, order))
+    }
+
+    pub fn reciprocal(&self) -> ComputeResult<Series> {
+        let Some(first) = self.coeffs.first() else {
+            return Ok(self.with(0, vec![], UNKNOWN));
+        };
+        let inverse = pow(first.clone(), (-1).into())?;
+        let negative = inverse.clone().negate()?;
+        // `b_k = -(a_1 b_(k-1) + .. + a_k b_0) / a_0`
+        let len = (self.order - self.start) as usize;
+        let mut coeffs = vec![inverse];
+        for k in 1..len {
+            limits::step(|| self.to_series_data())?;
+            let terms = (1..=k)
+                .map(|j| {
+                    mul(
+                        self.coefficient(self.start + j as i64),
+                        coeffs[k - j].clone(),
+                    )
+                })
+                .collect::<ComputeResult<_>>()?;
+            coeffs.push(mul(negative.clone(), total(terms)?)?);
+        }
+        Ok(self.with(-self.start, coeffs, self.order - 2 * self.start))
+    }
+
+    pub fn div(&self, other: &Series) -> ComputeResult<Series> {
+        self.mul(&other.reciprocal()?)
+    }
+
+    pub fn powi(&self, n: i64) -> ComputeResult<Series> {
+        if n < 0 {
+            return self.reciprocal()?.powi(-n);
+        }
+        if n == 0 {
+            return Ok(self.constant(1.into(), self.order - self.start));
+        }
+        let mut result = self.clone();
+        for _ in 1..n {
+            limits::step(|| self.to_series_data())?;
+            result = result.mul(self)?;
+        }
+        Ok(result)
+    }
+
+    // `self^exponent` for an exponent free of the variable. `None` if the result is not a
+    // power series, as for `Sqrt[x]` at zero.
+    pub fn pow(&self, exponent: &SimpleExpr) -> ComputeResult<Option<Series>> {
+        if let Some(n) = as_integer(exponent) {
+            return self.powi(n).map(Some);
+        }
+        let Some(first) = self.coeffs.first() else {
+            return Ok(Some(self.with(0, vec![], UNKNOWN)));
+        };
+        // self = c (x - x0)^v (1 + u), and (1 + u)^a is a binomial series
+        let shift = mul(int(self.start), exponent.clone())?;
+        let Some(shift) = as_integer(&shift) else {
+            return Ok(None);
+        };
+        let unit = self.map(|c| div(c, first.clone()))?.shift(-self.start);
+        let mut coeffs = vec![SimpleExpr::from(1)];
+        for k in 1..unit.order.max(0) {
+            limits::step(|| self.to_series_data())?;
+            let factor = mul(add(exponent.clone(), int(1 - k))?, ratio(1, k))?;
+            coeffs.push(mul(coeffs[k as usize - 1].clone(), factor)?);
+        }
+        let binomial = Series::new(&self.var, 1.into(), 0, coeffs, unit.order);
+        let c = pow(first.clone(), exponent.clone())?;
+        let result = binomial.compose(&unit)?.map(|x| mul(c.clone(), x))?;
+        Ok(Some(result.shift(shift)))
+    }
+
+    // `self(inner)`, where `inner` takes the value of the point of `self` at its own point
+    pub fn compose(&self, inner: &Series) -> ComputeResult<Series> {
+        let h = inner.sub(&inner.constant(self.point.clone(), inner.order))?;
+        if h.start < 1 {
+            return Err(ComputeError::Unsupported(inner.to_series_data()));
+        }
+        // the order term of `self` becomes `O(h^order)`
+        let order = self.order * h.start;
+        let mut result = inner.with(0, vec![], order);
+        for c in self.coeffs.iter().rev() {
+            limits::step(|| self.to_series_data())?;
+            result = result.mul(&h)?.add(&inner.constant(c.clone(), order))?;
+        }
+        if self.start != 0 {
+            result = result.mul(&h.powi(self.start)?)?;
+        }
+        Ok(result.truncate(order))
+    }
+
+    // The inverse function as a series about the value of `self` at its point, in the same
+    // variable. The first derivative must not vanish.
+    pub fn revert(&self) -> ComputeResult<Series> {
+        let value = self.coefficient(0);
+        let h = self.sub(&self.constant(value.clone(), self.order))?;
+        if h.start != 1 {
+            return Err(ComputeError::domain("InverseSeries", self.to_series_data()));
+        }
+        // find `r` with `h(r(s)) = s` one coefficient at a time
+        let h = Series::new(&self.var, 0.into(), h.start, h.coeffs, h.order);
+        let inverse = pow(h.coefficient(1), (-1).into())?;
+        let mut coeffs = vec![inverse.clone()];
+        for k in 2..h.order {
+            limits::step(|| self.to_series_data())?;
+            let r = Series::new(&self.var, 0.into(), 1, coeffs.clone(), k + 1);
+            let error = h.compose(&r)?.coefficient(k);
+            coeffs.push(mul(error, inverse.clone())?.negate()?);
+        }
+        coeffs.insert(0, self.point.clone());
+        Ok(Series::new(&self.var, value, 0, coeffs, h.order))
+    }
+
+    pub fn derivative(&self) -> ComputeResult<Series> {
+        let coeffs = (self.start..self.order)
+            .map(|k| mul(int(k), self.coefficient(k)))
+            .collect::<ComputeResult<_>>()?;
+        Ok(self.with(self.start - 1, coeffs, self.order - 1))
+    }
+
+    // the antiderivative vanishing at the point, `None` if there is a `1/(x - x0)` term
+    pub fn integral(&self) -> ComputeResult<Option<Series>> {
+        if self.coefficient(-1) != 0 {
+            return Ok(None);
+        }
+        let coeffs = (self.start..self.order)
+            .map(|k| match k {
+                -1 => Ok(0.into()),
+                k => mul(self.coefficient(k), ratio(1, k + 1)),
+            })
+            .collect::<ComputeResult<_>>()?;
+        Ok(Some(self.with(self.start + 1, coeffs, self.order + 1)))
+    }
+
+    // `f(self)` for an elementary function `f`, `None` if it has no series here
+    pub fn apply(&self, name: &str) -> ComputeResult<Option<Series>> {
+        if self.order <= 0 {
+            // not even the value at the point is known
+            return Ok(Some(self.with(0, vec![], self.order)));
+        }
+        if self.start < 0 {
+            return Ok(None);
+        }
+        let value = self.coefficient(0);
+        // the Taylor series of `f` about `value` from the derivatives there
+        let taylor = |derivatives: &[SimpleExpr]| -> ComputeResult<Series> {
+            let mut coeffs = Vec::new();
+            let mut factorial = SimpleExpr::from(1);
+            for k in 0..self.order {
+                limits::step(|| self.to_series_data())?;
+                if k > 0 {
+                    factorial = mul(factorial, ratio(1, k))?;
+                }
+                let derivative = derivatives[k as usize % derivatives.len()].clone();
+                coeffs.push(mul(factorial.clone(), derivative)?);
+            }
+            Series::new(&self.var, value.clone(), 0, coeffs, self.order).compose(self)
+        };
+        let at = |name| apply(name, value.clone());
+        let one = self.constant(1.into(), self.order);
+        // `f(g) = f(g0) + ∫ f'(g) g'`
+        let derivative = match name {
+            "Exp" => return Ok(Some(taylor(&[at("Exp")?])?)),
+            "Sin" => {
+                let (sin, cos) = (at("Sin")?, at("Cos")?);
+                let derivatives = [sin.clone(), cos.clone(), sin.negate()?, cos.negate()?];
+                return Ok(Some(taylor(&derivatives)?));
+            }
+            "Cos" => {
+                let (sin, cos) = (at("Sin")?, at("Cos")?);
+                let derivatives = [cos.clone(), sin.clone().negate()?, cos.negate()?, sin];
+                return Ok(Some(taylor(&derivatives)?));
+            }
+            "Sinh" => return Ok(Some(taylor(&[at("Sinh")?, at("Cosh")?])?)),
+            "Cosh" => return Ok(Some(taylor(&[at("Cosh")?, at("Sinh")?])?)),
+            "Tan" | "Tanh" => {
+                let (sin, cos) = match name {
+                    "Tan" => ("Sin", "Cos"),
+                    _ => ("Sinh", "Cosh"),
+                };
+                let (Some(sin), Some(cos)) = (self.apply(sin)?, self.apply(cos)?) else {
+                    return Ok(None);
+                };
+                return Ok(Some(sin.div(&cos)?));
+            }
+            "Sqrt" => return self.pow(&ratio(1, 2)),
+            "Log" => self.reciprocal()?,
+            "ArcTan" => one.add(&self.powi(2)?)?.reciprocal()?,
+            "ArcSin" | "ArcCos" => {
+                let Some(root) = one.sub(&self.powi(2)?)?.pow(&ratio(-1, 2))? else {
+                    return Ok(None);
+                };
+                match name {
+                    "ArcSin" => root,
+                    _ => root.negate()?,
+                }
+            }
+            _ => return Ok(None),
+        };
+        let Some(integral) = derivative.mul(&self.derivative()?)?.integral()? else {
+            return Ok(None);
+        };
+        Ok(Some(
+            integral.add(&self.constant(at(name)?, integral.order))?,
+        ))
+    }
+
+    // the nonzero coefficients with their exponents, in increasing order
+    pub fn coefficients(&self) -> impl Iterator<Item = (i64, &SimpleExpr)> {
+        (self.start..).zip(&self.coeffs).filter(|(_, c)| **c != 0)
+    }
+
+    // `x - x0`
+    pub fn base(&self) -> ComputeResult {
+        add(
+            SimpleExpr::Symbol(self.var.clone()),
+            self.point.clone().negate()?,
+        )
+    }
+
+    // `(x - x0)^order`, for printing `O((x - x0)^order)`
+    pub fn order_term(&self) -> ComputeResult {
+        pow(self.base()?, int(self.order))
+    }
+
+    // the sum of the terms, without the order term
+    pub fn to_expr(&self) -> ComputeResult {
+        let base = self.base()?;
+        let terms = self
+            .coefficients()
+            .map(|(k, c)| mul(c.clone(), pow(base.clone(), int(k))?))
+            .collect::<ComputeResult<_>>()?;
+        total(terms)
+    }
+
+    // `SeriesData[x, x0, {c...}, start, order]`, the form a series takes as an expression
+    pub fn to_series_data(&self) -> SimpleExpr {
+        SimpleExpr::Function(
+            "SeriesData".into(),
+            vec![
+                SimpleExpr::Symbol(self.var.clone()),
+                self.point.clone(),
+                SimpleExpr::List(self.coeffs.clone()),
+                int(self.start),
+                int(self.order),
+            ],
+        )
+    }
+
+    pub fn from_series_data(expr: &SimpleExpr) -> Option<Series> {
+        let SimpleExpr::Function(name, args) = expr else {
+            return None;
+        };
+        match args.as_slice() {
+            [SimpleExpr::Symbol(var), point, SimpleExpr::List(coeffs), start, order]
+                if name == "SeriesData" =>
+            {
+                let (start, order) = (as_integer(start)?, as_integer(order)?);
+                Some(Series::new(
+                    var,
+                    point.clone(),
+                    start,
+                    coeffs.clone(),
+                    order,
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn expand(
+    expr: &SimpleExpr,
+    var: &str,
+    point: &SimpleExpr,
+    precision: i64,
+) -> ComputeResult<Option<Series>> {
+    let series = |coeffs| Series::new(var, point.clone(), 0, coeffs, precision);
+    if is_free_of(expr, var) {
+        return Ok(Some(series(vec![expr.clone()])));
+    }
+    let each = |x: &[SimpleExpr], combine: fn(&Series, &Series) -> ComputeResult<Series>| {
+        let mut result: Option<Series> = None;
+        for x in x {
+            let Some(x) = expand(x, var, point, precision)? else {
+                return Ok(None);
+            };
+            result = Some(match result {
+                Some(result) => combine(&result, &x)?,
+                None => x,
+            });
+        }
+        Ok(result)
+    };
+    match expr {
+        SimpleExpr::Symbol(_) => Ok(Some(series(vec![point.clone(), 1.into()]))),
+        SimpleExpr::Sum(terms) => each(terms, Series::add),
+        SimpleExpr::Product(factors) => each(factors, Series::mul),
+        SimpleExpr::Pow(p) if is_free_of(&p.1, var) => match expand(&p.0, var, point, precision)? {
+            Some(base) => base.pow(&p.1),
+            None => Ok(None),
+        },
+        // b^e = Exp[e Log[b]]
+        SimpleExpr::Pow(p) => {
+            let exponent = mul(p.1.clone(), function("Log", p.0.clone()))?;
+            expand(&function("Exp", exponent), var, point, precision)
+        }
+        SimpleExpr::Function(name, args) if let [u] = args.as_slice() => {
+            match expand(u, var, point, precision)? {
+                Some(u) => u.apply(name),
+                None => Ok(None),
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+// The series of `expr` in `var` about `point` up to and including the `(var - point)^n`
+// term, as `Series[f, {x, x0, n}]`. Returns `None` if there is no such expansion, as at a
+// branch point or an essential singularity.
+pub fn series(
+    expr: &SimpleExpr,
+    var: &str,
+    point: &SimpleExpr,
+    n: i64,
+) -> ComputeResult<Option<Series>> {
+    let target = n + 1;
+    let mut precision = target;
+    for _ in 0..MAX_RETRIES {
+        limits::step(|| expr.clone())?;
+        let Some(series) = expand(expr, var, point, precision)? else {
+            return Ok(None);
+        };
+        if series.order >= target {
+            return Ok(Some(series.truncate(target)));
+        }
+        precision += (target - series.order).clamp(1, precision.abs().max(1));
+    }
+    Ok(None)
+}
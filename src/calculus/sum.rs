@@ -0,0 +1,430 @@
+// Sums and products over a range of integers.
+//
+// Sums are found by linearity from Faulhaber's formula for powers, the geometric series, and
+// telescoping: terms `c_i g(k + d_i)` of a common `g` whose `c_i` add up to zero leave only
+// a few values of `g` at either end. Products of linear factors are quotients of factorials.
+// Ranges of a few explicit values are evaluated term by term.
+use num::{One, Signed, ToPrimitive};
+
+use super::{add, is_free_of, limit, mul, pow, Direction};
+use crate::constant::Constant;
+use crate::numeric::to_f64;
+use crate::poly::{apart, coefficients, rational_function, Polynomial};
+use crate::simplify::{Operation, Product, SimpleExpr, Sum};
+use crate::visit::Node;
+use crate::{BasicAlgebraicExpr, ComputeResult};
+
+// the most values a range is expanded into
+const MAX_TERMS: usize = 1000;
+
+// the largest shift `d` looked for in terms `g(k + d)`
+const MAX_SHIFT: i128 = 4;
+
+fn total(terms: Vec<SimpleExpr>) -> ComputeResult {
+    match terms.len() {
+        0 => Ok(0.into()),
+        _ => Sum.simplify(terms),
+    }
+}
+
+fn divide(a: SimpleExpr, b: SimpleExpr) -> ComputeResult {
+    mul(a, pow(b, (-1).into())?)
+}
+
+fn sub(a: SimpleExpr, b: SimpleExpr) -> ComputeResult {
+    add(a, b.negate()?)
+}
+
+fn factorial(x: SimpleExpr) -> ComputeResult {
+    BasicAlgebraicExpr::simplify_factorial(x)
+}
+
+// `f` with `value` for `k`
+fn at(f: &SimpleExpr, k: &str, value: SimpleExpr) -> ComputeResult {
+    f.substitute(k, &value)
+}
+
+// `k + d`
+fn shifted(k: &str, d: SimpleExpr) -> ComputeResult {
+    add(SimpleExpr::Symbol(k.into()), d)
+}
+
+// the number of values from `a` to `b`
+fn count(a: &SimpleExpr, b: &SimpleExpr) -> ComputeResult {
+    add(sub(b.clone(), a.clone())?, 1.into())
+}
+
+// The range steps by one from `a`, so its last value is `a + floor(b - a)`. `None` when
+// `b - a` is not a number and one of the bounds is not an integer.
+fn last_value(a: &SimpleExpr, b: &SimpleExpr) -> ComputeResult<Option<SimpleExpr>> {
+    let is_fraction = |x: &SimpleExpr| matches!(x, SimpleExpr::Const(c) if !c.is_integer());
+    match sub(b.clone(), a.clone())? {
+        SimpleExpr::Const(d) if d.is_integer() => Ok(Some(b.clone())),
+        SimpleExpr::Const(d) => add(a.clone(), SimpleExpr::Const(d.floor().into())).map(Some),
+        _ if is_fraction(a) || is_fraction(b) => Ok(None),
+        _ => Ok(Some(b.clone())),
+    }
+}
+
+// the values from `a` to `b` when both are integers and there are few of them
+fn values(a: &SimpleExpr, b: &SimpleExpr) -> Option<Vec<SimpleExpr>> {
+    let (SimpleExpr::Const(a), SimpleExpr::Const(b)) = (a, b) else {
+        return None;
+    };
+    let (a, b) = (a.as_integer()?.to_i128()?, b.as_integer()?.to_i128()?);
+    let len = usize::try_from(b - a + 1).unwrap_or(0);
+    (len <= MAX_TERMS).then(|| (a..=b).map(SimpleExpr::from).collect())
+}
+
+fn is_finite(x: &SimpleExpr) -> bool {
+    !x.free_symbols()
+        .into_iter()
+        .any(|s| matches!(s, "Infinity" | "ComplexInfinity" | "Indeterminate"))
+}
+
+// Bernoulli numbers `B_0` to `B_n`, with `B_1 = 1/2`
+fn bernoulli(n: usize) -> Vec<Constant> {
+    let mut b: Vec<Constant> = vec![Constant::one()];
+    for m in 1..=n {
+        // B_m = 1 - sum C(m, j) B_j / (m - j + 1)
+        let mut value = Constant::one();
+        let mut binomial = Constant::one();
+        for (j, b) in b.iter().enumerate() {
+            value = value - binomial.clone() * b.clone() / Constant::from((m - j + 1) as i128);
+            binomial = binomial * Constant::from((m - j) as i128) / Constant::from(j as i128 + 1);
+        }
+        b.push(value);
+    }
+    b
+}
+
+// `1^p + .. + n^p` as a polynomial in `n`
+fn power_sum(p: usize) -> Polynomial {
+    let b = bernoulli(p);
+    let mut coeffs = vec![Constant::from(0); p + 2];
+    let mut binomial = Constant::one();
+    for (j, b) in b.iter().enumerate() {
+        coeffs[p + 1 - j] = binomial.clone() * b.clone() / Constant::from(p as i128 + 1);
+        binomial = binomial * Constant::from((p + 1 - j) as i128) / Constant::from(j as i128 + 1);
+    }
+    Polynomial::new(coeffs)
+}
+
+fn evaluate(p: &Polynomial, x: &SimpleExpr) -> ComputeResult {
+    p.coeffs()
+        .iter()
+        .rev()
+        .try_fold(SimpleExpr::from(0), |acc, c| {
+            add(mul(acc, x.clone())?, SimpleExpr::Const(c.clone()))
+        })
+}
+
+// Faulhaber's formula for a polynomial with coefficients `coeffs`
+fn faulhaber(coeffs: &[SimpleExpr], a: &SimpleExpr, b: &SimpleExpr) -> ComputeResult {
+    let before = sub(a.clone(), 1.into())?;
+    let mut terms = Vec::new();
+    for (p, c) in coeffs.iter().enumerate() {
+        let s = power_sum(p);
+        terms.push(mul(
+            c.clone(),
+            sub(evaluate(&s, b)?, evaluate(&s, &before)?)?,
+        )?);
+    }
+    total(terms)?.expand()
+}
+
+// `s(k) = c t(k + d)` with `c` free of `k`
+fn shift_of(t: &SimpleExpr, s: &SimpleExpr, k: &str) -> ComputeResult<Option<(i128, SimpleExpr)>> {
+    for d in -MAX_SHIFT..=MAX_SHIFT {
+        let c = divide(s.clone(), at(t, k, shifted(k, d.into())?)?)?;
+        if is_free_of(&c, k) {
+            return Ok(Some((d, c)));
+        }
+    }
+    Ok(None)
+}
+
+// `sum c_i g(k + d_i)` for `k` from `a` to `b`, where the `c_i` add up to zero
+fn telescope(
+    g: &SimpleExpr,
+    class: &[(i128, SimpleExpr)],
+    k: &str,
+    a: &SimpleExpr,
+    b: &SimpleExpr,
+) -> ComputeResult<Option<SimpleExpr>> {
+    let lowest = class.iter().map(|(d, _)| *d).min().expect("nonempty class");
+    let g = at(g, k, shifted(k, lowest.into())?)?;
+    // the sum over k + d is the sum over k with `d` values moved from the start to the end
+    let limit = match *b == SimpleExpr::infinity() {
+        true => match limit(&g, k, b, Direction::TwoSided)? {
+            Some(l) if is_finite(&l) => Some(l),
+            _ => return Ok(None),
+        },
+        false => None,
+    };
+    let mut terms = Vec::new();
+    for (d, c) in class {
+        let d = d - lowest;
+        let mut moved = Vec::new();
+        for t in 0..d {
+            moved.push(at(&g, k, add(a.clone(), t.into())?)?.negate()?);
+            moved.push(match &limit {
+                Some(l) => l.clone(),
+                None => at(&g, k, add(b.clone(), (t + 1).into())?)?,
+            });
+        }
+        terms.push(mul(c.clone(), total(moved)?)?);
+    }
+    total(terms)?.expand().map(Some)
+}
+
+// the sum of a single term, which is not telescoping
+fn sum_term(
+    f: &SimpleExpr,
+    k: &str,
+    a: &SimpleExpr,
+    b: &SimpleExpr,
+) -> ComputeResult<Option<SimpleExpr>> {
+    let infinite = *b == SimpleExpr::infinity();
+    if is_free_of(f, k) {
+        return Ok(match infinite {
+            true => (*f == 0).then(|| 0.into()),
+            false => Some(mul(f.clone(), count(a, b)?)?),
+        });
+    }
+    if let Some(coeffs) = coefficients(&f.clone().expand()?, k)? {
+        return Ok(match infinite {
+            true => None,
+            false => Some(faulhaber(&coeffs, a, b)?),
+        });
+    }
+    // a geometric series with ratio `q`
+    let q = divide(at(f, k, shifted(k, 1.into())?)?, f.clone())?;
+    if !is_free_of(&q, k) {
+        return Ok(None);
+    }
+    let first = at(f, k, a.clone())?;
+    let rest = sub(1.into(), q.clone())?;
+    if infinite {
+        return Ok(match to_f64(&q)? {
+            Some(q) if q.abs() < 1.0 => Some(divide(first, rest)?),
+            _ => None,
+        });
+    }
+    let power = pow(q, count(a, b)?)?;
+    let factor = divide(first, rest)?;
+    let sum = mul(factor.clone(), sub(1.into(), power)?)?;
+    // distribute a numeric factor, for `2^(n + 1) - 1` rather than `-(1 - 2^(n + 1))`
+    match factor {
+        SimpleExpr::Const(_) => sum.expand().map(Some),
+        _ => Ok(Some(sum)),
+    }
+}
+
+// The sum of `f` for integers `k` from `a` to `b`, where `b` may be `Infinity`. Returns
+// `None` if no closed form is found.
+pub fn sum(
+    f: &SimpleExpr,
+    k: &str,
+    a: &SimpleExpr,
+    b: &SimpleExpr,
+) -> ComputeResult<Option<SimpleExpr>> {
+    let Some(b) = last_value(a, b)? else {
+        return Ok(None);
+    };
+    let b = &b;
+    if let Some(values) = values(a, b) {
+        let terms = values
+            .into_iter()
+            .map(|x| at(f, k, x))
+            .collect::<ComputeResult<_>>()?;
+        return total(terms).map(Some);
+    }
+    if !is_free_of(a, k) || !is_free_of(b, k) {
+        return Ok(None);
+    }
+    if coefficients(&f.clone().expand()?, k)?.is_some() {
+        return sum_term(f, k, a, b);
+    }
+    let f = match rational_function(f, k)? {
+        Some(_) => apart(f, k)?,
+        None => f.clone(),
+    };
+    let terms = match f {
+        SimpleExpr::Sum(terms) => terms,
+        f => vec![f],
+    };
+
+    let mut sums = Vec::new();
+    let mut rest = Vec::new();
+    let mut used = vec![false; terms.len()];
+    for (i, g) in terms.iter().enumerate() {
+        if used[i] {
+            continue;
+        }
+        used[i] = true;
+        if is_free_of(g, k) {
+            rest.push(g.clone());
+            continue;
+        }
+        let mut class = vec![(0, SimpleExpr::from(1))];
+        let mut members = vec![g.clone()];
+        for (j, s) in terms.iter().enumerate().skip(i + 1) {
+            if !used[j]
+                && !is_free_of(s, k)
+                && let Some(shift) = shift_of(g, s, k)?
+            {
+                used[j] = true;
+                class.push(shift);
+                members.push(s.clone());
+            }
+        }
+        let weights = total(class.iter().map(|(_, c)| c.clone()).collect())?;
+        match class.len() > 1 && weights == 0 {
+            true => match telescope(g, &class, k, a, b)? {
+                Some(x) => sums.push(x),
+                None => return Ok(None),
+            },
+            false => rest.extend(members),
+        }
+    }
+    for term in rest {
+        match sum_term(&term, k, a, b)? {
+            Some(x) => sums.push(x),
+            None => return Ok(None),
+        }
+    }
+    total(sums).map(Some)
+}
+
+// `prod (k + c)` for `k` from `a` to `b`, if no factor can be zero
+fn rising(c: &SimpleExpr, a: &SimpleExpr, b: &SimpleExpr) -> ComputeResult<Option<SimpleExpr>> {
+    let low = add(a.clone(), sub(c.clone(), 1.into())?)?;
+    if let SimpleExpr::Const(low) = &low
+        && low.is_negative()
+    {
+        return Ok(None);
+    }
+    let high = add(b.clone(), c.clone())?;
+    Ok(Some(divide(factorial(high)?, factorial(low)?)?))
+}
+
+// the product of `alpha k + beta`
+fn linear_product(
+    alpha: &SimpleExpr,
+    beta: &SimpleExpr,
+    a: &SimpleExpr,
+    b: &SimpleExpr,
+) -> ComputeResult<Option<SimpleExpr>> {
+    let n = count(a, b)?;
+    // (-alpha) (gamma - k) is a falling factorial: (gamma - a)! / (gamma - b - 1)!
+    if let SimpleExpr::Const(c) = alpha
+        && c.is_negative()
+    {
+        let alpha = alpha.clone().negate()?;
+        let gamma = divide(beta.clone(), alpha.clone())?;
+        let low = sub(sub(gamma.clone(), b.clone())?, 1.into())?;
+        if let SimpleExpr::Const(low) = &low
+            && low.is_negative()
+        {
+            return Ok(None);
+        }
+        let high = factorial(sub(gamma, a.clone())?)?;
+        return Ok(Some(mul(pow(alpha, n)?, divide(high, factorial(low)?)?)?));
+    }
+    let c = divide(beta.clone(), alpha.clone())?;
+    let Some(rising) = rising(&c, a, b)? else {
+        return Ok(None);
+    };
+    Ok(Some(mul(pow(alpha.clone(), n)?, rising)?))
+}
+
+fn closed_product(
+    f: &SimpleExpr,
+    k: &str,
+    a: &SimpleExpr,
+    b: &SimpleExpr,
+) -> ComputeResult<Option<SimpleExpr>> {
+    if is_free_of(f, k) {
+        return pow(f.clone(), count(a, b)?).map(Some);
+    }
+    match f {
+        SimpleExpr::Product(factors) => {
+            let mut products = Vec::new();
+            for factor in factors {
+                match closed_product(factor, k, a, b)? {
+                    Some(x) => products.push(x),
+                    None => return Ok(None),
+                }
+            }
+            return Product.simplify(products).map(Some);
+        }
+        SimpleExpr::Pow(p) if is_free_of(&p.1, k) => {
+            return Ok(match closed_product(&p.0, k, a, b)? {
+                Some(x) => Some(pow(x, p.1.clone())?),
+                None => None,
+            });
+        }
+        // the exponents add up
+        SimpleExpr::Pow(p) if is_free_of(&p.0, k) => {
+            return Ok(match sum(&p.1, k, a, b)? {
+                Some(x) => Some(pow(p.0.clone(), x)?),
+                None => None,
+            });
+        }
+        _ => {}
+    }
+    let expanded = f.clone().expand()?;
+    if let Some(coeffs) = coefficients(&expanded, k)?
+        && let [beta, alpha] = coeffs.as_slice()
+    {
+        return linear_product(alpha, beta, a, b);
+    }
+    // a polynomial that splits into linear factors
+    let Some(p) = Polynomial::from_expr(&expanded, k) else {
+        return Ok(None);
+    };
+    let (leading, factors) = p.factor()?;
+    let mut products = vec![pow(SimpleExpr::Const(leading), count(a, b)?)?];
+    for (factor, multiplicity) in factors {
+        let [c, _] = factor.coeffs() else {
+            return Ok(None);
+        };
+        let Some(x) = rising(&SimpleExpr::Const(c.clone()), a, b)? else {
+            return Ok(None);
+        };
+        products.push(pow(x, (multiplicity as i128).into())?);
+    }
+    Product.simplify(products).map(Some)
+}
+
+// The product of `f` for integers `k` from `a` to `b`. Returns `None` if no closed form is
+// found.
+pub fn product(
+    f: &SimpleExpr,
+    k: &str,
+    a: &SimpleExpr,
+    b: &SimpleExpr,
+) -> ComputeResult<Option<SimpleExpr>> {
+    let Some(b) = last_value(a, b)? else {
+        return Ok(None);
+    };
+    let b = &b;
+    if let Some(values) = values(a, b) {
+        let factors = values
+            .into_iter()
+            .map(|x| at(f, k, x))
+            .collect::<ComputeResult<Vec<_>>>()?;
+        return match factors.len() {
+            0 => Ok(Some(1.into())),
+            _ => Product.simplify(factors).map(Some),
+        };
+    }
+    if *b == SimpleExpr::infinity() {
+        return Ok((*f == 1).then(|| 1.into()));
+    }
+    if !is_free_of(a, k) || !is_free_of(b, k) {
+        return Ok(None);
+    }
+    closed_product(f, k, a, b)
+}
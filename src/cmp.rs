@@ -1,6 +1,9 @@
 use std::cmp::Ordering;
 use std::slice;
 
+use num::Signed;
+
+use crate::constant::Constant;
 use crate::simplify::SimpleExpr;
 use crate::BasicAlgebraicExpr;
 
@@ -10,8 +13,17 @@ impl PartialOrd for BasicAlgebraicExpr {
     }
 }
 
+// `c` compared with 1
+fn cmp_one(c: &Constant) -> Ordering {
+    if c.is_negative() {
+        Ordering::Less
+    } else {
+        c.numer().cmp(c.denom())
+    }
+}
+
 fn cmp_list<T: Ord>(a: &[T], b: &[T]) -> Ordering {
-    let elems = a.iter().zip(b.iter()).rev();
+    let elems = a.iter().rev().zip(b.iter().rev());
 
     for (a, b) in elems {
         match a.cmp(b) {
@@ -36,15 +48,28 @@ macro_rules! common_ord {
             (Const(a), Const(b)) => a.cmp(b),
             (Const(_), _) => Ordering::Less,
             (_, Const(_)) => Ordering::Greater,
+            (Relation(op1, a), Relation(op2, b)) => op1.cmp(op2).then_with(|| a.cmp(b)),
+            (Relation(..), _) => Ordering::Greater,
+            (_, Relation(..)) => Ordering::Less,
+            (List(a), List(b)) => a.cmp(b),
+            (List(_), _) => Ordering::Greater,
+            (_, List(_)) => Ordering::Less,
             (Product(a), Product(b)) => cmp_list(a, b),
             (Product(a), b) => cmp_list(a, slice::from_ref(b)),
-            (a, Product(b)) => cmp_list(slice::from_ref(a), b).reverse(),
+            (a, Product(b)) => cmp_list(slice::from_ref(a), b),
             (Pow(a), Pow(b)) => a.cmp(b),
-            (Pow(a), b) => a.cmp(&Box::new((b.clone(), 1.into()))),
-            (a, Pow(b)) => Box::new((a.clone(), 1.into())).cmp(b),
+            // as if `b` was `b^1`
+            (Pow(a), b) => a.0.cmp(b).then_with(|| match &a.1 {
+                Const(c) => cmp_one(c),
+                _ => Ordering::Greater,
+            }),
+            (a, Pow(b)) => a.cmp(&b.0).then_with(|| match &b.1 {
+                Const(c) => cmp_one(c).reverse(),
+                _ => Ordering::Less,
+            }),
             (Sum(a), Sum(b)) => cmp_list(a, b),
             (Sum(a), b) => cmp_list(a, slice::from_ref(b)),
-            (a, Sum(b)) => cmp_list(slice::from_ref(a), b).reverse(),
+            (a, Sum(b)) => cmp_list(slice::from_ref(a), b),
             (Factorial(a), Factorial(b)) => a.cmp(b),
             (Factorial(a), b) => {
                 if &**a == b {
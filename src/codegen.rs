@@ -0,0 +1,335 @@
+// Source code generation, for evaluating expressions as `f64`s in Rust, C or Python.
+//
+// Integer powers use `powi` in Rust, or `powf` beyond the range of `i32`, `pow` in C and `**` in
+// Python, and Python functions come from NumPy, which is expected to be imported as `np`.
+// Rational constants are written as divisions such as `1.0/3.0`. Subexpressions that occur more
+// than once are computed only once, into temporaries `t0`, `t1` and so on that skip the names of
+// the arguments.
+use std::collections::HashMap;
+
+use num::{Signed, ToPrimitive};
+
+use crate::constant::Constant;
+use crate::print::{negated, split_fraction};
+use crate::simplify::SimpleExpr;
+use crate::visit::Node;
+use crate::{ComputeError, ComputeResult};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Language {
+    Rust,
+    C,
+    Python,
+}
+
+// `pub fn name(x: f64, ..) -> f64`
+pub fn to_rust(name: &str, args: &[&str], x: &SimpleExpr) -> ComputeResult<String> {
+    generate(Language::Rust, name, args, x)
+}
+
+// `double name(double x, ..)`, using `math.h`
+pub fn to_c(name: &str, args: &[&str], x: &SimpleExpr) -> ComputeResult<String> {
+    generate(Language::C, name, args, x)
+}
+
+// `def name(x, ..)`, using NumPy as `np`
+pub fn to_python(name: &str, args: &[&str], x: &SimpleExpr) -> ComputeResult<String> {
+    generate(Language::Python, name, args, x)
+}
+
+// A function `name` of `args` evaluating `x`. Symbols other than the arguments, `Pi` and `E`,
+// and operations without a floating point counterpart such as factorials or lists are
+// unsupported.
+pub fn generate(
+    language: Language,
+    name: &str,
+    args: &[&str],
+    x: &SimpleExpr,
+) -> ComputeResult<String> {
+    let mut generator = Generator {
+        language,
+        args,
+        counts: HashMap::new(),
+        temps: HashMap::new(),
+        lets: Vec::new(),
+    };
+    generator.count(x);
+    let (body, _) = generator.code(x)?;
+    let mut f = String::new();
+    match language {
+        Language::Rust => {
+            let args: Vec<_> = args.iter().map(|x| format!("{x}: f64")).collect();
+            f.push_str(&format!("pub fn {name}({}) -> f64 {{\n", args.join(", ")));
+            for (temp, code) in &generator.lets {
+                f.push_str(&format!("    let {temp} = {code};\n"));
+            }
+            f.push_str(&format!("    {body}\n}}\n"));
+        }
+        Language::C => {
+            let args: Vec<_> = args.iter().map(|x| format!("double {x}")).collect();
+            f.push_str(&format!("double {name}({}) {{\n", args.join(", ")));
+            for (temp, code) in &generator.lets {
+                f.push_str(&format!("    const double {temp} = {code};\n"));
+            }
+            f.push_str(&format!("    return {body};\n}}\n"));
+        }
+        Language::Python => {
+            f.push_str(&format!("def {name}({}):\n", args.join(", ")));
+            for (temp, code) in &generator.lets {
+                f.push_str(&format!("    {temp} = {code}\n"));
+            }
+            f.push_str(&format!("    return {body}\n"));
+        }
+    }
+    Ok(f)
+}
+
+// how tightly generated code binds, for deciding on parentheses
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+enum Prec {
+    Sum,
+    Product,
+    // a leading minus, or `**` in Python
+    Unary,
+    Atom,
+}
+
+// the Rust method, C function and NumPy function
+fn function(name: &str) -> Option<(&'static str, &'static str, &'static str)> {
+    Some(match name {
+        "Exp" => ("exp", "exp", "exp"),
+        "Log" => ("ln", "log", "log"),
+        "Sqrt" => ("sqrt", "sqrt", "sqrt"),
+        "Sin" => ("sin", "sin", "sin"),
+        "Cos" => ("cos", "cos", "cos"),
+        "Tan" => ("tan", "tan", "tan"),
+        "Sinh" => ("sinh", "sinh", "sinh"),
+        "Cosh" => ("cosh", "cosh", "cosh"),
+        "Tanh" => ("tanh", "tanh", "tanh"),
+        "ArcSin" => ("asin", "asin", "arcsin"),
+        "ArcCos" => ("acos", "acos", "arccos"),
+        "ArcTan" => ("atan", "atan", "arctan"),
+        "Abs" => ("abs", "fabs", "abs"),
+        _ => return None,
+    })
+}
+
+// only these are worth keeping in temporaries
+fn is_shareable(x: &SimpleExpr) -> bool {
+    matches!(
+        x,
+        SimpleExpr::Sum(_) | SimpleExpr::Product(_) | SimpleExpr::Pow(_) | SimpleExpr::Function(..)
+    )
+}
+
+fn literal(c: &Constant) -> (String, Prec) {
+    match c.as_integer() {
+        Some(n) if n.is_negative() => (format!("{n}.0"), Prec::Unary),
+        Some(n) => (format!("{n}.0"), Prec::Atom),
+        None => (format!("{}.0/{}.0", c.numer(), c.denom()), Prec::Product),
+    }
+}
+
+struct Generator<'a> {
+    language: Language,
+    args: &'a [&'a str],
+    // occurrences of each subexpression, where those inside a repeated one are counted once
+    counts: HashMap<SimpleExpr, usize>,
+    temps: HashMap<SimpleExpr, String>,
+    lets: Vec<(String, String)>,
+}
+
+impl Generator<'_> {
+    fn count(&mut self, x: &SimpleExpr) {
+        let count = self.counts.entry(x.clone()).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            x.children().into_iter().for_each(|x| self.count(x));
+        }
+    }
+
+    fn is_temp(&self, x: &SimpleExpr) -> bool {
+        is_shareable(x) && self.counts.get(x).is_some_and(|&n| n > 1)
+    }
+
+    fn code(&mut self, x: &SimpleExpr) -> ComputeResult<(String, Prec)> {
+        if let Some(temp) = self.temps.get(x) {
+            return Ok((temp.clone(), Prec::Atom));
+        }
+        let code = self.code_uncached(x)?;
+        if !self.is_temp(x) {
+            return Ok(code);
+        }
+        let temp = (self.lets.len()..)
+            .map(|i| format!("t{i}"))
+            .find(|t| {
+                !self.args.contains(&t.as_str()) && !self.lets.iter().any(|(x, _)| x == t)
+            })
+            .expect("infinitely many candidates");
+        self.lets.push((temp.clone(), code.0));
+        self.temps.insert(x.clone(), temp.clone());
+        Ok((temp, Prec::Atom))
+    }
+
+    // `x`, in parentheses if it binds less tightly than `context`
+    fn operand(&mut self, x: &SimpleExpr, context: Prec) -> ComputeResult<String> {
+        let (code, prec) = self.code(x)?;
+        Ok(if prec < context {
+            format!("({code})")
+        } else {
+            code
+        })
+    }
+
+    // the receiver of a method call in Rust, where float literals need a type
+    fn receiver(&mut self, x: &SimpleExpr) -> ComputeResult<String> {
+        match x {
+            SimpleExpr::Const(c) => {
+                let (code, prec) = literal(c);
+                let code = code.replacen(".0", ".0_f64", 1);
+                Ok(if prec < Prec::Atom {
+                    format!("({code})")
+                } else {
+                    code
+                })
+            }
+            x => self.operand(x, Prec::Atom),
+        }
+    }
+
+    fn call(&mut self, name: &str, x: &SimpleExpr) -> ComputeResult<(String, Prec)> {
+        let (rust, c, numpy) = function(name).expect("only known functions are called");
+        let code = match self.language {
+            Language::Rust => format!("{}.{rust}()", self.receiver(x)?),
+            Language::C => format!("{c}({})", self.code(x)?.0),
+            Language::Python => format!("np.{numpy}({})", self.code(x)?.0),
+        };
+        Ok((code, Prec::Atom))
+    }
+
+    fn product(&mut self, factors: &[SimpleExpr]) -> ComputeResult<(String, Prec)> {
+        let (numer, denom) = split_fraction(factors);
+        let (sign, numer) = match numer.as_slice() {
+            [first, rest @ ..] if *first == -1 => ("-", rest),
+            numer => ("", numer),
+        };
+        let numer = numer
+            .iter()
+            .map(|x| self.code(x))
+            .collect::<ComputeResult<Vec<_>>>()?;
+        let mut prec = match (sign, numer.as_slice()) {
+            ("", [(_, prec)]) => (*prec).max(Prec::Unary),
+            ("", []) => Prec::Atom,
+            ("-", _) => Prec::Unary,
+            _ => Prec::Product,
+        };
+        let numer: Vec<_> = numer
+            .into_iter()
+            .map(|(code, prec)| match prec < Prec::Unary {
+                true => format!("({code})"),
+                false => code,
+            })
+            .collect();
+        let mut code = match numer.is_empty() {
+            true => format!("{sign}1.0"),
+            false => format!("{sign}{}", numer.join(" * ")),
+        };
+        if !denom.is_empty() {
+            let factors = denom
+                .iter()
+                .map(|x| self.operand(x, Prec::Unary))
+                .collect::<ComputeResult<Vec<_>>>()?;
+            match factors.as_slice() {
+                [x] => code.push_str(&format!(" / {x}")),
+                factors => code.push_str(&format!(" / ({})", factors.join(" * "))),
+            }
+            prec = Prec::Product;
+        }
+        Ok((code, prec))
+    }
+
+    fn power(&mut self, base: &SimpleExpr, exp: &SimpleExpr) -> ComputeResult<(String, Prec)> {
+        if let SimpleExpr::Const(e) = exp
+            && let Some(n) = e.as_integer()
+        {
+            return Ok(match self.language {
+                Language::Rust if n.to_i32().is_none() => {
+                    (format!("{}.powf({n}.0)", self.receiver(base)?), Prec::Atom)
+                }
+                Language::Rust => (format!("{}.powi({n})", self.receiver(base)?), Prec::Atom),
+                Language::C => (format!("pow({}, {n})", self.code(base)?.0), Prec::Atom),
+                Language::Python => (
+                    format!("{}**{n}", self.operand(base, Prec::Atom)?),
+                    Prec::Unary,
+                ),
+            });
+        }
+        Ok(match self.language {
+            Language::Rust => {
+                let base = self.receiver(base)?;
+                (format!("{base}.powf({})", self.code(exp)?.0), Prec::Atom)
+            }
+            Language::C => {
+                let base = self.code(base)?.0;
+                (format!("pow({base}, {})", self.code(exp)?.0), Prec::Atom)
+            }
+            Language::Python => {
+                let base = self.operand(base, Prec::Atom)?;
+                (
+                    format!("{base}**{}", self.operand(exp, Prec::Atom)?),
+                    Prec::Unary,
+                )
+            }
+        })
+    }
+
+    fn code_uncached(&mut self, x: &SimpleExpr) -> ComputeResult<(String, Prec)> {
+        match x {
+            SimpleExpr::Const(c) => Ok(literal(c)),
+            SimpleExpr::Symbol(s) if self.args.contains(&s.as_str()) => Ok((s.clone(), Prec::Atom)),
+            SimpleExpr::Symbol(s) if s == "Pi" || s == "E" => {
+                let code = match self.language {
+                    Language::Rust => format!("std::f64::consts::{}", s.to_uppercase()),
+                    Language::C => format!("M_{}", s.to_uppercase()),
+                    Language::Python => format!("np.{}", s.to_lowercase()),
+                };
+                Ok((code, Prec::Atom))
+            }
+            SimpleExpr::Sum(terms) => {
+                let mut code = String::new();
+                for (i, x) in terms.iter().enumerate() {
+                    match negated(x) {
+                        _ if i == 0 => code.push_str(&self.operand(x, Prec::Sum)?),
+                        Some(x) if !self.is_temp(&terms[i]) => {
+                            code.push_str(&format!(" - {}", self.operand(&x, Prec::Product)?));
+                        }
+                        _ => code.push_str(&format!(" + {}", self.operand(x, Prec::Sum)?)),
+                    }
+                }
+                Ok((code, Prec::Sum))
+            }
+            SimpleExpr::Product(factors) => self.product(factors),
+            // negative powers are divisions
+            SimpleExpr::Pow(p)
+                if let SimpleExpr::Const(e) = &p.1
+                    && e.is_negative() =>
+            {
+                self.product(std::slice::from_ref(x))
+            }
+            SimpleExpr::Pow(p)
+                if let SimpleExpr::Const(e) = &p.1
+                    && *e == Constant::from(1) / 2.into() =>
+            {
+                self.call("Sqrt", &p.0)
+            }
+            SimpleExpr::Pow(p) => self.power(&p.0, &p.1),
+            SimpleExpr::Function(name, args)
+                if let [x] = args.as_slice()
+                    && function(name).is_some() =>
+            {
+                self.call(name, x)
+            }
+            x => Err(ComputeError::Unsupported(x.clone())),
+        }
+    }
+}
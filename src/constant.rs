@@ -5,6 +5,10 @@ use std::ops::{Add, Deref, DerefMut, Div, Mul, Neg, Rem, Sub};
 use num::traits::Pow;
 use num::{BigInt, BigRational, Num, One, Signed, Zero};
 
+use crate::limits;
+use crate::simplify::SimpleExpr;
+use crate::ComputeResult;
+
 #[derive(PartialEq, Eq, Clone, PartialOrd, Ord, Hash)]
 pub struct Constant(BigRational);
 
@@ -18,6 +22,70 @@ impl Constant {
     pub fn into_inner(self) -> BigRational {
         self.0
     }
+
+    // Positive divisors of an integer in ascending order, found by trial division.
+    // `None` for fractions, zero and integers too large to search this way.
+    pub fn divisors(&self) -> ComputeResult<Option<Vec<BigInt>>> {
+        // trial division runs up to the square root, about a million steps at this size
+        const MAX_BITS: u64 = 40;
+
+        let Some(n) = self.as_integer().map(Signed::abs) else {
+            return Ok(None);
+        };
+        if n.is_zero() || n.bits() > MAX_BITS {
+            return Ok(None);
+        }
+        let mut small = Vec::new();
+        let mut large = Vec::new();
+        let mut d = BigInt::one();
+        while &d * &d <= n {
+            limits::step(|| SimpleExpr::Const(self.clone()))?;
+            if (&n % &d).is_zero() {
+                let q = &n / &d;
+                if q != d {
+                    large.push(q);
+                }
+                small.push(d.clone());
+            }
+            d += 1;
+        }
+        small.extend(large.into_iter().rev());
+        Ok(Some(small))
+    }
+
+    // Splits a positive constant into `(a, b)` such that `self^(1/n) = a * b^(1/n)`,
+    // where `b` is an integer with no `n`-th power factors among small primes.
+    pub fn extract_root(&self, n: u32) -> ComputeResult<(Constant, BigInt)> {
+        const TRIAL_DIVISION_LIMIT: u32 = 1000;
+
+        // rationalize the denominator: (p/q)^(1/n) = (p * q^(n-1))^(1/n) / q
+        let denom = self.denom().clone();
+        let mut rest = self.numer() * num::pow(denom.clone(), n as usize - 1);
+        let mut outside = BigInt::one();
+        let mut inside = BigInt::one();
+
+        let mut d = 2u32;
+        while d <= TRIAL_DIVISION_LIMIT && BigInt::from(d) * d <= rest {
+            limits::step(|| SimpleExpr::Const(self.clone()))?;
+            let mut power = 0;
+            while (&rest % d).is_zero() {
+                rest /= d;
+                power += 1;
+            }
+            outside *= num::pow(BigInt::from(d), power / n as usize);
+            inside *= num::pow(BigInt::from(d), power % n as usize);
+            d += 1;
+        }
+
+        let root = rest.nth_root(n);
+        if num::pow(root.clone(), n as usize) == rest {
+            outside *= root;
+        } else {
+            inside *= rest;
+        }
+
+        Ok((Self(BigRational::new(outside, denom)), inside))
+    }
 }
 
 impl From<i128> for Constant {
@@ -0,0 +1,68 @@
+// Why a computation has no result, with the subexpression that caused it.
+use std::fmt;
+
+use crate::limits::Limit;
+use crate::print::to_latex;
+use crate::simplify::SimpleExpr;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ComputeError {
+    // the power or quotient that divides by zero, as `0^(-1)` or `x 0^(-1)`
+    DivisionByZero(SimpleExpr),
+    // `0^0`, or a limit such as `Infinity - Infinity`
+    IndeterminateForm(SimpleExpr),
+    // an argument outside the domain of a function, as in `(-1)!`
+    DomainError { function: String, arg: SimpleExpr },
+    // an operation that is not implemented for, or does not apply to, its arguments
+    Unsupported(SimpleExpr),
+    // a result too large to compute
+    Overflow(SimpleExpr),
+    // one of the `Limits` was reached while computing `expr`
+    LimitExceeded { limit: Limit, expr: SimpleExpr },
+}
+
+impl ComputeError {
+    pub fn domain(function: &str, arg: SimpleExpr) -> Self {
+        ComputeError::DomainError {
+            function: function.into(),
+            arg,
+        }
+    }
+
+    // the subexpression responsible for the error
+    pub fn expr(&self) -> &SimpleExpr {
+        match self {
+            ComputeError::DivisionByZero(x)
+            | ComputeError::IndeterminateForm(x)
+            | ComputeError::DomainError { arg: x, .. }
+            | ComputeError::Unsupported(x)
+            | ComputeError::Overflow(x)
+            | ComputeError::LimitExceeded { expr: x, .. } => x,
+        }
+    }
+}
+
+impl fmt::Display for ComputeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComputeError::DivisionByZero(x) => write!(f, "division by zero in {}", to_latex(x)),
+            ComputeError::IndeterminateForm(x) => write!(f, "indeterminate form {}", to_latex(x)),
+            ComputeError::DomainError { function, arg } => {
+                write!(f, "{} is outside the domain of {function}", to_latex(arg))
+            }
+            ComputeError::Unsupported(x) => write!(f, "cannot compute {}", to_latex(x)),
+            ComputeError::Overflow(x) => write!(f, "{} is too large to compute", to_latex(x)),
+            ComputeError::LimitExceeded { limit, expr } => {
+                let reason = match limit {
+                    Limit::Steps => "step limit reached",
+                    Limit::Bits => "integer size limit reached",
+                    Limit::Deadline => "time limit reached",
+                    Limit::Cancelled => "cancelled",
+                };
+                write!(f, "{reason} while computing {}", to_latex(expr))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ComputeError {}
@@ -4,7 +4,7 @@ use num::BigRational;
 
 use crate::rational_expressions::SimplifiedRationalExpression;
 use crate::simplify::SimpleExpr;
-use crate::{BasicAlgebraicExpr, ComputeResult, Undefined};
+use crate::{BasicAlgebraicExpr, ComputeResult};
 
 impl PartialEq<SimpleExpr> for i64 {
     fn eq(&self, other: &SimpleExpr) -> bool {
@@ -23,11 +23,7 @@ impl PartialEq<i64> for SimpleExpr {
 
 impl From<SimplifiedRationalExpression> for ComputeResult {
     fn from(e: SimplifiedRationalExpression) -> Self {
-        match e {
-            SimplifiedRationalExpression::Frac(f) => Ok(SimpleExpr::Const(f.into())),
-            SimplifiedRationalExpression::Num(n) => Ok(SimpleExpr::Const(n.into())),
-            SimplifiedRationalExpression::Undefined => Err(Undefined),
-        }
+        e.into_algebraic_expr()
     }
 }
 
@@ -43,6 +39,32 @@ impl From<i128> for SimpleExpr {
     }
 }
 
+// allows an already simplified expression to be simplified again after being rewritten
+impl From<SimpleExpr> for BasicAlgebraicExpr {
+    fn from(x: SimpleExpr) -> Self {
+        fn list(x: Vec<SimpleExpr>) -> Vec<BasicAlgebraicExpr> {
+            x.into_iter().map(Into::into).collect()
+        }
+        match x {
+            SimpleExpr::Const(c) => BasicAlgebraicExpr::Const(c),
+            SimpleExpr::Symbol(s) => BasicAlgebraicExpr::Symbol(s),
+            SimpleExpr::Product(x) => BasicAlgebraicExpr::Product(list(x)),
+            SimpleExpr::Sum(x) => BasicAlgebraicExpr::Sum(list(x)),
+            SimpleExpr::Pow(x) => {
+                let (a, b) = *x;
+                BasicAlgebraicExpr::Pow(Box::new((a.into(), b.into())))
+            }
+            SimpleExpr::Factorial(x) => BasicAlgebraicExpr::Factorial(Box::new((*x).into())),
+            SimpleExpr::Function(name, args) => BasicAlgebraicExpr::Function(name, list(args)),
+            SimpleExpr::List(x) => BasicAlgebraicExpr::List(list(x)),
+            SimpleExpr::Relation(op, x) => {
+                let (a, b) = *x;
+                BasicAlgebraicExpr::Relation(op, Box::new((a.into(), b.into())))
+            }
+        }
+    }
+}
+
 impl Mul for SimpleExpr {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self::Output {
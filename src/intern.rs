@@ -0,0 +1,154 @@
+// Hash-consed expressions. Structurally equal expressions share one allocation, so equality is
+// a pointer comparison, hashes are computed once, and clones are reference count increments.
+// The table is per thread and only holds weak references: an expression is freed once the last
+// `Expr` pointing to it is dropped. Use `Expr::from(&SimpleExpr)` and `Expr::to_simple` to
+// convert.
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::{Rc, Weak};
+
+use crate::constant::Constant;
+use crate::relation::RelOp;
+use crate::simplify::SimpleExpr;
+
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub enum Kind {
+    Const(Constant),
+    Symbol(Rc<str>),
+    Product(Vec<Expr>),
+    Sum(Vec<Expr>),
+    Pow(Expr, Expr),
+    Factorial(Expr),
+    Function(Rc<str>, Vec<Expr>),
+    List(Vec<Expr>),
+    Relation(RelOp, Expr, Expr),
+}
+
+struct Node {
+    kind: Kind,
+    hash: u64,
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        // the table may already be gone when the thread exits
+        let _ = TABLE.try_with(|table| {
+            let mut table = table.borrow_mut();
+            if let Some(bucket) = table.get_mut(&self.hash) {
+                bucket.retain(|x| x.strong_count() > 0);
+                if bucket.is_empty() {
+                    table.remove(&self.hash);
+                }
+            }
+        });
+    }
+}
+
+thread_local! {
+    // interned nodes by hash
+    static TABLE: RefCell<HashMap<u64, Vec<Weak<Node>>>> = RefCell::default();
+}
+
+#[derive(Clone)]
+pub struct Expr(Rc<Node>);
+
+impl Expr {
+    pub fn new(kind: Kind) -> Expr {
+        let mut hasher = DefaultHasher::new();
+        kind.hash(&mut hasher);
+        let hash = hasher.finish();
+        TABLE.with(|table| {
+            let mut table = table.borrow_mut();
+            let bucket = table.entry(hash).or_default();
+            // children are interned already, so comparing kinds is shallow
+            if let Some(node) = bucket
+                .iter()
+                .filter_map(Weak::upgrade)
+                .find(|x| x.kind == kind)
+            {
+                return Expr(node);
+            }
+            let node = Rc::new(Node { kind, hash });
+            bucket.push(Rc::downgrade(&node));
+            Expr(node)
+        })
+    }
+
+    pub fn kind(&self) -> &Kind {
+        &self.0.kind
+    }
+
+    pub fn to_simple(&self) -> SimpleExpr {
+        let all = |x: &[Expr]| x.iter().map(Expr::to_simple).collect();
+        match self.kind() {
+            Kind::Const(c) => SimpleExpr::Const(c.clone()),
+            Kind::Symbol(s) => SimpleExpr::Symbol(s.to_string()),
+            Kind::Product(x) => SimpleExpr::Product(all(x)),
+            Kind::Sum(x) => SimpleExpr::Sum(all(x)),
+            Kind::Pow(a, b) => SimpleExpr::Pow(Box::new((a.to_simple(), b.to_simple()))),
+            Kind::Factorial(x) => SimpleExpr::Factorial(Box::new(x.to_simple())),
+            Kind::Function(name, args) => SimpleExpr::Function(name.to_string(), all(args)),
+            Kind::List(x) => SimpleExpr::List(all(x)),
+            Kind::Relation(op, a, b) => {
+                SimpleExpr::Relation(*op, Box::new((a.to_simple(), b.to_simple())))
+            }
+        }
+    }
+
+    // the number of distinct expressions interned on this thread
+    pub fn interned() -> usize {
+        TABLE.with(|table| table.borrow().values().map(Vec::len).sum())
+    }
+}
+
+impl PartialEq for Expr {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Expr {}
+
+impl Hash for Expr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.0.hash);
+    }
+}
+
+impl fmt::Debug for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind().fmt(f)
+    }
+}
+
+impl From<&SimpleExpr> for Expr {
+    fn from(x: &SimpleExpr) -> Self {
+        let all = |x: &[SimpleExpr]| x.iter().map(Expr::from).collect();
+        Expr::new(match x {
+            SimpleExpr::Const(c) => Kind::Const(c.clone()),
+            SimpleExpr::Symbol(s) => Kind::Symbol(s.as_str().into()),
+            SimpleExpr::Product(x) => Kind::Product(all(x)),
+            SimpleExpr::Sum(x) => Kind::Sum(all(x)),
+            SimpleExpr::Pow(x) => Kind::Pow((&x.0).into(), (&x.1).into()),
+            SimpleExpr::Factorial(x) => Kind::Factorial((&**x).into()),
+            SimpleExpr::Function(name, args) => Kind::Function(name.as_str().into(), all(args)),
+            SimpleExpr::List(x) => Kind::List(all(x)),
+            SimpleExpr::Relation(op, x) => Kind::Relation(*op, (&x.0).into(), (&x.1).into()),
+        })
+    }
+}
+
+impl From<SimpleExpr> for Expr {
+    fn from(x: SimpleExpr) -> Self {
+        Expr::from(&x)
+    }
+}
+
+impl From<&Expr> for SimpleExpr {
+    fn from(x: &Expr) -> Self {
+        x.to_simple()
+    }
+}
@@ -3,22 +3,45 @@
 use constant::Constant;
 
 use num::BigInt;
+use relation::RelOp;
 use simplify::SimpleExpr;
 
+pub mod assumptions;
+pub mod calculus;
 mod cmp;
+pub mod codegen;
 pub mod constant;
+mod error;
 mod helpers;
+pub mod intern;
+pub mod limits;
+pub mod linear;
+pub mod matrix;
+pub mod numeric;
 pub mod parse;
+pub mod pattern;
+pub mod poly;
 pub mod print;
 mod rational_expressions;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod relation;
+pub mod session;
 pub mod simplify;
+pub mod solve;
+pub mod trace;
+pub mod visit;
 
-#[derive(Debug)]
-pub struct Undefined;
+pub use error::ComputeError;
 
-pub type ComputeResult<T = SimpleExpr> = Result<T, Undefined>;
+pub type ComputeResult<T = SimpleExpr> = Result<T, ComputeError>;
 
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", content = "args")
+)]
 pub enum BasicAlgebraicExpr {
     Const(Constant),
     Symbol(String),
@@ -28,6 +51,8 @@ pub enum BasicAlgebraicExpr {
     Pow(Box<(BasicAlgebraicExpr, BasicAlgebraicExpr)>),
     Factorial(Box<BasicAlgebraicExpr>),
     Function(String, Vec<BasicAlgebraicExpr>),
+    List(Vec<BasicAlgebraicExpr>),
+    Relation(RelOp, Box<(BasicAlgebraicExpr, BasicAlgebraicExpr)>),
 }
 
 impl From<i32> for SimpleExpr {
@@ -0,0 +1,148 @@
+// Limits on long-running computations: a budget of simplification steps, a maximum size for
+// computed integers, a deadline and a flag for cancelling from another thread. Limits are set
+// with `with_limits` and apply to everything computed inside it on the same thread.
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use num::{BigInt, Signed, ToPrimitive};
+
+use crate::constant::Constant;
+use crate::simplify::SimpleExpr;
+use crate::{ComputeError, ComputeResult};
+
+// powers needing more bits than this are never computed
+const MAX_POWER_BITS: u64 = 1 << 24;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Limit {
+    Steps,
+    Bits,
+    Deadline,
+    Cancelled,
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Limits {
+    steps: Option<u64>,
+    max_bits: Option<u64>,
+    deadline: Option<Instant>,
+    cancelled: Option<Arc<AtomicBool>>,
+}
+
+impl Limits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_steps(mut self, steps: u64) -> Self {
+        self.steps = Some(steps);
+        self
+    }
+
+    pub fn with_max_bits(mut self, bits: u64) -> Self {
+        self.max_bits = Some(bits);
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    // the computation stops once `flag` is set
+    pub fn with_cancellation(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancelled = Some(flag);
+        self
+    }
+}
+
+struct State {
+    limits: Limits,
+    steps: u64,
+}
+
+thread_local! {
+    static STATE: RefCell<Option<State>> = const { RefCell::new(None) };
+}
+
+// restores the enclosing limits when dropped, also when `f` panics
+struct Restore(Option<State>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let outer = self.0.take();
+        STATE.with(|x| x.replace(outer));
+    }
+}
+
+// runs `f` under `limits`, which replace any enclosing ones until `f` returns
+pub fn with_limits<T>(limits: Limits, f: impl FnOnce() -> T) -> T {
+    let state = State { limits, steps: 0 };
+    let _restore = Restore(STATE.with(|x| x.replace(Some(state))));
+    f()
+}
+
+fn exceeded(limit: Limit, expr: impl FnOnce() -> SimpleExpr) -> ComputeError {
+    ComputeError::LimitExceeded {
+        limit,
+        expr: expr(),
+    }
+}
+
+// counts a step of the computation of `expr`
+pub(crate) fn step(expr: impl FnOnce() -> SimpleExpr) -> ComputeResult<()> {
+    let limit = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let state = state.as_mut()?;
+        state.steps += 1;
+        let limits = &state.limits;
+        if limits.steps.is_some_and(|n| state.steps > n) {
+            Some(Limit::Steps)
+        } else if limits
+            .cancelled
+            .as_ref()
+            .is_some_and(|x| x.load(Ordering::Relaxed))
+        {
+            Some(Limit::Cancelled)
+        } else if limits.deadline.is_some_and(|x| Instant::now() > x) {
+            Some(Limit::Deadline)
+        } else {
+            None
+        }
+    });
+    match limit {
+        Some(limit) => Err(exceeded(limit, expr)),
+        None => Ok(()),
+    }
+}
+
+// checks that a result of about `bits` bits may be computed for `expr`
+pub(crate) fn check_bits(bits: u64, expr: impl FnOnce() -> SimpleExpr) -> ComputeResult<()> {
+    let max = STATE.with(|state| state.borrow().as_ref().and_then(|x| x.limits.max_bits));
+    if max.is_some_and(|max| bits > max) {
+        return Err(exceeded(Limit::Bits, expr));
+    }
+    Ok(())
+}
+
+// checks that `base^exp` may be computed
+pub(crate) fn check_power(base: &Constant, exp: &BigInt) -> ComputeResult<()> {
+    let power = || {
+        let exp = SimpleExpr::Const(exp.clone().into());
+        SimpleExpr::Pow(Box::new((base.clone().into(), exp)))
+    };
+    let bits = base.numer().bits().max(base.denom().bits());
+    if bits <= 1 {
+        return Ok(());
+    }
+    match exp.abs().to_u64().map(|e| e.saturating_mul(bits)) {
+        Some(total) if total <= MAX_POWER_BITS => check_bits(total, power),
+        _ => Err(ComputeError::Overflow(power())),
+    }
+}
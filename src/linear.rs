@@ -0,0 +1,379 @@
+// Systems of linear equations.
+//
+// Equations are turned into an augmented matrix which is brought to reduced row echelon
+// form with fraction-free (Bareiss) elimination. Rational systems are eliminated over
+// `Constant`, scaled to integer rows first so that every entry stays an integer.
+// Systems with symbolic coefficients are eliminated over `SimpleExpr`.
+use std::collections::{btree_map, BTreeMap};
+
+use num::{BigInt, One, ToPrimitive, Zero};
+
+use crate::constant::Constant;
+use crate::poly::{coefficients, together};
+use crate::rational_expressions::divided_by_zero;
+use crate::relation::RelOp;
+use crate::simplify::{Operation, Product, SimpleExpr, Sum};
+use crate::visit::Node;
+use crate::{BasicAlgebraicExpr, ComputeError, ComputeResult};
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum LinearSolution {
+    Inconsistent,
+    // Values of the solved variables, which may depend on the free variables.
+    // Free variables can take any value and have no entry in `values`.
+    Solved {
+        values: Vec<(String, SimpleExpr)>,
+        free: Vec<String>,
+    },
+}
+
+// entries that fraction-free elimination can work with
+pub(crate) trait Entry: Clone {
+    fn is_zero(&self) -> bool;
+    fn zero() -> Self;
+    fn one() -> Self;
+    // a * d - b * c
+    fn cross(a: &Self, b: &Self, c: &Self, d: &Self) -> ComputeResult<Self>;
+    // `self / divisor`, where the division is known to be exact
+    fn div_exact(self, divisor: &Self) -> ComputeResult<Self>;
+}
+
+impl Entry for Constant {
+    fn is_zero(&self) -> bool {
+        Zero::is_zero(self)
+    }
+
+    fn zero() -> Self {
+        Zero::zero()
+    }
+
+    fn one() -> Self {
+        One::one()
+    }
+
+    fn cross(a: &Self, b: &Self, c: &Self, d: &Self) -> ComputeResult<Self> {
+        Ok(a.clone() * d.clone() - b.clone() * c.clone())
+    }
+
+    fn div_exact(self, divisor: &Self) -> ComputeResult<Self> {
+        if Zero::is_zero(divisor) {
+            return Err(divided_by_zero(SimpleExpr::Const(self)));
+        }
+        Ok(self / divisor.clone())
+    }
+}
+
+impl Entry for SimpleExpr {
+    fn is_zero(&self) -> bool {
+        *self == 0
+    }
+
+    fn zero() -> Self {
+        0.into()
+    }
+
+    fn one() -> Self {
+        1.into()
+    }
+
+    fn cross(a: &Self, b: &Self, c: &Self, d: &Self) -> ComputeResult<Self> {
+        let ad = Product.simplify(vec![a.clone(), d.clone()])?;
+        let bc = Product.simplify(vec![b.clone(), c.clone()])?;
+        Sum.simplify(vec![ad, bc.negate()?])?.expand()
+    }
+
+    // Divides as polynomials in the symbols and other factors of the terms, so that sums
+    // cancel as well. Back substitution and row reduction also divide where the quotient is
+    // a fraction, which is cancelled if only one symbol occurs and kept as a product with
+    // the inverse otherwise.
+    fn div_exact(self, divisor: &Self) -> ComputeResult<Self> {
+        if divisor.is_zero() {
+            return Err(divided_by_zero(self));
+        }
+        let dividend = self.expand()?;
+        let divisor = divisor.clone().expand()?;
+        if let Some(quotient) = polynomial_quotient(&dividend, &divisor)? {
+            return Ok(quotient);
+        }
+        let inverse = BasicAlgebraicExpr::simplify_power(divisor, (-1).into())?;
+        let quotient = Product.simplify(vec![dividend, inverse])?;
+        let symbols = quotient.free_symbols();
+        if let [var] = Vec::from_iter(&symbols)[..]
+            && let Some((numerator, denominator)) = together(&quotient, var)
+        {
+            let denominator = denominator.to_expr(var)?;
+            let inverse = BasicAlgebraicExpr::simplify_power(denominator, (-1).into())?;
+            return Product.simplify(vec![numerator.to_expr(var)?, inverse]);
+        }
+        quotient.expand()
+    }
+}
+
+// a term as its coefficient and the integer powers of its other factors
+fn monomial(term: &SimpleExpr) -> (Constant, Vec<(SimpleExpr, i64)>) {
+    let factors = match term {
+        SimpleExpr::Product(factors) => factors.as_slice(),
+        x => std::slice::from_ref(x),
+    };
+    let mut coefficient = <Constant as One>::one();
+    let mut powers = Vec::new();
+    for factor in factors {
+        match factor {
+            SimpleExpr::Const(c) => coefficient = coefficient * c.clone(),
+            SimpleExpr::Pow(p)
+                if let SimpleExpr::Const(n) = &p.1
+                    && let Some(n) = n.as_integer().and_then(ToPrimitive::to_i64) =>
+            {
+                powers.push((p.0.clone(), n))
+            }
+            x => powers.push((x.clone(), 1)),
+        }
+    }
+    (coefficient, powers)
+}
+
+// Divides expanded expressions as polynomials whose variables are the non-constant factors
+// of their terms, cancelling the leading term in lexicographic order until nothing is left.
+// Returns `None` if the division does not come out even.
+fn polynomial_quotient(
+    dividend: &SimpleExpr,
+    divisor: &SimpleExpr,
+) -> ComputeResult<Option<SimpleExpr>> {
+    // exact quotients are found term by term, so this bounds the terms of the quotient
+    const MAX_STEPS: usize = 1000;
+
+    let terms = |x: &SimpleExpr| match x {
+        SimpleExpr::Sum(terms) => terms.iter().map(monomial).collect::<Vec<_>>(),
+        x => vec![monomial(x)],
+    };
+    let (dividend, divisor) = (terms(dividend), terms(divisor));
+    let mut atoms: Vec<SimpleExpr> = dividend
+        .iter()
+        .chain(&divisor)
+        .flat_map(|(_, powers)| powers.iter().map(|(x, _)| x.clone()))
+        .collect();
+    atoms.sort();
+    atoms.dedup();
+    // exponent vectors over `atoms`, ordered lexicographically
+    let polynomial = |terms: Vec<(Constant, Vec<(SimpleExpr, i64)>)>| {
+        let mut p = BTreeMap::new();
+        for (coefficient, powers) in terms {
+            let mut exponents = vec![0; atoms.len()];
+            for (x, n) in powers {
+                let i = atoms.binary_search(&x).expect("atoms of every term are collected");
+                exponents[i] += n;
+            }
+            add_term(&mut p, exponents, coefficient);
+        }
+        p
+    };
+    let mut rest = polynomial(dividend);
+    let divisor = polynomial(divisor);
+    let Some((lead, lead_coefficient)) = divisor.last_key_value() else {
+        return Ok(None);
+    };
+
+    let mut quotient = BTreeMap::new();
+    for _ in 0..MAX_STEPS {
+        let Some((exponents, coefficient)) = rest.last_key_value() else {
+            let terms = quotient
+                .into_iter()
+                .map(|(exponents, coefficient): (Vec<i64>, Constant)| {
+                    let mut factors = vec![SimpleExpr::Const(coefficient)];
+                    for (x, n) in atoms.iter().zip(exponents).filter(|(_, n)| *n != 0) {
+                        let n = SimpleExpr::from(i128::from(n));
+                        factors.push(BasicAlgebraicExpr::simplify_power(x.clone(), n)?);
+                    }
+                    Product.simplify(factors)
+                })
+                .collect::<ComputeResult<Vec<_>>>()?;
+            return Ok(Some(match terms.len() {
+                0 => 0.into(),
+                _ => Sum.simplify(terms)?,
+            }));
+        };
+        let q: Vec<i64> = exponents.iter().zip(lead).map(|(a, b)| a - b).collect();
+        let c = coefficient.clone() / lead_coefficient.clone();
+        for (exponents, coefficient) in &divisor {
+            let product = exponents.iter().zip(&q).map(|(a, b)| a + b).collect();
+            add_term(&mut rest, product, -(coefficient.clone() * c.clone()));
+        }
+        add_term(&mut quotient, q, c);
+    }
+    Ok(None)
+}
+
+fn add_term(p: &mut BTreeMap<Vec<i64>, Constant>, exponents: Vec<i64>, coefficient: Constant) {
+    match p.entry(exponents) {
+        btree_map::Entry::Vacant(entry) => {
+            entry.insert(coefficient);
+        }
+        btree_map::Entry::Occupied(mut entry) => {
+            let sum = entry.get().clone() + coefficient;
+            if Zero::is_zero(&sum) {
+                entry.remove();
+            } else {
+                entry.insert(sum);
+            }
+        }
+    }
+}
+
+// Fraction-free Gauss-Jordan elimination on the first `columns` columns. Every pivot
+// row ends up with the same pivot, the determinant of the pivot minor, and zeros in the
+// other pivot columns. Returns the pivot columns.
+pub(crate) fn fraction_free_rref<T: Entry>(
+    m: &mut [Vec<T>],
+    columns: usize,
+) -> ComputeResult<Vec<usize>> {
+    let mut pivots = Vec::new();
+    let mut previous = T::one();
+    let mut r = 0;
+    for c in 0..columns {
+        let Some(p) = (r..m.len()).find(|&i| !m[i][c].is_zero()) else {
+            continue;
+        };
+        m.swap(r, p);
+        for i in 0..m.len() {
+            if i == r {
+                continue;
+            }
+            for j in 0..m[i].len() {
+                if j == c {
+                    continue;
+                }
+                let entry = T::cross(&m[r][c], &m[i][c], &m[r][j], &m[i][j])?;
+                m[i][j] = entry.div_exact(&previous)?;
+            }
+            m[i][c] = T::zero();
+        }
+        previous = m[r][c].clone();
+        pivots.push(c);
+        r += 1;
+        if r == m.len() {
+            break;
+        }
+    }
+    Ok(pivots)
+}
+
+// splits an expanded expression into coefficients of `vars` and the remaining constant part
+fn linear_form(
+    expr: &SimpleExpr,
+    vars: &[&str],
+) -> ComputeResult<Option<(Vec<SimpleExpr>, SimpleExpr)>> {
+    let terms = match expr {
+        SimpleExpr::Sum(terms) => terms.as_slice(),
+        x => std::slice::from_ref(x),
+    };
+    let mut by_var = vec![Vec::new(); vars.len()];
+    let mut rest = Vec::new();
+    for term in terms {
+        let symbols = term.free_symbols();
+        let mut present = vars
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| symbols.contains(**v));
+        match (present.next(), present.next()) {
+            (None, _) => rest.push(term.clone()),
+            (Some((i, var)), None) => match coefficients(term, var)?.as_deref() {
+                Some([zero, coefficient]) if *zero == 0 => by_var[i].push(coefficient.clone()),
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        }
+    }
+    let sum = |x: Vec<SimpleExpr>| match x.len() {
+        0 => Ok(0.into()),
+        _ => Sum.simplify(x),
+    };
+    let coefficients = by_var.into_iter().map(sum).collect::<ComputeResult<_>>()?;
+    Ok(Some((coefficients, sum(rest)?)))
+}
+
+// the row scaled to integers
+pub(crate) fn integer_row(row: Vec<Constant>) -> Vec<Constant> {
+    let lcm = row.iter().fold(BigInt::one(), |acc, c| {
+        num::integer::lcm(acc, c.denom().clone())
+    });
+    row.into_iter()
+        .map(|c| c * Constant::from(lcm.clone()))
+        .collect()
+}
+
+// Solves a system of linear equations for `vars`. Equations may also be given as
+// expressions that equal zero. Fails if an equation is not linear in `vars`.
+pub fn solve_linear(equations: Vec<SimpleExpr>, vars: &[&str]) -> ComputeResult<LinearSolution> {
+    let mut rows = Vec::new();
+    for equation in equations {
+        let expr = match equation.to_one_side()? {
+            SimpleExpr::Relation(RelOp::Equal, sides) => sides.0,
+            SimpleExpr::Relation(op, sides) => {
+                return Err(ComputeError::Unsupported(SimpleExpr::Relation(op, sides)));
+            }
+            x => x,
+        };
+        let Some((mut row, constant)) = linear_form(&expr.clone().expand()?, vars)? else {
+            return Err(ComputeError::Unsupported(expr));
+        };
+        row.push(constant.negate()?);
+        rows.push(row);
+    }
+
+    let numeric = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|x| match x {
+                    SimpleExpr::Const(c) => Some(c.clone()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(integer_row)
+        })
+        .collect::<Option<Vec<_>>>();
+
+    match numeric {
+        Some(mut m) => {
+            let pivots = fraction_free_rref(&mut m, vars.len())?;
+            let m = m
+                .into_iter()
+                .map(|row| row.into_iter().map(SimpleExpr::Const).collect())
+                .collect();
+            back_substitute(m, &pivots, vars)
+        }
+        None => {
+            let pivots = fraction_free_rref(&mut rows, vars.len())?;
+            back_substitute(rows, &pivots, vars)
+        }
+    }
+}
+
+// reads the solution off a matrix in reduced row echelon form
+fn back_substitute(
+    m: Vec<Vec<SimpleExpr>>,
+    pivots: &[usize],
+    vars: &[&str],
+) -> ComputeResult<LinearSolution> {
+    let columns = vars.len();
+    // a zero row with a nonzero right hand side reads 0 = b
+    if m[pivots.len()..].iter().any(|row| !row[columns].is_zero()) {
+        return Ok(LinearSolution::Inconsistent);
+    }
+
+    let free: Vec<usize> = (0..columns).filter(|c| !pivots.contains(c)).collect();
+    let mut values = Vec::new();
+    for (row, &c) in m.iter().zip(pivots) {
+        let mut terms = vec![row[columns].clone()];
+        for &j in &free {
+            let x = SimpleExpr::Symbol(vars[j].into());
+            terms.push(Product.simplify(vec![row[j].clone(), x])?.negate()?);
+        }
+        let value = Sum.simplify(terms)?.div_exact(&row[c])?;
+        values.push((vars[c].to_string(), value));
+    }
+    Ok(LinearSolution::Solved {
+        values,
+        free: free.into_iter().map(|j| vars[j].to_string()).collect(),
+    })
+}
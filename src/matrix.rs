@@ -0,0 +1,352 @@
+// Matrices with exact entries.
+//
+// In expressions a matrix is a list of rows of equal length, e.g. `{{1, 2}, {3, 4}}`.
+// Matrices of rationals take fast paths over `Constant`. Symbolic entries are handled
+// with division-free algorithms where possible, so that results stay polynomial.
+use num::{One, Zero};
+
+use crate::constant::Constant;
+use crate::linear::{fraction_free_rref, integer_row, Entry};
+use crate::simplify::{Operation, Product, SimpleExpr, Sum};
+use crate::{BasicAlgebraicExpr, ComputeError, ComputeResult};
+
+mod eigen;
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    // row-major
+    entries: Vec<SimpleExpr>,
+}
+
+fn add(a: SimpleExpr, b: SimpleExpr) -> ComputeResult {
+    Sum.simplify(vec![a, b])
+}
+
+fn mul(a: SimpleExpr, b: SimpleExpr) -> ComputeResult {
+    Product.simplify(vec![a, b])?.expand()
+}
+
+impl Matrix {
+    pub fn new(rows: usize, cols: usize, entries: Vec<SimpleExpr>) -> Self {
+        assert_eq!(rows * cols, entries.len(), "wrong number of entries");
+        Matrix {
+            rows,
+            cols,
+            entries,
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let entries = (0..n * n)
+            .map(|i| if i / n == i % n { 1.into() } else { 0.into() })
+            .collect();
+        Self::new(n, n, entries)
+    }
+
+    // `None` unless all rows have the same length
+    pub fn from_rows(rows: Vec<Vec<SimpleExpr>>) -> Option<Self> {
+        let cols = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != cols) {
+            return None;
+        }
+        Some(Self::new(
+            rows.len(),
+            cols,
+            rows.into_iter().flatten().collect(),
+        ))
+    }
+
+    // reads a non-empty list of rows such as `{{1, 2}, {3, 4}}`
+    pub fn from_expr(expr: &SimpleExpr) -> Option<Self> {
+        let SimpleExpr::List(rows) = expr else {
+            return None;
+        };
+        let rows = rows
+            .iter()
+            .map(|row| match row {
+                SimpleExpr::List(row) if !row.is_empty() => Some(row.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if rows.is_empty() {
+            return None;
+        }
+        Self::from_rows(rows)
+    }
+
+    pub fn to_expr(&self) -> SimpleExpr {
+        SimpleExpr::List(self.to_rows().into_iter().map(SimpleExpr::List).collect())
+    }
+
+    pub fn to_rows(&self) -> Vec<Vec<SimpleExpr>> {
+        (0..self.rows).map(|i| self.row(i).to_vec()).collect()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> &SimpleExpr {
+        &self.entries[i * self.cols + j]
+    }
+
+    pub fn row(&self, i: usize) -> &[SimpleExpr] {
+        &self.entries[i * self.cols..(i + 1) * self.cols]
+    }
+
+    // the rows as rationals, if every entry is constant
+    fn constant_rows(&self) -> Option<Vec<Vec<Constant>>> {
+        (0..self.rows)
+            .map(|i| {
+                self.row(i)
+                    .iter()
+                    .map(|x| match x {
+                        SimpleExpr::Const(c) => Some(c.clone()),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn transpose(&self) -> Self {
+        let entries = (0..self.cols)
+            .flat_map(|j| (0..self.rows).map(move |i| (i, j)))
+            .map(|(i, j)| self.get(i, j).clone())
+            .collect();
+        Self::new(self.cols, self.rows, entries)
+    }
+
+    pub fn mul(&self, other: &Matrix) -> ComputeResult<Matrix> {
+        if self.cols != other.rows {
+            let dot = SimpleExpr::Function("Dot".into(), vec![self.to_expr(), other.to_expr()]);
+            return Err(ComputeError::Unsupported(dot));
+        }
+        let mut entries = Vec::with_capacity(self.rows * other.cols);
+        for i in 0..self.rows {
+            for j in 0..other.cols {
+                let terms = (0..self.cols)
+                    .map(|k| {
+                        Product.simplify(vec![self.get(i, k).clone(), other.get(k, j).clone()])
+                    })
+                    .collect::<ComputeResult<Vec<_>>>()?;
+                entries.push(match terms.len() {
+                    0 => 0.into(),
+                    _ => Sum.simplify(terms)?,
+                });
+            }
+        }
+        Ok(Self::new(self.rows, other.cols, entries))
+    }
+
+    // the matrix without row `i` and column `j`
+    pub fn minor(&self, i: usize, j: usize) -> Matrix {
+        let entries = (0..self.rows)
+            .filter(|&r| r != i)
+            .flat_map(|r| (0..self.cols).filter(|&c| c != j).map(move |c| (r, c)))
+            .map(|(r, c)| self.get(r, c).clone())
+            .collect();
+        Self::new(self.rows - 1, self.cols - 1, entries)
+    }
+
+    // Coefficients of the characteristic polynomial `det(xI - A)`, highest degree first,
+    // computed with Berkowitz's division-free algorithm.
+    pub fn characteristic_coefficients(&self) -> ComputeResult<Vec<SimpleExpr>> {
+        if !self.is_square() {
+            return Err(ComputeError::domain(
+                "CharacteristicPolynomial",
+                self.to_expr(),
+            ));
+        }
+        let mut p: Vec<SimpleExpr> = vec![1.into()];
+        for k in 0..self.rows {
+            // the leading k x k block A, the row R and column C next to it and the corner a
+            // give the Toeplitz column [1, -a, -RC, -RAC, -RA^2C, ..]
+            let mut toeplitz = vec![1.into(), self.get(k, k).clone().negate()?];
+            let mut column: Vec<SimpleExpr> = (0..k).map(|i| self.get(i, k).clone()).collect();
+            // the first k entries of row i times the column
+            let times_column = |i: usize, column: &[SimpleExpr]| {
+                let terms = self.row(i)[..k]
+                    .iter()
+                    .zip(column)
+                    .map(|(a, c)| mul(a.clone(), c.clone()))
+                    .collect::<ComputeResult<Vec<_>>>()?;
+                Sum.simplify(terms)
+            };
+            for _ in 0..k {
+                toeplitz.push(times_column(k, &column)?.negate()?);
+                column = (0..k)
+                    .map(|i| times_column(i, &column))
+                    .collect::<ComputeResult<_>>()?;
+            }
+
+            let mut next = Vec::with_capacity(p.len() + 1);
+            for i in 0..=p.len() {
+                let mut acc = SimpleExpr::from(0);
+                for j in 0..p.len().min(i + 1) {
+                    acc = add(acc, mul(toeplitz[i - j].clone(), p[j].clone())?)?;
+                }
+                next.push(acc);
+            }
+            p = next;
+        }
+        Ok(p)
+    }
+
+    pub fn det(&self) -> ComputeResult {
+        if !self.is_square() {
+            return Err(ComputeError::domain("Det", self.to_expr()));
+        }
+        if let Some(rows) = self.constant_rows() {
+            return Ok(SimpleExpr::Const(bareiss_det(rows)));
+        }
+        let constant_term = self
+            .characteristic_coefficients()?
+            .pop()
+            .expect("never empty");
+        // det(A) = (-1)^n det(0I - A)
+        match self.rows % 2 {
+            0 => Ok(constant_term),
+            _ => constant_term.negate(),
+        }
+    }
+
+    pub fn inverse(&self) -> ComputeResult<Matrix> {
+        // singular and non-square matrices have no inverse
+        let singular = || ComputeError::domain("Inverse", self.to_expr());
+        if !self.is_square() {
+            return Err(singular());
+        }
+        let n = self.rows;
+        if let Some(rows) = self.constant_rows() {
+            // Gauss-Jordan on [A | I]
+            let mut m: Vec<Vec<Constant>> = rows
+                .into_iter()
+                .enumerate()
+                .map(|(i, mut row)| {
+                    row.extend((0..n).map(|j| if i == j { One::one() } else { Zero::zero() }));
+                    row
+                })
+                .collect();
+            if fraction_free_rref(&mut m, n)?.len() < n {
+                return Err(singular());
+            }
+            let entries = m
+                .into_iter()
+                .enumerate()
+                .flat_map(|(i, row)| {
+                    let pivot = row[i].clone();
+                    row.into_iter()
+                        .skip(n)
+                        .map(move |x| SimpleExpr::Const(x / pivot.clone()))
+                })
+                .collect();
+            return Ok(Self::new(n, n, entries));
+        }
+
+        // the adjugate divided by the determinant
+        let det = self.det()?;
+        if det == 0 {
+            return Err(singular());
+        }
+        let inverse = BasicAlgebraicExpr::simplify_power(det, (-1).into())?;
+        let mut entries = Vec::with_capacity(n * n);
+        for i in 0..n {
+            for j in 0..n {
+                let cofactor = self.minor(j, i).det()?;
+                let cofactor = if (i + j) % 2 == 0 {
+                    cofactor
+                } else {
+                    cofactor.negate()?
+                };
+                entries.push(Product.simplify(vec![cofactor, inverse.clone()])?);
+            }
+        }
+        Ok(Self::new(n, n, entries))
+    }
+
+    // the fraction-free echelon form and its pivot columns
+    fn echelon(&self) -> ComputeResult<(Vec<Vec<SimpleExpr>>, Vec<usize>)> {
+        if let Some(rows) = self.constant_rows() {
+            let mut m: Vec<_> = rows.into_iter().map(integer_row).collect();
+            let pivots = fraction_free_rref(&mut m, self.cols)?;
+            let m = m
+                .into_iter()
+                .map(|row| row.into_iter().map(SimpleExpr::Const).collect())
+                .collect();
+            return Ok((m, pivots));
+        }
+        let mut m = self.to_rows();
+        let pivots = fraction_free_rref(&mut m, self.cols)?;
+        Ok((m, pivots))
+    }
+
+    pub fn rank(&self) -> ComputeResult<usize> {
+        Ok(self.echelon()?.1.len())
+    }
+
+    // reduced row echelon form
+    pub fn rref(&self) -> ComputeResult<Matrix> {
+        let (mut m, pivots) = self.echelon()?;
+        for (row, &c) in m.iter_mut().zip(&pivots) {
+            let pivot = row[c].clone();
+            for x in row.iter_mut() {
+                *x = x.clone().div_exact(&pivot)?;
+            }
+        }
+        Ok(Self::from_rows(m).expect("rows keep their length"))
+    }
+
+    // a basis of the vectors `v` with `Av = 0`
+    pub fn nullspace(&self) -> ComputeResult<Vec<Vec<SimpleExpr>>> {
+        let rref = self.rref()?;
+        let (_, pivots) = self.echelon()?;
+        let mut basis = Vec::new();
+        for free in (0..self.cols).filter(|c| !pivots.contains(c)) {
+            let mut v = vec![SimpleExpr::from(0); self.cols];
+            v[free] = 1.into();
+            for (k, &c) in pivots.iter().enumerate() {
+                v[c] = rref.get(k, free).clone().negate()?;
+            }
+            basis.push(v);
+        }
+        Ok(basis)
+    }
+}
+
+// Bareiss' fraction-free determinant. Every intermediate entry is a minor of the input.
+fn bareiss_det(mut m: Vec<Vec<Constant>>) -> Constant {
+    let n = m.len();
+    let mut sign: Constant = One::one();
+    let mut previous: Constant = One::one();
+    for k in 0..n {
+        if Zero::is_zero(&m[k][k]) {
+            let Some(p) = (k + 1..n).find(|&i| !Zero::is_zero(&m[i][k])) else {
+                return Zero::zero();
+            };
+            m.swap(k, p);
+            sign = -sign;
+        }
+        for i in k + 1..n {
+            for j in k + 1..n {
+                let entry = m[i][j].clone() * m[k][k].clone() - m[i][k].clone() * m[k][j].clone();
+                m[i][j] = entry / previous.clone();
+            }
+        }
+        previous = m[k][k].clone();
+    }
+    match n {
+        0 => One::one(),
+        _ => sign * m[n - 1][n - 1].clone(),
+    }
+}
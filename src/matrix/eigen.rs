@@ -0,0 +1,99 @@
+use num::{Integer, Signed};
+
+use super::Matrix;
+use crate::simplify::{Operation, Product, SimpleExpr, Sum};
+use crate::solve::{solve, Solution};
+use crate::visit::Node;
+use crate::{BasicAlgebraicExpr, ComputeError, ComputeResult};
+
+// false when `x` takes an even root of a negative number, as in `(-4)^(1/2)`
+fn is_real(x: &SimpleExpr) -> bool {
+    !x.pre_order().any(|x| match x {
+        SimpleExpr::Pow(p) => match &**p {
+            (SimpleExpr::Const(base), SimpleExpr::Const(exp)) => {
+                base.is_negative() && exp.denom().is_even()
+            }
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+impl Matrix {
+    // a symbol that does not occur in any entry
+    fn fresh_symbol(&self) -> String {
+        let used: Vec<_> = self.entries.iter().flat_map(|x| x.free_symbols()).collect();
+        std::iter::once("x".to_string())
+            .chain((1..).map(|i| format!("x{i}")))
+            .find(|x| !used.contains(&x.as_str()))
+            .expect("infinitely many candidates")
+    }
+
+    // `det(A - xI)`, the same convention as Mathematica's `CharacteristicPolynomial`
+    pub fn characteristic_polynomial(&self, var: &str) -> ComputeResult {
+        let coefficients = self.characteristic_coefficients()?;
+        let x = SimpleExpr::Symbol(var.into());
+        let degree = coefficients.len() - 1;
+        // det(A - xI) = (-1)^n det(xI - A)
+        let sign: SimpleExpr = if degree % 2 == 0 {
+            1.into()
+        } else {
+            (-1).into()
+        };
+        let terms = coefficients
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let power =
+                    BasicAlgebraicExpr::simplify_power(x.clone(), ((degree - i) as i128).into())?;
+                Product.simplify(vec![sign.clone(), c, power])
+            })
+            .collect::<ComputeResult<Vec<_>>>()?;
+        Sum.simplify(terms)?.expand()
+    }
+
+    // Eigenvalues with their algebraic multiplicity. They are exact when the characteristic
+    // polynomial can be solved, see `solve`. Complex eigenvalues are not supported.
+    pub fn eigenvalues(&self) -> ComputeResult<Vec<Solution>> {
+        let var = self.fresh_symbol();
+        let eigenvalues = solve(self.characteristic_polynomial(&var)?, &var)?;
+        if let Some(complex) = eigenvalues.iter().find(|x| !is_real(&x.value)) {
+            return Err(ComputeError::Unsupported(complex.value.clone()));
+        }
+        Ok(eigenvalues)
+    }
+
+    // Each eigenvalue with a basis of its eigenspace, the nullspace of `A - λI`.
+    pub fn eigenvectors(&self) -> ComputeResult<Vec<(SimpleExpr, Vec<Vec<SimpleExpr>>)>> {
+        let n = self.rows;
+        let eigenvalues = self.eigenvalues()?;
+        // `Root[p, k]` placeholders cannot be substituted
+        let is_placeholder =
+            |x: &SimpleExpr| matches!(x, SimpleExpr::Function(name, _) if name == "Root");
+        if let Some(root) = eigenvalues
+            .iter()
+            .find(|x| x.value.pre_order().any(is_placeholder))
+        {
+            return Err(ComputeError::Unsupported(root.value.clone()));
+        }
+        eigenvalues
+            .into_iter()
+            .map(|eigenvalue| {
+                let negated = eigenvalue.value.clone().negate()?;
+                let mut shifted = self.clone();
+                for i in 0..n {
+                    let entry = &mut shifted.entries[i * n + i];
+                    *entry = Sum
+                        .simplify(vec![entry.clone(), negated.clone()])?
+                        .expand()?;
+                }
+                let basis = shifted.nullspace()?;
+                // the eigenvalue was not exact enough to find its eigenspace
+                if basis.is_empty() {
+                    return Err(ComputeError::Unsupported(eigenvalue.value));
+                }
+                Ok((eigenvalue.value, basis))
+            })
+            .collect()
+    }
+}
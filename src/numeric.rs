@@ -0,0 +1,228 @@
+// Floating point evaluation, used where no exact result is available.
+use num::{BigInt, BigRational, ToPrimitive};
+
+use crate::constant::Constant;
+use crate::limits;
+use crate::simplify::SimpleExpr;
+use crate::ComputeResult;
+
+// Evaluates `expr` with `env` giving the values of symbols. `Pi` and `E` are known.
+// Returns `None` for unknown symbols and functions or a result that is not a real number.
+pub fn approximate(
+    expr: &SimpleExpr,
+    env: &dyn Fn(&str) -> Option<f64>,
+) -> ComputeResult<Option<f64>> {
+    let value = match expr {
+        SimpleExpr::Const(c) => c.to_f64(),
+        SimpleExpr::Symbol(s) => match env(s) {
+            Some(x) => Some(x),
+            None if s == "Pi" => Some(std::f64::consts::PI),
+            None if s == "E" => Some(std::f64::consts::E),
+            None => None,
+        },
+        SimpleExpr::Sum(x) => x
+            .iter()
+            .map(|x| approximate(x, env))
+            .sum::<ComputeResult<_>>()?,
+        SimpleExpr::Product(x) => x
+            .iter()
+            .map(|x| approximate(x, env))
+            .product::<ComputeResult<_>>()?,
+        SimpleExpr::Pow(x) => {
+            let Some(base) = approximate(&x.0, env)? else {
+                return Ok(None);
+            };
+            match &x.1 {
+                SimpleExpr::Const(n) if let Some(n) = n.as_integer() => {
+                    n.to_i32().map(|n| base.powi(n))
+                }
+                exponent => approximate(exponent, env)?.map(|x| base.powf(x)),
+            }
+        }
+        // larger factorials overflow
+        SimpleExpr::Factorial(x) => match approximate(x, env)? {
+            Some(n) if (0.0..=170.0).contains(&n) && n.fract() == 0.0 => {
+                Some((1..=n as u64).map(|x| x as f64).product())
+            }
+            _ => None,
+        },
+        SimpleExpr::Function(name, args)
+            if let [f, SimpleExpr::List(spec)] = args.as_slice()
+                && let [SimpleExpr::Symbol(k), a, b] = spec.as_slice() =>
+        {
+            iterate(expr, name, f, k, (a, b), env)?
+        }
+        SimpleExpr::Function(name, args) if let [x] = args.as_slice() => {
+            let Some(x) = approximate(x, env)? else {
+                return Ok(None);
+            };
+            match name.as_str() {
+                "Exp" => Some(x.exp()),
+                "Log" => Some(x.ln()),
+                "Sqrt" => Some(x.sqrt()),
+                "Sin" => Some(x.sin()),
+                "Cos" => Some(x.cos()),
+                "Tan" => Some(x.tan()),
+                "Sinh" => Some(x.sinh()),
+                "Cosh" => Some(x.cosh()),
+                "Tanh" => Some(x.tanh()),
+                "ArcSin" => Some(x.asin()),
+                "ArcCos" => Some(x.acos()),
+                "ArcTan" => Some(x.atan()),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    Ok(value.filter(|x| x.is_finite()))
+}
+
+const RELATIVE_TOLERANCE: f64 = 1e-10;
+const MAX_EVALUATIONS: usize = 100_000;
+
+// Adaptive Simpson's rule, to a tolerance relative to the first estimate. Returns `None` when
+// `f` is not defined on `[a, b]` or does not converge within `MAX_EVALUATIONS`. Evaluations
+// count as steps of computing `expr`.
+pub fn simpson(
+    f: &dyn Fn(f64) -> ComputeResult<Option<f64>>,
+    a: f64,
+    b: f64,
+    expr: &SimpleExpr,
+) -> ComputeResult<Option<f64>> {
+    struct Quadrature<'a> {
+        f: &'a dyn Fn(f64) -> ComputeResult<Option<f64>>,
+        expr: &'a SimpleExpr,
+        evaluations: usize,
+    }
+
+    impl Quadrature<'_> {
+        fn eval(&mut self, x: f64) -> ComputeResult<Option<f64>> {
+            limits::step(|| self.expr.clone())?;
+            self.evaluations += 1;
+            if self.evaluations > MAX_EVALUATIONS {
+                return Ok(None);
+            }
+            (self.f)(x)
+        }
+
+        fn step(
+            &mut self,
+            (a, fa): (f64, f64),
+            (m, fm): (f64, f64),
+            (b, fb): (f64, f64),
+            whole: f64,
+            tolerance: f64,
+            depth: usize,
+        ) -> ComputeResult<Option<f64>> {
+            let (lm, rm) = ((a + m) / 2.0, (m + b) / 2.0);
+            let (Some(flm), Some(frm)) = (self.eval(lm)?, self.eval(rm)?) else {
+                return Ok(None);
+            };
+            let left = (m - a) / 6.0 * (fa + 4.0 * flm + fm);
+            let right = (b - m) / 6.0 * (fm + 4.0 * frm + fb);
+            let error = left + right - whole;
+            if depth == 0 || error.abs() <= 15.0 * tolerance {
+                return Ok(Some(left + right + error / 15.0));
+            }
+            let tolerance = tolerance / 2.0;
+            let Some(left) = self.step((a, fa), (lm, flm), (m, fm), left, tolerance, depth - 1)?
+            else {
+                return Ok(None);
+            };
+            let right = self.step((m, fm), (rm, frm), (b, fb), right, tolerance, depth - 1)?;
+            Ok(right.map(|right| left + right))
+        }
+    }
+
+    let mut quadrature = Quadrature {
+        f,
+        expr,
+        evaluations: 0,
+    };
+    let m = (a + b) / 2.0;
+    let (Some(fa), Some(fm), Some(fb)) = (
+        quadrature.eval(a)?,
+        quadrature.eval(m)?,
+        quadrature.eval(b)?,
+    ) else {
+        return Ok(None);
+    };
+    let whole = (b - a) / 6.0 * (fa + 4.0 * fm + fb);
+    // with a floor for integrals close to zero
+    let tolerance = (RELATIVE_TOLERANCE * whole.abs()).max(f64::EPSILON);
+    let value = quadrature.step((a, fa), (m, fm), (b, fb), whole, tolerance, 50)?;
+    Ok(value.filter(|x| x.is_finite()))
+}
+
+// `Sum[f, {k, a, b}]` and `Product[f, {k, a, b}]`, given as `expr`. Sums to `Infinity` are
+// added up until the terms no longer change the result, or else up to `n` with the rest
+// estimated by the integral of `f` from `n + 1/2`. Each term counts as a step.
+fn iterate(
+    expr: &SimpleExpr,
+    name: &str,
+    f: &SimpleExpr,
+    k: &str,
+    (a, b): (&SimpleExpr, &SimpleExpr),
+    env: &dyn Fn(&str) -> Option<f64>,
+) -> ComputeResult<Option<f64>> {
+    const MAX_TERMS: f64 = 1e6;
+    const MAX_TAIL_TERMS: f64 = 1e4;
+    let term = |j: f64| {
+        limits::step(|| expr.clone())?;
+        approximate(f, &|s| if s == k { Some(j) } else { env(s) })
+    };
+    let Some(a) = approximate(a, env)?.filter(|a| a.fract() == 0.0) else {
+        return Ok(None);
+    };
+    if name == "Sum" && *b == SimpleExpr::infinity() {
+        let (mut sum, mut small) = (0f64, 0);
+        let mut j = a;
+        while j - a < MAX_TAIL_TERMS {
+            let Some(x) = term(j)? else {
+                return Ok(None);
+            };
+            small = if x.abs() <= f64::EPSILON * sum.abs() { small + 1 } else { 0 };
+            sum += x;
+            // a few negligible terms in a row
+            if small == 10 {
+                return Ok(Some(sum));
+            }
+            j += 1.0;
+        }
+        // with x = c / t
+        let c = j - 0.5;
+        let tail = |t: f64| Ok(term(c / t)?.map(|x| x * c / (t * t)));
+        return Ok(simpson(&tail, 1e-12, 1.0, expr)?.map(|tail| sum + tail));
+    }
+    let Some(b) = approximate(b, env)?.map(f64::floor) else {
+        return Ok(None);
+    };
+    if b - a >= MAX_TERMS {
+        return Ok(None);
+    }
+    let terms = (0..=(b - a).max(-1.0) as i64).map(|i| term(a + i as f64));
+    match name {
+        "Sum" => terms.sum(),
+        "Product" => terms.product(),
+        _ => Ok(None),
+    }
+}
+
+// the value of an expression without free symbols
+pub fn to_f64(expr: &SimpleExpr) -> ComputeResult<Option<f64>> {
+    approximate(expr, &|_| None)
+}
+
+// A rational close to `x`, rounded to 12 significant digits.
+pub fn rationalize(x: f64) -> Option<Constant> {
+    if !x.is_finite() {
+        return None;
+    }
+    if x == 0.0 {
+        return Some(0.into());
+    }
+    let digits = 11 - x.abs().log10().floor() as i32;
+    let scale = BigRational::from(BigInt::from(10)).pow(digits);
+    let x = BigRational::from_float(x)?;
+    Some(Constant::from((x * &scale).round() / scale))
+}
@@ -0,0 +1,92 @@
+// Mathematica's FullForm, as printed by `print::to_full_form`. Heads such as `Plus` and `Power`
+// become the matching operations and any other head is kept as a function call.
+use chumsky::prelude::*;
+use num::Zero;
+
+use super::{parse_with, Token};
+use crate::constant::Constant;
+use crate::relation::RelOp;
+use crate::BasicAlgebraicExpr as Expr;
+
+fn apply_head(head: String, args: Vec<Expr>) -> Result<Expr, String> {
+    let arity = |n: usize| match args.len() == n {
+        true => Ok(()),
+        false => Err(format!(
+            "{head} expects {n} arguments, found {}",
+            args.len()
+        )),
+    };
+    Ok(match head.as_str() {
+        // the empty sum and product
+        "Plus" if args.is_empty() => Expr::Const(0.into()),
+        "Times" if args.is_empty() => Expr::Const(1.into()),
+        "Plus" => Expr::Sum(args),
+        "Times" => Expr::Product(args),
+        "List" => Expr::List(args),
+        "Power" => {
+            arity(2)?;
+            let [base, exp] = <[Expr; 2]>::try_from(args).expect("arity was checked");
+            Expr::Pow(Box::new((base, exp)))
+        }
+        "Factorial" => {
+            arity(1)?;
+            Expr::Factorial(Box::new(
+                args.into_iter().next().expect("arity was checked"),
+            ))
+        }
+        "Rational" => match args.as_slice() {
+            [Expr::Const(n), Expr::Const(d)]
+                if n.is_integer() && d.is_integer() && !d.is_zero() =>
+            {
+                Expr::Const(n.clone() / d.clone())
+            }
+            _ => {
+                return Err(
+                    "Rational expects an integer numerator and a nonzero denominator".into(),
+                )
+            }
+        },
+        name if let Some(op) = RelOp::from_name(name) => {
+            arity(2)?;
+            let [lhs, rhs] = <[Expr; 2]>::try_from(args).expect("arity was checked");
+            Expr::Relation(op, Box::new((lhs, rhs)))
+        }
+        _ => Expr::Function(head, args),
+    })
+}
+
+fn full_form() -> impl Parser<Token, Expr, Error = Simple<Token>> + Clone {
+    recursive(|expr| {
+        let int = just(Token::Sub).or_not().then(filter_map(|sp, x| match x {
+            Token::Number(n) => Ok(n),
+            _ => Err(Simple::custom(sp, "expected number")),
+        }));
+
+        let symbol = filter_map(|sp, x| match x {
+            Token::Symbol(s) => Ok(s),
+            _ => Err(Simple::custom(sp, "expected symbol")),
+        });
+
+        // a bare symbol, or a head applied to arguments as in `Plus[x, 1]`
+        let call = symbol
+            .then(
+                expr.separated_by(just(Token::Comma))
+                    .delimited_by(just(Token::LeftBr), just(Token::RightBr))
+                    .or_not(),
+            )
+            .try_map(|(head, args), span| match args {
+                Some(args) => apply_head(head, args).map_err(|msg| Simple::custom(span, msg)),
+                None => Ok(Expr::Symbol(head)),
+            });
+
+        int.map(|(neg, n)| {
+            let n = Constant::from(n);
+            Expr::Const(if neg.is_some() { -n } else { n })
+        })
+        .or(call)
+    })
+}
+
+pub fn parse_full_form(s: &str) -> Result<Expr, Simple<Token>> {
+    parse_with(full_form().then_ignore(end()), s)
+}
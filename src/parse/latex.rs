@@ -0,0 +1,408 @@
+// LaTeX input, for formulas pasted from papers such as `\frac{x^2+1}{2} \cdot \sqrt{y}`.
+//
+// Single letters are variables and juxtaposition is multiplication, so `2xy` is `2 x y`.
+// Superscripts, subscripts and the arguments of `\frac` take either a braced group or a
+// single token, where a single digit is a token as in TeX: `x^23` is `x^2 3`. `\sqrt` is
+// parsed as a power, named functions such as `\sin` and `\log` take either a parenthesized
+// argument or a single factor, and Greek letters become symbols named by their unicode
+// characters, with `\pi` as `Pi`.
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::CharIndices;
+
+use chumsky::prelude::*;
+use chumsky::Stream;
+use num::BigInt;
+
+use crate::relation::RelOp;
+use crate::BasicAlgebraicExpr as Expr;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum LatexToken {
+    // a single digit, adjacent digits form a number
+    Digit(u8),
+    Letter(char),
+    // a control sequence such as `\frac`, without the backslash
+    Command(String),
+    LeftBrace,
+    RightBrace,
+    LeftParen,
+    RightParen,
+    LeftBr,
+    RightBr,
+    // `\{` and `\}`, which delimit lists
+    LeftSet,
+    RightSet,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    Subscript,
+    Factorial,
+    Comma,
+    Relation(RelOp),
+    Unknown(char),
+}
+
+const GREEK: &[(&str, char)] = &[
+    ("alpha", 'α'),
+    ("beta", 'β'),
+    ("gamma", 'γ'),
+    ("delta", 'δ'),
+    ("epsilon", 'ϵ'),
+    ("varepsilon", 'ε'),
+    ("zeta", 'ζ'),
+    ("eta", 'η'),
+    ("theta", 'θ'),
+    ("vartheta", 'ϑ'),
+    ("iota", 'ι'),
+    ("kappa", 'κ'),
+    ("lambda", 'λ'),
+    ("mu", 'μ'),
+    ("nu", 'ν'),
+    ("xi", 'ξ'),
+    ("rho", 'ρ'),
+    ("sigma", 'σ'),
+    ("tau", 'τ'),
+    ("upsilon", 'υ'),
+    ("phi", 'ϕ'),
+    ("varphi", 'φ'),
+    ("chi", 'χ'),
+    ("psi", 'ψ'),
+    ("omega", 'ω'),
+    ("Gamma", 'Γ'),
+    ("Delta", 'Δ'),
+    ("Theta", 'Θ'),
+    ("Lambda", 'Λ'),
+    ("Xi", 'Ξ'),
+    ("Pi", 'Π'),
+    ("Sigma", 'Σ'),
+    ("Upsilon", 'Υ'),
+    ("Phi", 'Φ'),
+    ("Psi", 'Ψ'),
+    ("Omega", 'Ω'),
+];
+
+const FUNCTIONS: &[(&str, &str)] = &[
+    ("sin", "Sin"),
+    ("cos", "Cos"),
+    ("tan", "Tan"),
+    ("cot", "Cot"),
+    ("sec", "Sec"),
+    ("csc", "Csc"),
+    ("arcsin", "ArcSin"),
+    ("arccos", "ArcCos"),
+    ("arctan", "ArcTan"),
+    ("sinh", "Sinh"),
+    ("cosh", "Cosh"),
+    ("tanh", "Tanh"),
+    ("exp", "Exp"),
+    ("log", "Log"),
+    ("ln", "Log"),
+];
+
+struct LatexTokenizer<'a> {
+    s: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> LatexTokenizer<'a> {
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.s.len(), |&(i, _)| i)
+    }
+
+    fn advance_while(&mut self, f: impl Fn(char) -> bool) -> usize {
+        while self.chars.next_if(|&(_, ch)| f(ch)).is_some() {}
+        self.offset()
+    }
+
+    fn command(&mut self, start: usize) -> Option<LatexToken> {
+        let Some((_, first)) = self.chars.next() else {
+            return Some(LatexToken::Unknown('\\'));
+        };
+        if !first.is_ascii_alphabetic() {
+            return match first {
+                '{' => Some(LatexToken::LeftSet),
+                '}' => Some(LatexToken::RightSet),
+                // spacing such as `\,` or `\ `
+                ',' | ';' | ':' | '!' | ' ' => None,
+                x => Some(LatexToken::Unknown(x)),
+            };
+        }
+        let end = self.advance_while(|ch| ch.is_ascii_alphabetic());
+        Some(match &self.s[start + 1..end] {
+            // `\left(` and `\right)` are plain parentheses
+            "left" | "right" | "quad" | "qquad" | "displaystyle" => return None,
+            "cdot" | "times" => LatexToken::Mul,
+            "div" => LatexToken::Div,
+            "le" | "leq" => LatexToken::Relation(RelOp::LessEqual),
+            "ge" | "geq" => LatexToken::Relation(RelOp::GreaterEqual),
+            "ne" | "neq" => LatexToken::Relation(RelOp::NotEqual),
+            name => LatexToken::Command(name.to_string()),
+        })
+    }
+
+    fn scan_tokens(mut self) -> Vec<(LatexToken, Range<usize>)> {
+        let mut tokens = Vec::new();
+        while let Some((start, ch)) = self.chars.next() {
+            let token = match ch {
+                ch if ch.is_whitespace() => None,
+                '\\' => self.command(start),
+                '{' => Some(LatexToken::LeftBrace),
+                '}' => Some(LatexToken::RightBrace),
+                '(' => Some(LatexToken::LeftParen),
+                ')' => Some(LatexToken::RightParen),
+                '[' => Some(LatexToken::LeftBr),
+                ']' => Some(LatexToken::RightBr),
+                '+' => Some(LatexToken::Add),
+                '-' => Some(LatexToken::Sub),
+                '*' => Some(LatexToken::Mul),
+                '/' => Some(LatexToken::Div),
+                '^' => Some(LatexToken::Pow),
+                '_' => Some(LatexToken::Subscript),
+                '!' => Some(LatexToken::Factorial),
+                ',' => Some(LatexToken::Comma),
+                '=' => Some(LatexToken::Relation(RelOp::Equal)),
+                '<' => Some(LatexToken::Relation(RelOp::Less)),
+                '>' => Some(LatexToken::Relation(RelOp::Greater)),
+                ch if let Some(d) = ch.to_digit(10) => Some(LatexToken::Digit(d as u8)),
+                ch if ch.is_ascii_alphabetic() => Some(LatexToken::Letter(ch)),
+                ch => Some(LatexToken::Unknown(ch)),
+            };
+            if let Some(token) = token {
+                tokens.push((token, start..self.offset()));
+            }
+        }
+        tokens
+    }
+}
+
+fn latex_expression() -> impl Parser<LatexToken, Expr, Error = Simple<LatexToken>> + Clone {
+    fn add(a: Expr, b: Expr) -> Expr {
+        Expr::Sum(vec![a, b])
+    }
+
+    fn sub(a: Expr, b: Expr) -> Expr {
+        Expr::Sum(vec![a, Expr::Neg(Box::new(b))])
+    }
+
+    fn div(a: Expr, b: Expr) -> Expr {
+        Expr::Product(vec![a, Expr::Pow(Box::new((b, Expr::Const((-1).into()))))])
+    }
+
+    fn mul(a: Expr, b: Expr) -> Expr {
+        Expr::Product(vec![a, b])
+    }
+
+    fn pow(a: Expr, b: Expr) -> Expr {
+        Expr::Pow(Box::new((a, b)))
+    }
+
+    // the error for a token that cannot start or continue an expression here
+    fn unexpected(span: Range<usize>, x: LatexToken) -> Simple<LatexToken> {
+        Simple::expected_input_found(span, None, Some(x))
+    }
+
+    fn command(
+        name: &'static str,
+    ) -> impl Parser<LatexToken, LatexToken, Error = Simple<LatexToken>> + Clone {
+        just(LatexToken::Command(name.into()))
+    }
+
+    recursive(|expr| {
+        let group = expr
+            .clone()
+            .delimited_by(just(LatexToken::LeftBrace), just(LatexToken::RightBrace));
+
+        let symbol = filter_map(|sp, x| match x {
+            LatexToken::Letter(c) => Ok(Expr::Symbol(c.into())),
+            LatexToken::Command(name) if name == "pi" => Ok(Expr::Symbol("Pi".into())),
+            LatexToken::Command(name) if name == "infty" => Ok(Expr::Symbol("Infinity".into())),
+            LatexToken::Command(name)
+                if let Some((_, c)) = GREEK.iter().find(|(n, _)| *n == name) =>
+            {
+                Ok(Expr::Symbol(c.to_string()))
+            }
+            x => Err(unexpected(sp, x)),
+        });
+
+        let digit = filter_map(|sp, x| match x {
+            LatexToken::Digit(d) => Ok(BigInt::from(d)),
+            x => Err(unexpected(sp, x)),
+        });
+
+        let number = digit
+            .repeated()
+            .at_least(1)
+            .map(|digits| {
+                let n = digits.into_iter().fold(BigInt::from(0), |n, d| n * 10 + d);
+                Expr::Const(n.into())
+            });
+
+        // the operand of `^`, `_` or `\frac`, either a group or a single token as in `x^2`
+        let script = group
+            .clone()
+            .or(digit.map(|d| Expr::Const(d.into())))
+            .or(symbol);
+
+        // `x_{1}` is `Subscript[x, 1]`
+        let variable = symbol
+            .then(
+                just(LatexToken::Subscript)
+                    .ignore_then(script.clone())
+                    .or_not(),
+            )
+            .map(|(x, sub)| match sub {
+                Some(sub) => Expr::Function("Subscript".into(), vec![x, sub]),
+                None => x,
+            });
+
+        let parens = expr
+            .clone()
+            .delimited_by(just(LatexToken::LeftParen), just(LatexToken::RightParen))
+            .or(expr
+                .clone()
+                .delimited_by(just(LatexToken::LeftBr), just(LatexToken::RightBr)));
+
+        let list = expr
+            .clone()
+            .separated_by(just(LatexToken::Comma))
+            .delimited_by(just(LatexToken::LeftSet), just(LatexToken::RightSet))
+            .map(Expr::List);
+
+        let frac = command("frac")
+            .or(command("dfrac"))
+            .or(command("tfrac"))
+            .ignore_then(script.clone())
+            .then(script.clone())
+            .map(|(a, b)| div(a, b));
+
+        // `\sqrt[n]{x}` is `x^(1/n)`
+        let sqrt = command("sqrt")
+            .ignore_then(
+                expr.clone()
+                    .delimited_by(just(LatexToken::LeftBr), just(LatexToken::RightBr))
+                    .or_not(),
+            )
+            .then(script.clone())
+            .map(|(n, x)| {
+                let n = n.unwrap_or_else(|| Expr::Const(2.into()));
+                pow(x, div(Expr::Const(1.into()), n))
+            });
+
+        let function_name = filter_map(|sp, x| match x {
+            LatexToken::Command(name)
+                if let Some((_, f)) = FUNCTIONS.iter().find(|(n, _)| *n == name) =>
+            {
+                Ok(f.to_string())
+            }
+            x => Err(unexpected(sp, x)),
+        });
+
+        // `\sin^2 x` is `Sin[x]^2` and `\log_2 x` is `Log[2, x]`
+        let function = function_name
+            .then(
+                just(LatexToken::Subscript)
+                    .ignore_then(script.clone())
+                    .or_not(),
+            )
+            .then(just(LatexToken::Pow).ignore_then(script.clone()).or_not())
+            .then(
+                parens.clone().or(script
+                    .clone()
+                    .then(just(LatexToken::Pow).ignore_then(script.clone()).or_not())
+                    .map(|(x, exp)| match exp {
+                        Some(exp) => pow(x, exp),
+                        None => x,
+                    })),
+            )
+            .map(|(((name, base), exp), x)| {
+                let f = Expr::Function(name, base.into_iter().chain([x]).collect());
+                match exp {
+                    Some(exp) => pow(f, exp),
+                    None => f,
+                }
+            });
+
+        let atom = number
+            .or(parens)
+            .or(group)
+            .or(list)
+            .or(frac)
+            .or(sqrt)
+            .or(function)
+            .or(variable);
+
+        let factorial = atom
+            .then(just(LatexToken::Factorial).repeated())
+            .foldl(|x, _| Expr::Factorial(Box::new(x)));
+
+        let power = factorial
+            .then(just(LatexToken::Pow).ignore_then(script).or_not())
+            .map(|(base, exp)| match exp {
+                Some(exp) => pow(base, exp),
+                None => base,
+            });
+
+        let unary = just(LatexToken::Sub)
+            .repeated()
+            .then(power.clone())
+            .foldr(|_, rhs| Expr::Neg(Box::new(rhs)));
+
+        let product = unary
+            .clone()
+            .then(
+                just(LatexToken::Mul)
+                    .to(mul as fn(_, _) -> _)
+                    .or(just(LatexToken::Div).to(div as fn(_, _) -> _))
+                    .then(unary)
+                    .or(power.map(|rhs| (mul as fn(_, _) -> _, rhs)))
+                    .repeated(),
+            )
+            .foldl(|lhs, (op, rhs)| op(lhs, rhs));
+
+        let sum = product
+            .clone()
+            .then(
+                just(LatexToken::Add)
+                    .to(add as fn(_, _) -> _)
+                    .or(just(LatexToken::Sub).to(sub as fn(_, _) -> _))
+                    .then(product)
+                    .repeated(),
+            )
+            .foldl(|lhs, (op, rhs)| op(lhs, rhs));
+
+        let relop = filter_map(|sp, x| match x {
+            LatexToken::Relation(op) => Ok(op),
+            x => Err(unexpected(sp, x)),
+        });
+
+        sum.clone()
+            .then(relop.then(sum).or_not())
+            .map(|(lhs, rhs)| match rhs {
+                Some((op, rhs)) => Expr::Relation(op, Box::new((lhs, rhs))),
+                None => lhs,
+            })
+    })
+}
+
+// error spans are byte ranges into `s`
+pub fn parse_latex(s: &str) -> Result<Expr, Simple<LatexToken>> {
+    let tokens = LatexTokenizer {
+        s,
+        chars: s.char_indices().peekable(),
+    }
+    .scan_tokens();
+    let stream = Stream::from_iter(s.len()..s.len() + 1, tokens.into_iter());
+    latex_expression()
+        .then_ignore(end())
+        .parse(stream)
+        .map_err(|mut x| {
+            let mut err = x.pop().unwrap();
+            for e in x {
+                err = err.merge(e);
+            }
+            err
+        })
+}
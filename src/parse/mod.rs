@@ -2,10 +2,11 @@
 // and thus parsed before other operation types as well.
 //
 // Precedence list
-// 1. Parentheses, function calls
+// 1. Parentheses, function calls, lists
 // 2. Exponentiation
-// 3. Multiplication, division, modulo
+// 3. Multiplication (explicit or by juxtaposition, as in `2x`), division, modulo
 // 4. Addition, subtraction
+// 5. Relations (=, !=, <, <=, >, >=)
 //
 // Tokens are either numbers or symbols. Function calls must be following symbols
 use std::iter::Peekable;
@@ -13,14 +14,23 @@ use std::str::Chars;
 
 use num::BigInt;
 
+use crate::relation::RelOp;
 use crate::BasicAlgebraicExpr;
 
+mod full_form;
+mod latex;
+
+pub use full_form::parse_full_form;
+pub use latex::{parse_latex, LatexToken};
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum Token {
     Number(BigInt),
     Symbol(String),
     LeftBr,
     RightBr,
+    LeftBrace,
+    RightBrace,
     LeftParen,
     RightParen,
     Add,
@@ -30,6 +40,10 @@ pub enum Token {
     Pow,
     Factorial,
     Comma,
+    Arrow,
+    Relation(RelOp),
+    // a character that starts no token, rejected by the parsers
+    Unknown(char),
 }
 
 pub struct Tokenizer<'a> {
@@ -62,8 +76,9 @@ impl<'a> Tokenizer<'a> {
         self.chars.peek().copied()
     }
     fn advance(&mut self) -> Option<char> {
-        self.current += 1;
-        self.chars.next()
+        let ch = self.chars.next()?;
+        self.current += ch.len_utf8();
+        Some(ch)
     }
     fn number(&mut self) -> Option<Token> {
         while let Some(ch) = self.peek() {
@@ -81,7 +96,7 @@ impl<'a> Tokenizer<'a> {
     }
     fn symbol(&mut self) -> Option<Token> {
         while let Some(ch) = self.peek() {
-            if ch.is_ascii_alphabetic() {
+            if ch.is_alphabetic() || ch == '_' {
                 self.advance();
             } else {
                 break;
@@ -98,16 +113,44 @@ impl<'a> Tokenizer<'a> {
             ')' => Some(Token::RightParen),
             '[' => Some(Token::LeftBr),
             ']' => Some(Token::RightBr),
+            '{' => Some(Token::LeftBrace),
+            '}' => Some(Token::RightBrace),
             '+' => Some(Token::Add),
+            '-' if self.peek() == Some('>') => {
+                self.advance();
+                Some(Token::Arrow)
+            }
             '-' => Some(Token::Sub),
             '*' => Some(Token::Mul),
             '/' => Some(Token::Div),
             '^' => Some(Token::Pow),
+            '!' if self.peek() == Some('=') => {
+                self.advance();
+                Some(Token::Relation(RelOp::NotEqual))
+            }
             '!' => Some(Token::Factorial),
+            '=' => {
+                // both `=` and `==` denote equality
+                if self.peek() == Some('=') {
+                    self.advance();
+                }
+                Some(Token::Relation(RelOp::Equal))
+            }
+            '<' if self.peek() == Some('=') => {
+                self.advance();
+                Some(Token::Relation(RelOp::LessEqual))
+            }
+            '<' => Some(Token::Relation(RelOp::Less)),
+            '>' if self.peek() == Some('=') => {
+                self.advance();
+                Some(Token::Relation(RelOp::GreaterEqual))
+            }
+            '>' => Some(Token::Relation(RelOp::Greater)),
             ',' => Some(Token::Comma),
             x if x.is_ascii_digit() => self.number(),
-            x if x.is_ascii_alphanumeric() => self.symbol(),
-            _ => panic!("AAAAAAAAAAAAH"),
+            // symbols may also be unicode letters such as `α`
+            x if x.is_alphabetic() || x == '_' => self.symbol(),
+            x => Some(Token::Unknown(x)),
         }
     }
 
@@ -126,7 +169,7 @@ pub fn parse(s: &str) -> Vec<Token> {
 
 use chumsky::prelude::*;
 
-fn expression_parser() -> impl Parser<Token, BasicAlgebraicExpr, Error = Simple<Token>> {
+fn expression() -> impl Parser<Token, BasicAlgebraicExpr, Error = Simple<Token>> + Clone {
     use super::BasicAlgebraicExpr as Expr;
 
     fn add(a: Expr, b: Expr) -> Expr {
@@ -147,7 +190,11 @@ fn expression_parser() -> impl Parser<Token, BasicAlgebraicExpr, Error = Simple<
         ])
     }
 
-    let expr = recursive(|expr| {
+    fn pow(a: Expr, b: Expr) -> Expr {
+        Expr::Pow(Box::new((a, b)))
+    }
+
+    recursive(|expr| {
         let int = filter_map(|sp, x| match x {
             Token::Number(n) => Ok(n),
             _ => Err(Simple::custom(sp, "expected number")),
@@ -158,32 +205,77 @@ fn expression_parser() -> impl Parser<Token, BasicAlgebraicExpr, Error = Simple<
             _ => Err(Simple::custom(sp, "expected symbol")),
         });
 
+        // arguments may be rules such as `x -> 0`, kept as `Rule[x, 0]`
+        let argument = expr
+            .clone()
+            .then(just(Token::Arrow).ignore_then(expr.clone()).or_not())
+            .map(|(lhs, rhs)| match rhs {
+                Some(rhs) => Expr::Function("Rule".into(), vec![lhs, rhs]),
+                None => lhs,
+            });
+
         let call = symbol
             .clone()
             .then_ignore(just(Token::LeftBr))
             .then(
-                expr.clone()
+                argument
                     .separated_by(just(Token::Comma))
                     .allow_trailing(),
             )
             .then_ignore(just(Token::RightBr))
             .map(|(name, args)| Expr::Function(name, args));
 
+        let list = expr
+            .clone()
+            .separated_by(just(Token::Comma))
+            .allow_trailing()
+            .delimited_by(just(Token::LeftBrace), just(Token::RightBrace))
+            .map(Expr::List);
+
         let atom = int
             .map(|i| Expr::Const(i.into()))
             .or(expr.delimited_by(just(Token::LeftParen), just(Token::RightParen)))
+            .or(list)
             .or(call)
-            .or(symbol.map(|x| match x.len() {
+            .or(symbol.map(|x| match x.chars().count() {
                 0 => unreachable!(),
                 1 => Expr::Symbol(x),
+                // pattern names such as `x_` or `n_Integer`, and capitalized names
+                // such as `Sin` are kept whole
+                _ if x.contains('_') || x.starts_with(|c: char| c.is_ascii_uppercase()) => {
+                    Expr::Symbol(x)
+                }
                 _ => Expr::Product(x.chars().map(|x| Expr::Symbol(x.into())).collect()),
             }));
 
+        // `n!` binds tighter than powers
+        let factorial = atom
+            .then(just(Token::Factorial).repeated())
+            .foldl(|x, _| Expr::Factorial(Box::new(x)));
+
+        // exponentiation is right associative: a^b^c = a^(b^c), and a minus in the
+        // exponent negates the whole tower after it: a^-b^c = a^(-(b^c))
+        let power = recursive(|power| {
+            let exponent = just(Token::Sub)
+                .repeated()
+                .then(power)
+                .foldr(|_, rhs| BasicAlgebraicExpr::Neg(Box::new(rhs)));
+            factorial
+                .clone()
+                .then(just(Token::Pow).ignore_then(exponent).or_not())
+                .map(|(base, exponent)| match exponent {
+                    Some(exponent) => pow(base, exponent),
+                    None => base,
+                })
+        });
+
         let unary = just(Token::Sub)
             .repeated()
-            .then(atom)
+            .then(power.clone())
             .foldr(|_, rhs| BasicAlgebraicExpr::Neg(Box::new(rhs)));
 
+        // juxtaposed factors such as `2x` or `3(x + 1)` are multiplied, but a
+        // leading minus always means subtraction
         let product = unary
             .clone()
             .then(
@@ -191,6 +283,7 @@ fn expression_parser() -> impl Parser<Token, BasicAlgebraicExpr, Error = Simple<
                     .to(mul as fn(_, _) -> _)
                     .or(just(Token::Div).to(div as fn(_, _) -> _))
                     .then(unary)
+                    .or(power.map(|rhs| (mul as fn(_, _) -> _, rhs)))
                     .repeated(),
             )
             .foldl(|lhs, (op, rhs)| op(lhs, rhs));
@@ -205,14 +298,26 @@ fn expression_parser() -> impl Parser<Token, BasicAlgebraicExpr, Error = Simple<
                     .repeated(),
             )
             .foldl(|lhs, (op, rhs)| op(lhs, rhs));
-        
-        sum
-    });
-    expr.then_ignore(end())
+
+        let relop = filter_map(|sp, x| match x {
+            Token::Relation(op) => Ok(op),
+            _ => Err(Simple::custom(sp, "expected relational operator")),
+        });
+
+        sum.clone()
+            .then(relop.then(sum).or_not())
+            .map(|(lhs, rhs)| match rhs {
+                Some((op, rhs)) => Expr::Relation(op, Box::new((lhs, rhs))),
+                None => lhs,
+            })
+    })
 }
 
-pub fn parse_into_expression(s: &str) -> Result<BasicAlgebraicExpr, Simple<Token>> {
-    expression_parser().parse(parse(s)).map_err(|mut x| {
+fn parse_with<T>(
+    parser: impl Parser<Token, T, Error = Simple<Token>>,
+    s: &str,
+) -> Result<T, Simple<Token>> {
+    parser.parse(parse(s)).map_err(|mut x| {
         let mut err = x.pop().unwrap();
         for e in x {
             err = err.merge(e);
@@ -220,3 +325,18 @@ pub fn parse_into_expression(s: &str) -> Result<BasicAlgebraicExpr, Simple<Token
         err
     })
 }
+
+pub fn parse_into_expression(s: &str) -> Result<BasicAlgebraicExpr, Simple<Token>> {
+    parse_with(expression().then_ignore(end()), s)
+}
+
+// parses `lhs -> rhs`
+pub fn parse_rule(s: &str) -> Result<(BasicAlgebraicExpr, BasicAlgebraicExpr), Simple<Token>> {
+    parse_with(
+        expression()
+            .then_ignore(just(Token::Arrow))
+            .then(expression())
+            .then_ignore(end()),
+        s,
+    )
+}
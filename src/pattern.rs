@@ -0,0 +1,489 @@
+// Declarative rewriting over `SimpleExpr`.
+//
+// Patterns are ordinary expressions in which some symbols are wildcards:
+//
+// - `x_` matches any single expression and binds it to `x`
+// - `x_Integer` additionally requires the expression to have the head `Integer`
+//   (see `SimpleExpr::head`)
+// - `xs__` matches one or more expressions. Inside a sum, product or function call
+//   it captures a run of operands, otherwise it behaves like `xs_`.
+// - an anonymous `_` matches anything without binding
+//
+// On the right hand side of a rule a binding is referred to either by its bare name or
+// by the wildcard itself. The latter is required for names longer than one letter,
+// since the parser reads `xs` as `x * s`.
+//
+// Sums and products are matched up to commutativity and associativity, so
+// `Sin[x_]^2 + Cos[x_]^2` matches inside `3 + Cos[y]^2 + Sin[y]^2`.
+use std::collections::BTreeMap;
+use std::slice;
+
+use chumsky::error::Simple;
+
+use crate::constant::Constant;
+use crate::limits;
+use crate::parse::{parse_rule, Token};
+use crate::relation::RelOp;
+use crate::simplify::SimpleExpr;
+use crate::visit::Node;
+use crate::{BasicAlgebraicExpr, ComputeError, ComputeResult};
+
+// upper bound on the number of passes made by `replace_repeated`
+const MAX_ITERATIONS: usize = 1000;
+// upper bound on the ways leftover operands are split among sequence wildcards
+const MAX_DISTRIBUTIONS: usize = 1000;
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Blank {
+    pub name: Option<String>,
+    pub head: Option<String>,
+}
+
+impl Blank {
+    fn accepts(&self, expr: &SimpleExpr) -> bool {
+        match &self.head {
+            Some(head) => head == expr.head(),
+            None => true,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Pattern {
+    Blank(Blank),
+    BlankSequence(Blank),
+    Const(Constant),
+    Symbol(String),
+    Product(Vec<Pattern>),
+    Sum(Vec<Pattern>),
+    Pow(Box<(Pattern, Pattern)>),
+    Factorial(Box<Pattern>),
+    Function(String, Vec<Pattern>),
+    List(Vec<Pattern>),
+    Relation(RelOp, Box<(Pattern, Pattern)>),
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Binding {
+    Expr(SimpleExpr),
+    Sequence(Vec<SimpleExpr>),
+}
+
+pub type Bindings = BTreeMap<String, Binding>;
+
+// Splits `name_Head` / `name__Head` into its parts.
+// Returns `None` for symbols that are not wildcards.
+fn parse_blank(symbol: &str) -> Option<(Blank, bool)> {
+    let (name, rest) = symbol.split_once('_')?;
+    let (is_sequence, head) = match rest.strip_prefix('_') {
+        Some(head) => (true, head),
+        None => (false, rest),
+    };
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_owned());
+    Some((
+        Blank {
+            name: non_empty(name),
+            head: non_empty(head),
+        },
+        is_sequence,
+    ))
+}
+
+impl From<SimpleExpr> for Pattern {
+    fn from(x: SimpleExpr) -> Self {
+        fn list(x: Vec<SimpleExpr>) -> Vec<Pattern> {
+            x.into_iter().map(Into::into).collect()
+        }
+        match x {
+            SimpleExpr::Symbol(s) => match parse_blank(&s) {
+                Some((blank, false)) => Pattern::Blank(blank),
+                Some((blank, true)) => Pattern::BlankSequence(blank),
+                None => Pattern::Symbol(s),
+            },
+            SimpleExpr::Const(c) => Pattern::Const(c),
+            SimpleExpr::Product(x) => Pattern::Product(list(x)),
+            SimpleExpr::Sum(x) => Pattern::Sum(list(x)),
+            SimpleExpr::Pow(x) => {
+                let (a, b) = *x;
+                Pattern::Pow(Box::new((a.into(), b.into())))
+            }
+            SimpleExpr::Factorial(x) => Pattern::Factorial(Box::new((*x).into())),
+            SimpleExpr::Function(name, args) => Pattern::Function(name, list(args)),
+            SimpleExpr::List(x) => Pattern::List(list(x)),
+            SimpleExpr::Relation(op, x) => {
+                let (a, b) = *x;
+                Pattern::Relation(op, Box::new((a.into(), b.into())))
+            }
+        }
+    }
+}
+
+fn bind(blank: &Blank, value: Binding, mut bindings: Bindings) -> Option<Bindings> {
+    let Some(name) = &blank.name else {
+        return Some(bindings);
+    };
+    match bindings.get(name) {
+        Some(existing) => (*existing == value).then_some(bindings),
+        None => {
+            bindings.insert(name.clone(), value);
+            Some(bindings)
+        }
+    }
+}
+
+impl Pattern {
+    fn is_sequence(&self) -> bool {
+        matches!(self, Pattern::BlankSequence(_))
+    }
+
+    /// Every way of matching `expr` against this pattern, extending `bindings`.
+    pub fn matches(&self, expr: &SimpleExpr, bindings: Bindings) -> ComputeResult<Vec<Bindings>> {
+        limits::step(|| expr.clone())?;
+        Ok(match (self, expr) {
+            (Pattern::Blank(blank), e) if blank.accepts(e) => {
+                bind(blank, Binding::Expr(e.clone()), bindings)
+                    .into_iter()
+                    .collect()
+            }
+            (Pattern::BlankSequence(blank), e) if blank.accepts(e) => {
+                bind(blank, Binding::Sequence(vec![e.clone()]), bindings)
+                    .into_iter()
+                    .collect()
+            }
+            (Pattern::Const(a), SimpleExpr::Const(b)) if a == b => vec![bindings],
+            (Pattern::Symbol(a), SimpleExpr::Symbol(b)) if a == b => vec![bindings],
+            (Pattern::Pow(p), SimpleExpr::Pow(e)) => match_pair(p, e, bindings)?,
+            (Pattern::Relation(op1, p), SimpleExpr::Relation(op2, e)) if op1 == op2 => {
+                match_pair(p, e, bindings)?
+            }
+            (Pattern::Factorial(p), SimpleExpr::Factorial(e)) => p.matches(e, bindings)?,
+            (Pattern::Function(name1, p), SimpleExpr::Function(name2, e)) if name1 == name2 => {
+                match_ordered(p, e, bindings)?
+            }
+            (Pattern::List(p), SimpleExpr::List(e)) => match_ordered(p, e, bindings)?,
+            (Pattern::Sum(p), SimpleExpr::Sum(e))
+            | (Pattern::Product(p), SimpleExpr::Product(e)) => match_commutative(p, e, bindings)?
+                .into_iter()
+                .filter_map(|(b, rest)| rest.is_empty().then_some(b))
+                .collect(),
+            _ => vec![],
+        })
+    }
+}
+
+fn match_pair(
+    (p1, p2): &(Pattern, Pattern),
+    (e1, e2): &(SimpleExpr, SimpleExpr),
+    bindings: Bindings,
+) -> ComputeResult<Vec<Bindings>> {
+    let mut out = Vec::new();
+    for b in p1.matches(e1, bindings)? {
+        out.extend(p2.matches(e2, b)?);
+    }
+    Ok(out)
+}
+
+// matches function arguments in order, letting sequence wildcards absorb runs of arguments
+fn match_ordered(
+    patterns: &[Pattern],
+    exprs: &[SimpleExpr],
+    bindings: Bindings,
+) -> ComputeResult<Vec<Bindings>> {
+    let Some((first, patterns)) = patterns.split_first() else {
+        return Ok(if exprs.is_empty() {
+            vec![bindings]
+        } else {
+            vec![]
+        });
+    };
+    let mut out = Vec::new();
+    match first {
+        Pattern::BlankSequence(blank) => {
+            for n in 1..=exprs.len() {
+                if !exprs[..n].iter().all(|e| blank.accepts(e)) {
+                    continue;
+                }
+                let sequence = Binding::Sequence(exprs[..n].to_vec());
+                if let Some(b) = bind(blank, sequence, bindings.clone()) {
+                    out.extend(match_ordered(patterns, &exprs[n..], b)?);
+                }
+            }
+        }
+        _ => {
+            let Some((e, exprs)) = exprs.split_first() else {
+                return Ok(vec![]);
+            };
+            for b in first.matches(e, bindings)? {
+                out.extend(match_ordered(patterns, exprs, b)?);
+            }
+        }
+    }
+    Ok(out)
+}
+
+// Matches the operands of a sum or product regardless of order.
+// Operands not consumed by any pattern are returned alongside the bindings.
+fn match_commutative(
+    patterns: &[Pattern],
+    exprs: &[SimpleExpr],
+    bindings: Bindings,
+) -> ComputeResult<Vec<(Bindings, Vec<SimpleExpr>)>> {
+    let (sequences, singles): (Vec<_>, Vec<_>) = patterns.iter().partition(|p| p.is_sequence());
+    let mut out = Vec::new();
+    match_singles(&singles, exprs.to_vec(), bindings, &mut |b, rest| {
+        distribute(&sequences, rest, b, &mut out)
+    })?;
+    Ok(out)
+}
+
+fn match_singles(
+    patterns: &[&Pattern],
+    remaining: Vec<SimpleExpr>,
+    bindings: Bindings,
+    k: &mut dyn FnMut(Bindings, Vec<SimpleExpr>) -> ComputeResult<()>,
+) -> ComputeResult<()> {
+    let Some((first, patterns)) = patterns.split_first() else {
+        return k(bindings, remaining);
+    };
+    for i in 0..remaining.len() {
+        limits::step(|| remaining[i].clone())?;
+        for b in first.matches(&remaining[i], bindings.clone())? {
+            let mut rest = remaining.clone();
+            rest.remove(i);
+            match_singles(patterns, rest, b, k)?;
+        }
+    }
+    Ok(())
+}
+
+// assigns every leftover operand to one of the sequence wildcards, each of which needs at least one
+fn distribute(
+    sequences: &[&Pattern],
+    rest: Vec<SimpleExpr>,
+    bindings: Bindings,
+    out: &mut Vec<(Bindings, Vec<SimpleExpr>)>,
+) -> ComputeResult<()> {
+    if sequences.is_empty() {
+        out.push((bindings, rest));
+        return Ok(());
+    }
+    let mut groups = vec![Vec::new(); sequences.len()];
+    assign(sequences, &rest, &mut groups, &bindings, out)
+}
+
+// whether `e` may join group `i` without contradicting its name, which is either bound already
+// or shared with other groups that all have to end up with the same operands
+fn consistent(
+    sequences: &[&Pattern],
+    groups: &[Vec<SimpleExpr>],
+    bindings: &Bindings,
+    i: usize,
+    e: &SimpleExpr,
+) -> bool {
+    let Some(name) = &sequence_blank(sequences[i]).name else {
+        return true;
+    };
+    let len = groups[i].len();
+    match bindings.get(name) {
+        Some(Binding::Sequence(bound)) => bound.get(len) == Some(e),
+        Some(Binding::Expr(_)) => false,
+        None => sequences.iter().zip(groups).all(|(pattern, other)| {
+            sequence_blank(pattern).name.as_ref() != Some(name)
+                || !matches!(other.get(len), Some(x) if x != e)
+        }),
+    }
+}
+
+fn sequence_blank(pattern: &Pattern) -> &Blank {
+    match pattern {
+        Pattern::BlankSequence(blank) => blank,
+        _ => unreachable!(),
+    }
+}
+
+// places the operands one at a time, backtracking when a wildcard rejects one or when too few
+// operands are left for the wildcards that are still empty
+fn assign(
+    sequences: &[&Pattern],
+    rest: &[SimpleExpr],
+    groups: &mut [Vec<SimpleExpr>],
+    bindings: &Bindings,
+    out: &mut Vec<(Bindings, Vec<SimpleExpr>)>,
+) -> ComputeResult<()> {
+    let empty = groups.iter().filter(|g| g.is_empty()).count();
+    if out.len() >= MAX_DISTRIBUTIONS || rest.len() < empty {
+        return Ok(());
+    }
+    let Some((e, rest)) = rest.split_first() else {
+        let mut b = bindings.clone();
+        for (pattern, group) in sequences.iter().zip(groups.iter()) {
+            let binding = Binding::Sequence(group.clone());
+            let Some(next) = bind(sequence_blank(pattern), binding, b) else {
+                return Ok(());
+            };
+            b = next;
+        }
+        out.push((b, vec![]));
+        return Ok(());
+    };
+    for (i, pattern) in sequences.iter().enumerate() {
+        if sequence_blank(pattern).accepts(e) && consistent(sequences, groups, bindings, i, e) {
+            limits::step(|| e.clone())?;
+            groups[i].push(e.clone());
+            let result = assign(sequences, rest, groups, bindings, out);
+            groups[i].pop();
+            result?;
+        }
+    }
+    Ok(())
+}
+
+// Replaces the bound names in `expr`. Pattern-shaped symbols like `x_` refer to the same binding as `x`.
+fn substitute(expr: &SimpleExpr, bindings: &Bindings) -> SimpleExpr {
+    match expr {
+        e @ SimpleExpr::Symbol(_) => match lookup(e, bindings) {
+            Some(Binding::Expr(x)) => x.clone(),
+            Some(Binding::Sequence(x)) if x.len() == 1 => x[0].clone(),
+            Some(Binding::Sequence(x)) => SimpleExpr::Function("Sequence".into(), x.clone()),
+            None => e.clone(),
+        },
+        SimpleExpr::Const(_) => expr.clone(),
+        SimpleExpr::Product(x) => SimpleExpr::Product(substitute_list(x, bindings)),
+        SimpleExpr::Sum(x) => SimpleExpr::Sum(substitute_list(x, bindings)),
+        SimpleExpr::Pow(x) => SimpleExpr::Pow(Box::new((
+            substitute(&x.0, bindings),
+            substitute(&x.1, bindings),
+        ))),
+        SimpleExpr::Relation(op, x) => SimpleExpr::relation(
+            *op,
+            substitute(&x.0, bindings),
+            substitute(&x.1, bindings),
+        ),
+        SimpleExpr::Factorial(x) => SimpleExpr::Factorial(Box::new(substitute(x, bindings))),
+        SimpleExpr::Function(name, args) => {
+            SimpleExpr::Function(name.clone(), substitute_list(args, bindings))
+        }
+        SimpleExpr::List(x) => SimpleExpr::List(substitute_list(x, bindings)),
+    }
+}
+
+// like `substitute`, but sequence bindings are spliced into the list
+fn substitute_list(x: &[SimpleExpr], bindings: &Bindings) -> Vec<SimpleExpr> {
+    let mut out = Vec::with_capacity(x.len());
+    for e in x {
+        match lookup(e, bindings) {
+            Some(Binding::Sequence(items)) => out.extend(items.iter().cloned()),
+            _ => out.push(substitute(e, bindings)),
+        }
+    }
+    out
+}
+
+fn lookup<'a>(expr: &SimpleExpr, bindings: &'a Bindings) -> Option<&'a Binding> {
+    let SimpleExpr::Symbol(s) = expr else {
+        return None;
+    };
+    match parse_blank(s) {
+        Some((
+            Blank {
+                name: Some(name), ..
+            },
+            _,
+        )) => bindings.get(&name),
+        Some(_) => None,
+        None => bindings.get(s),
+    }
+}
+
+#[derive(Debug)]
+pub enum RuleError {
+    Parse(Simple<Token>),
+    Compute(ComputeError),
+}
+
+impl From<Simple<Token>> for RuleError {
+    fn from(e: Simple<Token>) -> Self {
+        RuleError::Parse(e)
+    }
+}
+
+impl From<ComputeError> for RuleError {
+    fn from(e: ComputeError) -> Self {
+        RuleError::Compute(e)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rule {
+    pub lhs: Pattern,
+    pub rhs: SimpleExpr,
+}
+
+impl Rule {
+    pub fn new(lhs: SimpleExpr, rhs: SimpleExpr) -> Self {
+        Rule {
+            lhs: lhs.into(),
+            rhs,
+        }
+    }
+
+    // parses rules such as `Sin[x_]^2 + Cos[x_]^2 -> 1`
+    pub fn parse(s: &str) -> Result<Self, RuleError> {
+        let (lhs, rhs) = parse_rule(s)?;
+        Ok(Rule::new(lhs.simplify()?, rhs.simplify()?))
+    }
+
+    // Tries to rewrite `expr` itself (not its subexpressions).
+    // For sums and products a subset of the operands may match, the rest is kept.
+    pub fn apply(&self, expr: &SimpleExpr) -> ComputeResult<Option<SimpleExpr>> {
+        let replaced = match (&self.lhs, expr) {
+            (Pattern::Sum(p), SimpleExpr::Sum(e))
+            | (Pattern::Product(p), SimpleExpr::Product(e)) => {
+                let Some((bindings, mut operands)) =
+                    match_commutative(p, e, Bindings::new())?.into_iter().next()
+                else {
+                    return Ok(None);
+                };
+                // a sequence on the right hand side is spliced into the surrounding operation
+                operands.extend(substitute_list(slice::from_ref(&self.rhs), &bindings));
+                match expr {
+                    SimpleExpr::Sum(_) => SimpleExpr::Sum(operands),
+                    _ => SimpleExpr::Product(operands),
+                }
+            }
+            (lhs, expr) => {
+                let Some(bindings) = lhs.matches(expr, Bindings::new())?.into_iter().next() else {
+                    return Ok(None);
+                };
+                substitute(&self.rhs, &bindings)
+            }
+        };
+        BasicAlgebraicExpr::from(replaced).simplify().map(Some)
+    }
+}
+
+// Rewrites each subexpression with the first rule that matches, trying the whole expression first.
+// Replaced parts are not rewritten again.
+pub fn replace_all(expr: SimpleExpr, rules: &[Rule]) -> ComputeResult {
+    for rule in rules {
+        if let Some(result) = rule.apply(&expr)? {
+            return Ok(result);
+        }
+    }
+    let rewritten = Node::try_map_children(expr, |x| replace_all(x, rules))?;
+    BasicAlgebraicExpr::from(rewritten).simplify()
+}
+
+// Applies `replace_all` until the expression stops changing.
+// Rules that keep rewriting past `MAX_ITERATIONS` passes are reported as unsupported.
+pub fn replace_repeated(mut expr: SimpleExpr, rules: &[Rule]) -> ComputeResult {
+    for _ in 0..MAX_ITERATIONS {
+        limits::step(|| expr.clone())?;
+        let next = replace_all(expr.clone(), rules)?;
+        if next == expr {
+            return Ok(next);
+        }
+        expr = next;
+    }
+    Err(ComputeError::Unsupported(expr))
+}
@@ -0,0 +1,566 @@
+// Univariate polynomials.
+//
+// `coefficients` reads the coefficients of an expanded `SimpleExpr`, which may
+// themselves be symbolic. `Polynomial` is the dense representation used when all
+// coefficients are rational.
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num::{BigInt, BigRational, One, Signed, ToPrimitive, Zero};
+
+use crate::constant::Constant;
+use crate::limits;
+use crate::simplify::{Operation, Product, SimpleExpr, Sum};
+use crate::visit::Node;
+use crate::{BasicAlgebraicExpr, ComputeResult};
+
+mod apart;
+
+pub use apart::apart;
+
+fn is_free_of(expr: &SimpleExpr, var: &str) -> bool {
+    !expr.free_symbols().contains(var)
+}
+
+// splits a term into (coefficient, degree in `var`)
+fn term_degree(term: &SimpleExpr, var: &str) -> Option<(SimpleExpr, usize)> {
+    let degree = |x: &SimpleExpr| -> Option<usize> {
+        match x {
+            SimpleExpr::Symbol(s) if s == var => Some(1),
+            SimpleExpr::Pow(p) if matches!(&p.0, SimpleExpr::Symbol(s) if s == var) => match &p.1 {
+                SimpleExpr::Const(n) if !n.is_negative() => n.as_integer()?.to_usize(),
+                _ => None,
+            },
+            x if is_free_of(x, var) => Some(0),
+            _ => None,
+        }
+    };
+    match term {
+        SimpleExpr::Product(factors) => {
+            let mut total = 0;
+            let mut coefficient = Vec::new();
+            for factor in factors {
+                match degree(factor)? {
+                    0 => coefficient.push(factor.clone()),
+                    n => total += n,
+                }
+            }
+            let coefficient = match coefficient.len() {
+                0 => 1.into(),
+                1 => coefficient.pop().unwrap(),
+                _ => SimpleExpr::Product(coefficient),
+            };
+            Some((coefficient, total))
+        }
+        x => match degree(x)? {
+            0 => Some((x.clone(), 0)),
+            n => Some((1.into(), n)),
+        },
+    }
+}
+
+// The coefficients of `expr` as a polynomial in `var`, lowest degree first.
+// `expr` must already be expanded. Returns `None` if it is not a polynomial in `var`.
+pub fn coefficients(expr: &SimpleExpr, var: &str) -> ComputeResult<Option<Vec<SimpleExpr>>> {
+    let terms = match expr {
+        SimpleExpr::Sum(terms) => terms.as_slice(),
+        x => std::slice::from_ref(x),
+    };
+    let mut by_degree: Vec<Vec<SimpleExpr>> = Vec::new();
+    for term in terms {
+        let Some((coefficient, degree)) = term_degree(term, var) else {
+            return Ok(None);
+        };
+        if by_degree.len() <= degree {
+            by_degree.resize(degree + 1, Vec::new());
+        }
+        by_degree[degree].push(coefficient);
+    }
+    by_degree
+        .into_iter()
+        .map(|x| match x.len() {
+            0 => Ok(0.into()),
+            _ => Sum.simplify(x),
+        })
+        .collect::<ComputeResult<_>>()
+        .map(Some)
+}
+
+// `expr` as a quotient of polynomials with rational coefficients in `var`, the numerator
+// collecting all factors but the powers with a negative exponent
+pub fn rational_function(
+    expr: &SimpleExpr,
+    var: &str,
+) -> ComputeResult<Option<(Polynomial, Polynomial)>> {
+    let factors = match expr {
+        SimpleExpr::Product(factors) => factors.as_slice(),
+        x => std::slice::from_ref(x),
+    };
+    let mut numerator = Vec::new();
+    let mut denominator = Vec::new();
+    for factor in factors {
+        match factor {
+            SimpleExpr::Pow(p)
+                if let SimpleExpr::Const(n) = &p.1
+                    && n.is_negative() =>
+            {
+                let power = SimpleExpr::Const(-n.clone());
+                denominator.push(BasicAlgebraicExpr::simplify_power(p.0.clone(), power)?);
+            }
+            x => numerator.push(x.clone()),
+        }
+    }
+    let as_polynomial = |factors: Vec<SimpleExpr>| -> ComputeResult<Option<Polynomial>> {
+        let product = match factors.len() {
+            0 => 1.into(),
+            _ => Product.simplify(factors)?,
+        };
+        Ok(Polynomial::from_expr(&product.expand()?, var))
+    };
+    Ok(as_polynomial(numerator)?.zip(as_polynomial(denominator)?))
+}
+
+// Combines `expr` into a single fraction of polynomials with rational coefficients in
+// `var`, with the common factors cancelled and a monic denominator.
+pub fn together(expr: &SimpleExpr, var: &str) -> Option<(Polynomial, Polynomial)> {
+    let one = || Polynomial::constant(Constant::one());
+    let reduce = |numerator: Polynomial, denominator: Polynomial| {
+        let g = numerator.gcd(&denominator);
+        let leading = Constant::one() / denominator.leading();
+        let numerator = numerator.div_rem(&g).0.scale(&leading);
+        let denominator = denominator.div_rem(&g).0.scale(&leading);
+        (numerator, denominator)
+    };
+    match expr {
+        SimpleExpr::Const(c) => Some((Polynomial::constant(c.clone()), one())),
+        SimpleExpr::Symbol(s) if s == var => {
+            Some((Polynomial::new(vec![Constant::zero(), Constant::one()]), one()))
+        }
+        SimpleExpr::Sum(terms) => terms.iter().try_fold(
+            (Polynomial::new(vec![]), one()),
+            |(a, b), term| {
+                let (c, d) = together(term, var)?;
+                Some(reduce(a * d.clone() + c * b.clone(), b * d))
+            },
+        ),
+        SimpleExpr::Product(factors) => factors.iter().try_fold((one(), one()), |(a, b), x| {
+            let (c, d) = together(x, var)?;
+            Some(reduce(a * c, b * d))
+        }),
+        SimpleExpr::Pow(p) if let SimpleExpr::Const(n) = &p.1 => {
+            let (numerator, denominator) = together(&p.0, var)?;
+            let (numerator, denominator) = match n.is_negative() {
+                true if numerator.is_zero() => return None,
+                true => (denominator, numerator),
+                false => (numerator, denominator),
+            };
+            let n = n.as_integer()?.abs().to_usize()?;
+            let power = |p: &Polynomial| (0..n).fold(one(), |acc, _| acc * p.clone());
+            Some(reduce(power(&numerator), power(&denominator)))
+        }
+        _ => None,
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Polynomial {
+    // lowest degree first, without trailing zeros
+    coeffs: Vec<Constant>,
+}
+
+impl Polynomial {
+    pub fn new(mut coeffs: Vec<Constant>) -> Self {
+        while matches!(coeffs.last(), Some(c) if c.is_zero()) {
+            coeffs.pop();
+        }
+        Polynomial { coeffs }
+    }
+
+    pub fn constant(c: Constant) -> Self {
+        Self::new(vec![c])
+    }
+
+    // x - root
+    pub fn linear_factor(root: Constant) -> Self {
+        Self::new(vec![-root, Constant::one()])
+    }
+
+    // `expr` must already be expanded
+    pub fn from_expr(expr: &SimpleExpr, var: &str) -> Option<Self> {
+        let coeffs = coefficients(expr, var).ok().flatten()?;
+        coeffs
+            .into_iter()
+            .map(|x| match x {
+                SimpleExpr::Const(c) => Some(c),
+                _ => None,
+            })
+            .collect::<Option<_>>()
+            .map(Self::new)
+    }
+
+    pub fn to_expr(&self, var: &str) -> ComputeResult {
+        let x = SimpleExpr::Symbol(var.into());
+        let terms = self
+            .coeffs
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !c.is_zero())
+            .map(|(i, c)| {
+                let power = BasicAlgebraicExpr::simplify_power(x.clone(), (i as i128).into())?;
+                Product.simplify(vec![SimpleExpr::Const(c.clone()), power])
+            })
+            .collect::<ComputeResult<Vec<_>>>()?;
+        match terms.len() {
+            0 => Ok(0.into()),
+            _ => Sum.simplify(terms),
+        }
+    }
+
+    pub fn coeffs(&self) -> &[Constant] {
+        &self.coeffs
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    // `None` for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    pub fn leading(&self) -> Constant {
+        self.coeffs.last().cloned().unwrap_or_else(Constant::zero)
+    }
+
+    pub fn eval(&self, x: &Constant) -> Constant {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(Constant::zero(), |acc, c| acc * x.clone() + c.clone())
+    }
+
+    pub fn derivative(&self) -> Self {
+        Self::new(
+            self.coeffs
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, c)| c.clone() * Constant::from(i as i128))
+                .collect(),
+        )
+    }
+
+    // the antiderivative without constant term
+    pub fn integral(&self) -> Self {
+        let mut coeffs = vec![Constant::zero()];
+        coeffs.extend(
+            self.coeffs
+                .iter()
+                .enumerate()
+                .map(|(i, c)| c.clone() / Constant::from(i as i128 + 1)),
+        );
+        Self::new(coeffs)
+    }
+
+    pub fn monic(&self) -> Self {
+        let leading = self.leading();
+        if leading.is_zero() {
+            return self.clone();
+        }
+        Self::new(
+            self.coeffs
+                .iter()
+                .map(|c| c.clone() / leading.clone())
+                .collect(),
+        )
+    }
+
+    pub fn scale(&self, factor: &Constant) -> Self {
+        Self::new(
+            self.coeffs
+                .iter()
+                .map(|c| c.clone() * factor.clone())
+                .collect(),
+        )
+    }
+
+    // polynomial long division, panics on division by zero
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let divisor_degree = divisor.degree().expect("division by the zero polynomial");
+        let leading = divisor.leading();
+        let mut rem = self.coeffs.clone();
+        let mut quotient = vec![Constant::zero(); rem.len().saturating_sub(divisor_degree)];
+        while rem.len() > divisor_degree && !rem.is_empty() {
+            let shift = rem.len() - 1 - divisor_degree;
+            let factor = rem.last().unwrap().clone() / leading.clone();
+            for (i, c) in divisor.coeffs.iter().enumerate() {
+                let updated = rem[shift + i].clone() - factor.clone() * c.clone();
+                rem[shift + i] = updated;
+            }
+            quotient[shift] = factor;
+            rem.pop();
+        }
+        (Self::new(quotient), Self::new(rem))
+    }
+
+    // monic greatest common divisor
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let (_, r) = a.div_rem(&b);
+            a = b;
+            b = r;
+        }
+        a.monic()
+    }
+
+    // Returns `(g, s, t)` where `g` is the monic gcd and `s * self + t * other = g`.
+    pub fn extended_gcd(&self, other: &Self) -> (Self, Self, Self) {
+        let (mut r0, mut r1) = (self.clone(), other.clone());
+        let (mut s0, mut s1) = (Self::constant(Constant::one()), Self::new(vec![]));
+        let (mut t0, mut t1) = (Self::new(vec![]), Self::constant(Constant::one()));
+        while !r1.is_zero() {
+            let (q, r) = r0.div_rem(&r1);
+            let s = s0 - q.clone() * s1.clone();
+            let t = t0 - q * t1.clone();
+            (r0, r1) = (r1, r);
+            (s0, s1) = (s1, s);
+            (t0, t1) = (t1, t);
+        }
+        let leading = r0.leading();
+        if leading.is_zero() {
+            return (r0, s0, t0);
+        }
+        let inverse = Constant::one() / leading;
+        (r0.scale(&inverse), s0.scale(&inverse), t0.scale(&inverse))
+    }
+
+    // Yun's algorithm. Returns square free factors paired with their multiplicity.
+    pub fn square_free_decomposition(&self) -> Vec<(Polynomial, usize)> {
+        let mut out = Vec::new();
+        if self.degree().unwrap_or(0) == 0 {
+            return out;
+        }
+        let derivative = self.derivative();
+        let a = self.gcd(&derivative);
+        let mut b = self.div_rem(&a).0;
+        let mut d = derivative.div_rem(&a).0 - b.derivative();
+        let mut multiplicity = 1;
+        while b.degree().unwrap_or(0) > 0 {
+            let factor = b.gcd(&d);
+            b = b.div_rem(&factor).0;
+            let c = d.div_rem(&factor).0;
+            d = c - b.derivative();
+            if factor.degree().unwrap_or(0) > 0 {
+                out.push((factor, multiplicity));
+            }
+            multiplicity += 1;
+        }
+        out
+    }
+
+    // The number of distinct real roots in `[a, b]`, by Sturm's theorem.
+    pub fn count_roots(&self, a: &Constant, b: &Constant) -> usize {
+        if self.degree().unwrap_or(0) == 0 {
+            return 0;
+        }
+        let square_free = self.div_rem(&self.gcd(&self.derivative())).0;
+        let mut sequence = vec![square_free.clone(), square_free.derivative()];
+        while let [.., p, q] = sequence.as_slice()
+            && !q.is_zero()
+        {
+            let remainder = -p.div_rem(q).1;
+            sequence.push(remainder);
+        }
+        let variations = |x: &Constant| {
+            let signs: Vec<_> = sequence
+                .iter()
+                .map(|p| p.eval(x))
+                .filter(|c| !c.is_zero())
+                .map(|c| c.is_positive())
+                .collect();
+            signs.windows(2).filter(|w| w[0] != w[1]).count()
+        };
+        // the sequence counts the roots in (a, b]
+        let at_start = usize::from(square_free.eval(a).is_zero());
+        variations(a).saturating_sub(variations(b)) + at_start
+    }
+
+    // Factors into the leading coefficient and monic factors with their multiplicity, lowest
+    // degree first. Linear factors come from rational roots and quadratic ones from
+    // Kronecker's method, so a remaining factor of degree six or more may be reducible.
+    pub fn factor(&self) -> ComputeResult<(Constant, Vec<(Polynomial, usize)>)> {
+        let mut factors = Vec::new();
+        for (p, multiplicity) in self.monic().square_free_decomposition() {
+            for factor in p.factor_square_free()? {
+                factors.push((factor, multiplicity));
+            }
+        }
+        factors.sort_by(|(a, _), (b, _)| {
+            a.degree()
+                .cmp(&b.degree())
+                .then_with(|| a.coeffs.cmp(&b.coeffs))
+        });
+        Ok((self.leading(), factors))
+    }
+
+    fn factor_square_free(&self) -> ComputeResult<Vec<Polynomial>> {
+        let mut factors = Vec::new();
+        let mut rest = self.clone();
+        for root in self.rational_roots()? {
+            let factor = Self::linear_factor(root);
+            rest = rest.div_rem(&factor).0;
+            factors.push(factor);
+        }
+        // without rational roots, factors of degree 2 or 3 are irreducible
+        while rest.degree().unwrap_or(0) >= 4 {
+            let Some(factor) = rest.quadratic_factor()? else {
+                break;
+            };
+            rest = rest.div_rem(&factor).0;
+            factors.push(factor);
+        }
+        if rest.degree().unwrap_or(0) > 0 {
+            factors.push(rest.monic());
+        }
+        Ok(factors)
+    }
+
+    // Kronecker's method for a polynomial without rational roots: an integer quadratic
+    // factor `q` has `q(0)`, `q(1)` and `q(-1)` dividing the values of `self` there.
+    fn quadratic_factor(&self) -> ComputeResult<Option<Polynomial>> {
+        const MAX_CANDIDATES: usize = 100_000;
+        let p = Self::new(
+            self.integer_coeffs()
+                .into_iter()
+                .map(Constant::from)
+                .collect(),
+        );
+        let divisors = [0, 1, -1]
+            .iter()
+            .map(|&x| p.eval(&Constant::from(x)).divisors())
+            .collect::<ComputeResult<Option<Vec<_>>>>()?;
+        let Some(divisors) = divisors else {
+            return Ok(None);
+        };
+        if divisors.iter().map(Vec::len).product::<usize>() * 4 > MAX_CANDIDATES {
+            return Ok(None);
+        }
+        let half = Constant::one() / Constant::from(2);
+        let signs = [Constant::one(), -Constant::one()];
+        // `q` and `-q` are both factors, so `q(0)` can be taken positive
+        for c in &divisors[0] {
+            let c = Constant::from(c.clone());
+            for (at_one, at_minus_one) in divisors[1]
+                .iter()
+                .flat_map(|x| divisors[2].iter().map(move |y| (x, y)))
+            {
+                limits::step(|| SimpleExpr::Const(c.clone()))?;
+                for (s1, s2) in signs.iter().flat_map(|x| signs.iter().map(move |y| (x, y))) {
+                    let v1 = Constant::from(at_one.clone()) * s1.clone();
+                    let v2 = Constant::from(at_minus_one.clone()) * s2.clone();
+                    // q = a x^2 + b x + c
+                    let a = (v1.clone() + v2.clone()) * half.clone() - c.clone();
+                    let b = (v1 - v2) * half.clone();
+                    if a.is_zero() || !a.is_integer() || !b.is_integer() {
+                        continue;
+                    }
+                    let q = Self::new(vec![c.clone(), b, a]);
+                    if p.div_rem(&q).1.is_zero() {
+                        return Ok(Some(q.monic()));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    // the coefficients scaled to integers
+    fn integer_coeffs(&self) -> Vec<BigInt> {
+        let lcm = self.coeffs.iter().fold(BigInt::one(), |acc, c| {
+            num::integer::lcm(acc, c.denom().clone())
+        });
+        self.coeffs
+            .iter()
+            .map(|c| (c.clone() * Constant::from(lcm.clone())).to_integer())
+            .collect()
+    }
+
+    // Distinct rational roots in ascending order, found with the rational root theorem.
+    pub fn rational_roots(&self) -> ComputeResult<Vec<Constant>> {
+        let mut roots = Vec::new();
+        let mut p = self.clone();
+        if p.is_zero() {
+            return Ok(roots);
+        }
+        if p.coeffs[0].is_zero() {
+            roots.push(Constant::zero());
+            let shift = p.coeffs.iter().take_while(|c| c.is_zero()).count();
+            p = Self::new(p.coeffs[shift..].to_vec());
+        }
+        let coeffs = p.integer_coeffs();
+        let (Some(constant), Some(leading)) = (
+            Constant::from(coeffs[0].clone()).divisors()?,
+            Constant::from(coeffs[coeffs.len() - 1].clone()).divisors()?,
+        ) else {
+            return Ok(roots);
+        };
+        for numer in &constant {
+            for denom in &leading {
+                let candidate = Constant::from(BigRational::new(numer.clone(), denom.clone()));
+                limits::step(|| SimpleExpr::Const(candidate.clone()))?;
+                for candidate in [candidate.clone(), -candidate] {
+                    if p.eval(&candidate).is_zero() && !roots.contains(&candidate) {
+                        roots.push(candidate);
+                    }
+                }
+            }
+        }
+        roots.sort();
+        Ok(roots)
+    }
+}
+
+impl Add for Polynomial {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let len = self.coeffs.len().max(rhs.coeffs.len());
+        let get = |p: &Self, i: usize| p.coeffs.get(i).cloned().unwrap_or_else(Constant::zero);
+        Self::new((0..len).map(|i| get(&self, i) + get(&rhs, i)).collect())
+    }
+}
+
+impl Neg for Polynomial {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(self.coeffs.into_iter().map(Neg::neg).collect())
+    }
+}
+
+impl Sub for Polynomial {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for Polynomial {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.is_zero() || rhs.is_zero() {
+            return Self::new(vec![]);
+        }
+        let mut coeffs = vec![Constant::zero(); self.coeffs.len() + rhs.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in rhs.coeffs.iter().enumerate() {
+                let updated = coeffs[i + j].clone() + a.clone() * b.clone();
+                coeffs[i + j] = updated;
+            }
+        }
+        Self::new(coeffs)
+    }
+}
@@ -0,0 +1,69 @@
+// Partial fraction decomposition.
+use num::One;
+
+use super::{is_free_of, rational_function, Polynomial};
+use crate::constant::Constant;
+use crate::simplify::{Operation, Product, SimpleExpr, Sum};
+use crate::{BasicAlgebraicExpr, ComputeResult};
+
+// Rewrites a rational function of `var` as a polynomial plus fractions `a / f^k` with
+// `deg a < deg f`, for the factors `f` of the denominator over the rationals. Expressions
+// that are not rational functions with rational coefficients are returned unchanged.
+pub fn apart(expr: &SimpleExpr, var: &str) -> ComputeResult {
+    // factors free of `var` multiply every fraction
+    if let SimpleExpr::Product(factors) = expr
+        && factors.iter().any(|x| is_free_of(x, var))
+    {
+        let (constant, mut dependent): (Vec<_>, Vec<_>) =
+            factors.iter().cloned().partition(|x| is_free_of(x, var));
+        let dependent = match dependent.len() {
+            0 => return Ok(expr.clone()),
+            1 => dependent.pop().unwrap(),
+            _ => SimpleExpr::Product(dependent),
+        };
+        let constant = Product.simplify(constant)?;
+        return match apart(&dependent, var)? {
+            SimpleExpr::Sum(terms) => Sum.simplify(
+                terms
+                    .into_iter()
+                    .map(|x| Product.simplify(vec![constant.clone(), x]))
+                    .collect::<ComputeResult<_>>()?,
+            ),
+            x => Product.simplify(vec![constant, x]),
+        };
+    }
+    let Some((numerator, denominator)) = rational_function(expr, var)? else {
+        return Ok(expr.clone());
+    };
+    if denominator.degree().unwrap_or(0) == 0 {
+        return Ok(expr.clone());
+    }
+    let (leading, factors) = denominator.factor()?;
+    let numerator = numerator.scale(&(Constant::one() / leading));
+    let denominator = denominator.monic();
+    let (quotient, remainder) = numerator.div_rem(&denominator);
+
+    let mut terms = vec![quotient.to_expr(var)?];
+    for (factor, multiplicity) in factors {
+        let power = (0..multiplicity).fold(Polynomial::constant(Constant::one()), |acc, _| {
+            acc * factor.clone()
+        });
+        // the numerator over `factor^multiplicity` is the remainder divided by the other
+        // factors, modulo `factor^multiplicity`
+        let (cofactor, _) = denominator.div_rem(&power);
+        let (_, inverse, _) = cofactor.extended_gcd(&power);
+        let (_, mut numerator) = (remainder.clone() * inverse).div_rem(&power);
+        // the digits of the numerator in base `factor`
+        let base = factor.to_expr(var)?;
+        for k in (1..=multiplicity).rev() {
+            let (quotient, digit) = numerator.div_rem(&factor);
+            if !digit.is_zero() {
+                let power =
+                    BasicAlgebraicExpr::simplify_power(base.clone(), (-(k as i128)).into())?;
+                terms.push(Product.simplify(vec![digit.to_expr(var)?, power])?);
+            }
+            numerator = quotient;
+        }
+    }
+    Sum.simplify(terms)
+}
@@ -0,0 +1,38 @@
+// Mathematica's FullForm, where every operation is written as a call such as
+// `Plus[Times[3, x], Power[y, 2]]`. Read back with `parse::parse_full_form`.
+use crate::simplify::SimpleExpr;
+
+pub fn to_full_form(x: &SimpleExpr) -> String {
+    let mut f = String::new();
+    full_form_print(x, &mut f);
+    f
+}
+
+fn call<'a>(head: &str, args: impl IntoIterator<Item = &'a SimpleExpr>, f: &mut String) {
+    f.push_str(head);
+    f.push('[');
+    for (i, x) in args.into_iter().enumerate() {
+        if i != 0 {
+            f.push_str(", ");
+        }
+        full_form_print(x, f);
+    }
+    f.push(']');
+}
+
+fn full_form_print(x: &SimpleExpr, f: &mut String) {
+    match x {
+        SimpleExpr::Const(c) => match c.as_integer() {
+            Some(n) => f.push_str(&n.to_string()),
+            None => f.push_str(&format!("Rational[{}, {}]", c.numer(), c.denom())),
+        },
+        SimpleExpr::Symbol(x) => f.push_str(x),
+        SimpleExpr::Sum(x) => call("Plus", x, f),
+        SimpleExpr::Product(x) => call("Times", x, f),
+        SimpleExpr::Pow(x) => call("Power", [&x.0, &x.1], f),
+        SimpleExpr::Factorial(x) => call("Factorial", [&**x], f),
+        SimpleExpr::Function(name, args) => call(name, args, f),
+        SimpleExpr::List(x) => call("List", x, f),
+        SimpleExpr::Relation(op, x) => call(op.name(), [&x.0, &x.1], f),
+    }
+}
@@ -0,0 +1,339 @@
+// MathML output. Presentation markup lays out an expression like the LaTeX printer, with
+// fractions for negative powers and radicals for fractional ones. Content markup encodes its
+// structure instead, as in `<apply><plus/>..</apply>`.
+use num::{One, Signed, ToPrimitive};
+
+use super::{matrix_rows, negated, precedence, split_fraction, Precedence};
+use crate::calculus::Series;
+use crate::constant::Constant;
+use crate::relation::RelOp;
+use crate::simplify::SimpleExpr;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MathMl {
+    Presentation,
+    Content,
+}
+
+pub fn to_mathml(x: &SimpleExpr, markup: MathMl) -> String {
+    let mut f = String::from(r#"<math xmlns="http://www.w3.org/1998/Math/MathML">"#);
+    match markup {
+        MathMl::Presentation => presentation(x, &mut f),
+        MathMl::Content => content(x, &mut f),
+    }
+    f.push_str("</math>");
+    f
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn tag(name: &str, f: &mut String, inner: impl FnOnce(&mut String)) {
+    f.push_str(&format!("<{name}>"));
+    inner(f);
+    f.push_str(&format!("</{name}>"));
+}
+
+fn leaf(name: &str, text: &str, f: &mut String) {
+    tag(name, f, |f| f.push_str(&escape(text)));
+}
+
+fn mo(op: &str, f: &mut String) {
+    leaf("mo", op, f);
+}
+
+fn fenced(open: &str, close: &str, f: &mut String, inner: impl FnOnce(&mut String)) {
+    tag("mrow", f, |f| {
+        mo(open, f);
+        inner(f);
+        mo(close, f);
+    });
+}
+
+fn separated(x: &[SimpleExpr], f: &mut String) {
+    for (i, x) in x.iter().enumerate() {
+        if i != 0 {
+            mo(",", f);
+        }
+        presentation(x, f);
+    }
+}
+
+// `x`, in parentheses if it binds less tightly than `context`
+fn operand(x: &SimpleExpr, context: Precedence, f: &mut String) {
+    if precedence(x) < context {
+        fenced("(", ")", f, |f| presentation(x, f));
+    } else {
+        presentation(x, f);
+    }
+}
+
+fn constant(c: &Constant, f: &mut String) {
+    if c.is_negative() {
+        return tag("mrow", f, |f| {
+            mo("-", f);
+            constant(&-c.clone(), f);
+        });
+    }
+    match c.as_integer() {
+        Some(n) => leaf("mn", &n.to_string(), f),
+        None => tag("mfrac", f, |f| {
+            leaf("mn", &c.numer().to_string(), f);
+            leaf("mn", &c.denom().to_string(), f);
+        }),
+    }
+}
+
+fn product_row(factors: &[SimpleExpr], f: &mut String) {
+    match factors {
+        [] => leaf("mn", "1", f),
+        [x] => presentation(x, f),
+        _ => tag("mrow", f, |f| {
+            let factors = match factors {
+                [first, rest @ ..] if *first == -1 => {
+                    mo("-", f);
+                    rest
+                }
+                _ => factors,
+            };
+            for (i, x) in factors.iter().enumerate() {
+                if i != 0 {
+                    mo("\u{22c5}", f);
+                }
+                // a leading coefficient needs no parentheses
+                match x {
+                    SimpleExpr::Const(_) if i == 0 => presentation(x, f),
+                    _ => operand(x, Precedence::Product, f),
+                }
+            }
+        }),
+    }
+}
+
+fn product(factors: &[SimpleExpr], f: &mut String) {
+    let (numer, denom) = split_fraction(factors);
+    if denom.is_empty() {
+        product_row(&numer, f);
+    } else {
+        tag("mfrac", f, |f| {
+            product_row(&numer, f);
+            product_row(&denom, f);
+        });
+    }
+}
+
+// a series as its terms and an order term
+fn series_terms(series: &Series) -> Option<SimpleExpr> {
+    let base = series.base().ok()?;
+    let power = |k: i64| match k {
+        0 => SimpleExpr::from(1),
+        1 => base.clone(),
+        k => SimpleExpr::Pow(Box::new((base.clone(), (k as i128).into()))),
+    };
+    let mut terms: Vec<_> = series
+        .coefficients()
+        .map(|(k, c)| match (k, c) {
+            (0, c) => c.clone(),
+            (k, c) if *c == 1 => power(k),
+            (k, c) => SimpleExpr::Product(vec![c.clone(), power(k)]),
+        })
+        .collect();
+    terms.push(SimpleExpr::Function(
+        "O".into(),
+        vec![power(series.order())],
+    ));
+    Some(SimpleExpr::Sum(terms))
+}
+
+fn relation_symbol(op: RelOp) -> &'static str {
+    match op {
+        RelOp::Equal => "=",
+        RelOp::NotEqual => "\u{2260}",
+        RelOp::Less => "<",
+        RelOp::LessEqual => "\u{2264}",
+        RelOp::Greater => ">",
+        RelOp::GreaterEqual => "\u{2265}",
+    }
+}
+
+fn presentation(x: &SimpleExpr, f: &mut String) {
+    match x {
+        SimpleExpr::Const(c) => constant(c, f),
+        SimpleExpr::Symbol(s) => match s.as_str() {
+            "Pi" => leaf("mi", "\u{3c0}", f),
+            "E" => leaf("mi", "e", f),
+            "Infinity" => leaf("mi", "\u{221e}", f),
+            "ComplexInfinity" => tag("mover", f, |f| {
+                leaf("mi", "\u{221e}", f);
+                mo("~", f);
+            }),
+            s => leaf("mi", s, f),
+        },
+        SimpleExpr::Sum(terms) => tag("mrow", f, |f| {
+            for (i, x) in terms.iter().enumerate() {
+                match negated(x) {
+                    Some(x) if i != 0 => {
+                        mo("-", f);
+                        operand(&x, Precedence::Product, f);
+                    }
+                    _ if i != 0 => {
+                        mo("+", f);
+                        operand(x, Precedence::Sum, f);
+                    }
+                    _ => operand(x, Precedence::Sum, f),
+                }
+            }
+        }),
+        SimpleExpr::Product(factors) => product(factors, f),
+        SimpleExpr::Pow(p) => {
+            let (base, exp) = (&p.0, &p.1);
+            let root = match exp {
+                SimpleExpr::Const(e) if e.numer().is_one() => e.denom().to_u32(),
+                _ => None,
+            };
+            match (exp, root) {
+                (SimpleExpr::Const(e), _) if e.is_negative() => product(std::slice::from_ref(x), f),
+                (_, Some(2)) => tag("msqrt", f, |f| presentation(base, f)),
+                (_, Some(n)) => tag("mroot", f, |f| {
+                    presentation(base, f);
+                    leaf("mn", &n.to_string(), f);
+                }),
+                _ => tag("msup", f, |f| {
+                    operand(base, Precedence::Factorial, f);
+                    presentation(exp, f);
+                }),
+            }
+        }
+        SimpleExpr::Factorial(x) => tag("mrow", f, |f| {
+            operand(x, Precedence::Atom, f);
+            mo("!", f);
+        }),
+        SimpleExpr::Function(name, args)
+            if name == "Approximate"
+                && let [SimpleExpr::Const(c)] = args.as_slice()
+                && let Some(c) = c.to_f64() =>
+        {
+            tag("mrow", f, |f| {
+                mo("\u{2248}", f);
+                leaf("mn", &c.to_string(), f);
+            })
+        }
+        SimpleExpr::Function(..)
+            if let Some(series) = Series::from_series_data(x)
+                && let Some(terms) = series_terms(&series) =>
+        {
+            presentation(&terms, f)
+        }
+        SimpleExpr::Function(name, args) => tag("mrow", f, |f| {
+            leaf("mi", name, f);
+            // function application
+            mo("\u{2061}", f);
+            fenced("(", ")", f, |f| separated(args, f));
+        }),
+        SimpleExpr::List(x) if let Some(rows) = matrix_rows(x) => fenced("(", ")", f, |f| {
+            tag("mtable", f, |f| {
+                for row in rows {
+                    tag("mtr", f, |f| {
+                        for x in row {
+                            tag("mtd", f, |f| presentation(x, f));
+                        }
+                    });
+                }
+            });
+        }),
+        SimpleExpr::List(x) => fenced("{", "}", f, |f| separated(x, f)),
+        SimpleExpr::Relation(op, x) => tag("mrow", f, |f| {
+            operand(&x.0, Precedence::Sum, f);
+            mo(relation_symbol(*op), f);
+            operand(&x.1, Precedence::Sum, f);
+        }),
+    }
+}
+
+// the content markup for a function, `<sin/>` for `Sin`
+fn content_function(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "Sin" => "<sin/>",
+        "Cos" => "<cos/>",
+        "Tan" => "<tan/>",
+        "Cot" => "<cot/>",
+        "Sec" => "<sec/>",
+        "Csc" => "<csc/>",
+        "ArcSin" => "<arcsin/>",
+        "ArcCos" => "<arccos/>",
+        "ArcTan" => "<arctan/>",
+        "Sinh" => "<sinh/>",
+        "Cosh" => "<cosh/>",
+        "Tanh" => "<tanh/>",
+        "Exp" => "<exp/>",
+        "Log" => "<ln/>",
+        "Sqrt" => "<root/>",
+        "Abs" => "<abs/>",
+        _ => return None,
+    })
+}
+
+fn apply(operator: &str, args: &[&SimpleExpr], f: &mut String) {
+    tag("apply", f, |f| {
+        f.push_str(operator);
+        for x in args {
+            content(x, f);
+        }
+    });
+}
+
+fn all(x: &[SimpleExpr]) -> Vec<&SimpleExpr> {
+    x.iter().collect()
+}
+
+fn content(x: &SimpleExpr, f: &mut String) {
+    match x {
+        SimpleExpr::Const(c) => match c.as_integer() {
+            Some(n) => f.push_str(&format!(r#"<cn type="integer">{n}</cn>"#)),
+            None => f.push_str(&format!(
+                r#"<cn type="rational">{}<sep/>{}</cn>"#,
+                c.numer(),
+                c.denom()
+            )),
+        },
+        SimpleExpr::Symbol(s) => match s.as_str() {
+            "Pi" => f.push_str("<pi/>"),
+            "E" => f.push_str("<exponentiale/>"),
+            "Infinity" => f.push_str("<infinity/>"),
+            s => leaf("ci", s, f),
+        },
+        SimpleExpr::Sum(x) => apply("<plus/>", &all(x), f),
+        SimpleExpr::Product(x) => apply("<times/>", &all(x), f),
+        SimpleExpr::Pow(p) => apply("<power/>", &[&p.0, &p.1], f),
+        SimpleExpr::Factorial(x) => apply("<factorial/>", &[x], f),
+        SimpleExpr::Function(name, args) => match content_function(name) {
+            Some(operator) => apply(operator, &all(args), f),
+            None => tag("apply", f, |f| {
+                leaf("ci", name, f);
+                for x in args {
+                    content(x, f);
+                }
+            }),
+        },
+        SimpleExpr::List(x) if let Some(rows) = matrix_rows(x) => tag("matrix", f, |f| {
+            for row in rows {
+                tag("matrixrow", f, |f| row.iter().for_each(|x| content(x, f)));
+            }
+        }),
+        SimpleExpr::List(x) => tag("list", f, |f| x.iter().for_each(|x| content(x, f))),
+        SimpleExpr::Relation(op, x) => {
+            let operator = match op {
+                RelOp::Equal => "<eq/>",
+                RelOp::NotEqual => "<neq/>",
+                RelOp::Less => "<lt/>",
+                RelOp::LessEqual => "<leq/>",
+                RelOp::Greater => "<gt/>",
+                RelOp::GreaterEqual => "<geq/>",
+            };
+            apply(operator, &[&x.0, &x.1], f);
+        }
+    }
+}
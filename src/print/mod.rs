@@ -1,11 +1,132 @@
+use num::{One, Signed, ToPrimitive};
+
+use crate::calculus::Series;
+use crate::constant::Constant;
 use crate::simplify::SimpleExpr;
 
+mod full_form;
+mod mathml;
+
+pub use full_form::to_full_form;
+pub use mathml::{to_mathml, MathMl};
+
+// how tightly the outermost operation of an expression binds, for deciding on parentheses
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub(crate) enum Precedence {
+    Relation,
+    Sum,
+    Product,
+    Power,
+    Factorial,
+    Atom,
+}
+
+pub(crate) fn precedence(x: &SimpleExpr) -> Precedence {
+    match x {
+        SimpleExpr::Relation(..) => Precedence::Relation,
+        SimpleExpr::Sum(_) => Precedence::Sum,
+        // printed with a leading minus
+        SimpleExpr::Const(c) if c.is_negative() => Precedence::Sum,
+        SimpleExpr::Product(_) => Precedence::Product,
+        SimpleExpr::Const(c) if !c.is_integer() => Precedence::Product,
+        SimpleExpr::Pow(_) => Precedence::Power,
+        SimpleExpr::Factorial(_) => Precedence::Factorial,
+        _ => Precedence::Atom,
+    }
+}
+
+// a product as the factors above and below a fraction bar, `2 x/(3 y^2)` for `2/3 x y^(-2)`
+pub(crate) fn split_fraction(factors: &[SimpleExpr]) -> (Vec<SimpleExpr>, Vec<SimpleExpr>) {
+    let (mut numer, mut denom) = (Vec::new(), Vec::new());
+    for x in factors {
+        match x {
+            SimpleExpr::Const(c) if !c.is_integer() => {
+                if !c.numer().is_one() {
+                    numer.push(SimpleExpr::Const(c.numer().clone().into()));
+                }
+                denom.push(SimpleExpr::Const(c.denom().clone().into()));
+            }
+            SimpleExpr::Pow(p)
+                if let SimpleExpr::Const(e) = &p.1
+                    && e.is_negative() =>
+            {
+                let e = -e.clone();
+                denom.push(match e.is_one() {
+                    true => p.0.clone(),
+                    false => SimpleExpr::Pow(Box::new((p.0.clone(), e.into()))),
+                });
+            }
+            x => numer.push(x.clone()),
+        }
+    }
+    (numer, denom)
+}
+
+// `-x` for a term `x` with a negative coefficient
+pub(crate) fn negated(x: &SimpleExpr) -> Option<SimpleExpr> {
+    match x {
+        SimpleExpr::Const(c) if c.is_negative() => Some(SimpleExpr::Const(-c.clone())),
+        SimpleExpr::Product(factors)
+            if let [SimpleExpr::Const(c), rest @ ..] = factors.as_slice()
+                && c.is_negative() =>
+        {
+            let mut factors = rest.to_vec();
+            if *c != Constant::from(-1) {
+                factors.insert(0, SimpleExpr::Const(-c.clone()));
+            }
+            Some(match factors.len() {
+                1 => factors.pop().expect("len == 1"),
+                _ => SimpleExpr::Product(factors),
+            })
+        }
+        _ => None,
+    }
+}
+
+// `x`, in parentheses if it binds less tightly than `context`
+fn latex_operand(x: &SimpleExpr, context: Precedence, f: &mut String) {
+    if precedence(x) < context {
+        f.push('(');
+        latex_print(x, f);
+        f.push(')');
+    } else {
+        latex_print(x, f);
+    }
+}
+
 pub fn to_latex(x: &SimpleExpr) -> String {
     let mut f = String::new();
     latex_print(x, &mut f);
     f
 }
 
+// the rows of a list shaped like a matrix, `{{1, 2}, {3, 4}}`
+fn matrix_rows(x: &[SimpleExpr]) -> Option<Vec<&[SimpleExpr]>> {
+    let rows = x
+        .iter()
+        .map(|row| match row {
+            SimpleExpr::List(row) if !row.is_empty() => Some(row.as_slice()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let cols = rows.first()?.len();
+    rows.iter().all(|row| row.len() == cols).then_some(rows)
+}
+
+// `(x - x0)^k` in a series
+fn power_print(base: &SimpleExpr, k: i64, f: &mut String) {
+    match k {
+        0 => f.push('1'),
+        1 if let SimpleExpr::Symbol(x) = base => f.push_str(x),
+        1 => {
+            f.push('(');
+            latex_print(base, f);
+            f.push(')');
+        }
+        k => latex_print(&SimpleExpr::Pow(Box::new((base.clone(), (k as i128).into()))), f),
+    }
+}
+
 pub fn latex_print(x: &SimpleExpr, f: &mut String) {
     match x {
         SimpleExpr::Const(x) if let Some(i) = x.as_integer() =>  {
@@ -17,6 +138,8 @@ pub fn latex_print(x: &SimpleExpr, f: &mut String) {
             let denom = rational.denom();
             f.push_str(&format!("\\frac {{ {num} }} {{ {denom} }}"));
         }
+        SimpleExpr::Symbol(x) if x == "Infinity" => f.push_str("\\infty"),
+        SimpleExpr::Symbol(x) if x == "ComplexInfinity" => f.push_str("\\tilde{\\infty}"),
         SimpleExpr::Symbol(x) => {
             f.push_str(&x);
         }
@@ -28,7 +151,11 @@ pub fn latex_print(x: &SimpleExpr, f: &mut String) {
                     if i != 0 {
                         f.push_str(" \\cdot ");
                     }
-                    latex_print(x, f);
+                    // a leading coefficient needs no parentheses
+                    match x {
+                        SimpleExpr::Const(_) if i == 0 => latex_print(x, f),
+                        _ => latex_operand(x, Precedence::Product, f),
+                    }
                 }
             }
         }
@@ -48,8 +175,35 @@ pub fn latex_print(x: &SimpleExpr, f: &mut String) {
             f.push_str("}");
         }
         SimpleExpr::Factorial(x) => {
-            latex_print(x, f);
-            f.push_str("!");
+            latex_operand(x, Precedence::Atom, f);
+            f.push('!');
+        }
+        SimpleExpr::Function(x, y)
+            if x == "Approximate"
+                && let [SimpleExpr::Const(c)] = y.as_slice()
+                && let Some(c) = c.to_f64() =>
+        {
+            f.push_str(&format!("\\approx {c}"));
+        }
+        SimpleExpr::Function(..)
+            if let Some(series) = Series::from_series_data(x)
+                && let Ok(base) = series.base() =>
+        {
+            for (k, c) in series.coefficients() {
+                if k == 0 || *c != 1 {
+                    latex_print(c, f);
+                }
+                if k != 0 && *c != 1 {
+                    f.push_str(" \\cdot ");
+                }
+                if k != 0 {
+                    power_print(&base, k, f);
+                }
+                f.push_str(" + ");
+            }
+            f.push_str("O(");
+            power_print(&base, series.order(), f);
+            f.push_str(")");
         }
         SimpleExpr::Function(x, y) => {
             f.push_str(&x);
@@ -62,5 +216,37 @@ pub fn latex_print(x: &SimpleExpr, f: &mut String) {
             }
             f.push_str(")");
         }
+        SimpleExpr::List(x) if let Some(rows) = matrix_rows(x) => {
+            f.push_str("\\begin{pmatrix} ");
+            for (i, row) in rows.iter().enumerate() {
+                if i != 0 {
+                    f.push_str(" \\\\ ");
+                }
+                for (j, x) in row.iter().enumerate() {
+                    if j != 0 {
+                        f.push_str(" & ");
+                    }
+                    latex_print(x, f);
+                }
+            }
+            f.push_str(" \\end{pmatrix}");
+        }
+        SimpleExpr::List(x) => {
+            f.push_str("\\left\\{ ");
+            for (i, x) in x.iter().enumerate() {
+                if i != 0 {
+                    f.push_str(", ");
+                }
+                latex_print(x, f);
+            }
+            f.push_str(" \\right\\}");
+        }
+        SimpleExpr::Relation(op, x) => {
+            latex_print(&x.0, f);
+            f.push(' ');
+            f.push_str(op.latex());
+            f.push(' ');
+            latex_print(&x.1, f);
+        }
     }
 }
\ No newline at end of file
@@ -1,9 +1,24 @@
 use std::ops::Add;
 
-use num::{BigInt, BigRational, One, Zero};
+use num::{BigInt, BigRational, One, Signed, Zero};
 
+use crate::limits::check_power;
 use crate::simplify::SimpleExpr;
-use crate::{ComputeResult, Constant, Undefined};
+use crate::{ComputeError, ComputeResult, Constant};
+
+// `0^exp`, for a division by zero
+pub(crate) fn zero_power(exp: SimpleExpr) -> ComputeError {
+    ComputeError::DivisionByZero(SimpleExpr::Pow(Box::new((0.into(), exp))))
+}
+
+// `x/0`, kept as `x 0^(-1)`
+pub(crate) fn divided_by_zero(x: SimpleExpr) -> ComputeError {
+    ComputeError::DivisionByZero(SimpleExpr::Product(vec![x, reciprocal(0.into())]))
+}
+
+fn reciprocal(x: SimpleExpr) -> SimpleExpr {
+    SimpleExpr::Pow(Box::new((x, (-1).into())))
+}
 
 #[derive(Debug)]
 pub enum RationalExpr {
@@ -24,7 +39,8 @@ impl From<Constant> for RationalExpr {
 pub enum SimplifiedRationalExpression {
     Frac(BigRational),
     Num(BigInt),
-    Undefined,
+    // a constant with a zero denominator, as the error it causes
+    Undefined(ComputeError),
 }
 
 impl SimplifiedRationalExpression {
@@ -32,7 +48,7 @@ impl SimplifiedRationalExpression {
         match self {
             Self::Frac(x) => Ok(SimpleExpr::Const(x.into())),
             Self::Num(x) => Ok(SimpleExpr::Const(x.into())),
-            Self::Undefined => Err(Undefined),
+            Self::Undefined(e) => Err(e),
         }
     }
 }
@@ -41,7 +57,7 @@ impl From<Constant> for SimplifiedRationalExpression {
     fn from(x: Constant) -> Self {
         let denom = x.denom();
         if denom == &BigInt::from(0) {
-            Self::Undefined
+            Self::Undefined(divided_by_zero(SimpleExpr::Const(x.numer().clone().into())))
         } else if x.denom() == &BigInt::from(1) {
             Self::Num(x.to_integer())
         } else {
@@ -51,29 +67,59 @@ impl From<Constant> for SimplifiedRationalExpression {
 }
 
 impl RationalExpr {
-    fn simplify_to_const(self) -> Constant {
+    fn simplify_to_const(self) -> ComputeResult<Constant> {
         use RationalExpr::*;
-        match self {
+        let all = |x: Vec<RationalExpr>| x.into_iter().map(RationalExpr::simplify_to_const);
+        Ok(match self {
             Const(x) => x,
-            Add(x) => x.into_iter().map(|x| x.simplify_to_const()).sum(),
-            Mul(x) => x.into_iter().map(|x| x.simplify_to_const()).product(),
-            Sub(x) => x
-                .into_iter()
-                .map(|x| x.simplify_to_const())
-                .fold(Zero::zero(), |acc, x| acc - x),
-            Div(x) => x
-                .into_iter()
-                .map(|x| x.simplify_to_const())
-                .fold(One::one(), |acc, x| acc / x),
+            Add(x) => all(x).sum::<ComputeResult<_>>()?,
+            Mul(x) => all(x).product::<ComputeResult<_>>()?,
+            Sub(x) => all(x).try_fold(Zero::zero(), |acc, x| x.map(|x| acc - x))?,
+            Div(x) => x.into_iter().try_fold(One::one(), |acc, x| {
+                let divisor = x.to_expr();
+                let x = x.simplify_to_const()?;
+                if x.is_zero() {
+                    return Err(ComputeError::DivisionByZero(reciprocal(divisor)));
+                }
+                Ok(acc / x)
+            })?,
             Pow(base, exp) => {
-                let base = base.simplify_to_const();
+                // the base of a reciprocal, which is reported if it is zero
+                let divisor = exp.is_negative().then(|| base.to_expr());
+                let base = base.simplify_to_const()?;
+                if base.is_zero()
+                    && let Some(divisor) = divisor
+                {
+                    let power = SimpleExpr::Pow(Box::new((divisor, SimpleExpr::Const(exp.into()))));
+                    return Err(ComputeError::DivisionByZero(power));
+                }
+                check_power(&base, &exp)?;
                 num::pow::Pow::pow(base, &exp)
             }
+        })
+    }
+
+    // the expression before evaluation, for reporting which part of it divides by zero
+    fn to_expr(&self) -> SimpleExpr {
+        use RationalExpr::*;
+        let all = |x: &[RationalExpr], f: fn(SimpleExpr) -> SimpleExpr| {
+            x.iter().map(|x| f(x.to_expr())).collect()
+        };
+        match self {
+            Const(x) => SimpleExpr::Const(x.clone()),
+            Add(x) => SimpleExpr::Sum(all(x, |x| x)),
+            Mul(x) => SimpleExpr::Product(all(x, |x| x)),
+            Sub(x) => SimpleExpr::Sum(all(x, |x| SimpleExpr::Product(vec![(-1).into(), x]))),
+            Div(x) => SimpleExpr::Product(all(x, reciprocal)),
+            Pow(base, exp) => SimpleExpr::Pow(Box::new((
+                base.to_expr(),
+                SimpleExpr::Const(exp.clone().into()),
+            ))),
         }
     }
 
-    pub fn simplify(self) -> SimplifiedRationalExpression {
-        self.simplify_to_const().into()
+    pub fn simplify(self) -> ComputeResult<SimplifiedRationalExpression> {
+        Ok(self.simplify_to_const()?.into())
     }
 }
 
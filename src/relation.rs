@@ -0,0 +1,80 @@
+use crate::simplify::{Operation, SimpleExpr, Sum};
+use crate::ComputeResult;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RelOp {
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+impl RelOp {
+    // the Mathematica name, e.g. `Equal`
+    pub fn name(self) -> &'static str {
+        match self {
+            RelOp::Equal => "Equal",
+            RelOp::NotEqual => "Unequal",
+            RelOp::Less => "Less",
+            RelOp::LessEqual => "LessEqual",
+            RelOp::Greater => "Greater",
+            RelOp::GreaterEqual => "GreaterEqual",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<RelOp> {
+        [
+            RelOp::Equal,
+            RelOp::NotEqual,
+            RelOp::Less,
+            RelOp::LessEqual,
+            RelOp::Greater,
+            RelOp::GreaterEqual,
+        ]
+        .into_iter()
+        .find(|op| op.name() == name)
+    }
+
+    pub fn latex(self) -> &'static str {
+        match self {
+            RelOp::Equal => "=",
+            RelOp::NotEqual => "\\neq",
+            RelOp::Less => "<",
+            RelOp::LessEqual => "\\leq",
+            RelOp::Greater => ">",
+            RelOp::GreaterEqual => "\\geq",
+        }
+    }
+
+    // the operator that holds after swapping both sides, `a < b` <=> `b > a`
+    pub fn reversed(self) -> RelOp {
+        match self {
+            RelOp::Less => RelOp::Greater,
+            RelOp::LessEqual => RelOp::GreaterEqual,
+            RelOp::Greater => RelOp::Less,
+            RelOp::GreaterEqual => RelOp::LessEqual,
+            op => op,
+        }
+    }
+}
+
+impl SimpleExpr {
+    pub fn relation(op: RelOp, lhs: SimpleExpr, rhs: SimpleExpr) -> SimpleExpr {
+        SimpleExpr::Relation(op, Box::new((lhs, rhs)))
+    }
+
+    // Rewrites `a op b` into `a - b op 0`. Other expressions are returned unchanged.
+    pub fn to_one_side(self) -> ComputeResult {
+        match self {
+            SimpleExpr::Relation(op, sides) => {
+                let (lhs, rhs) = *sides;
+                let difference = Sum.simplify(vec![lhs, rhs.negate()?])?;
+                Ok(SimpleExpr::relation(op, difference, 0.into()))
+            }
+            x => Ok(x),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// The JSON representation, with the `serde` feature. Nodes are tagged with their variant and
+// hold their fields in `args`:
+//
+//     x^2 + 1/3   {"type": "Sum", "args": [
+//                     {"type": "Const", "args": "1/3"},
+//                     {"type": "Pow", "args": [
+//                         {"type": "Symbol", "args": "x"},
+//                         {"type": "Const", "args": "2"}]}]}
+//     Sin[x]      {"type": "Function", "args": ["Sin", [{"type": "Symbol", "args": "x"}]]}
+//     a < b       {"type": "Relation", "args": ["Less", [{..}, {..}]]}
+//
+// `Constant`s are strings, `"num/den"` or `"num"` for integers, so that they are exact. This
+// format is stable: variants may be added, but existing ones keep their shape.
+use std::fmt;
+
+use num::BigRational;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::constant::Constant;
+
+impl Serialize for Constant {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&**self)
+    }
+}
+
+struct ConstantVisitor;
+
+impl Visitor<'_> for ConstantVisitor {
+    type Value = Constant;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a rational number as \"num/den\"")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Constant, E> {
+        let (numer, denom) = s.split_once('/').unwrap_or((s, "1"));
+        let parse = |x: &str| {
+            x.trim()
+                .parse()
+                .map_err(|_| E::custom(format!("invalid integer {x:?}")))
+        };
+        let (numer, denom) = (parse(numer)?, parse(denom)?);
+        if num::Zero::is_zero(&denom) {
+            return Err(E::custom("zero denominator"));
+        }
+        Ok(BigRational::new(numer, denom).into())
+    }
+}
+
+impl<'de> Deserialize<'de> for Constant {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(ConstantVisitor)
+    }
+}
@@ -0,0 +1,191 @@
+// A session keeps what is assumed about symbols between calls, and a cache of simplified
+// subexpressions. Entries are keyed by the expression together with the assumptions in effect,
+// so an answer is never reused under different assumptions. The least recently used entries are
+// evicted once the cache is full.
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use crate::assumptions::{Assumption, Assumptions};
+use crate::simplify::SimpleExpr;
+use crate::{trace, BasicAlgebraicExpr, ComputeResult};
+
+const DEFAULT_CAPACITY: usize = 4096;
+
+type Key = (BasicAlgebraicExpr, Assumptions);
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub len: usize,
+}
+
+struct Cache {
+    capacity: usize,
+    entries: HashMap<Rc<Key>, (SimpleExpr, u64)>,
+    // entries by the time they were last used
+    recent: BTreeMap<u64, Rc<Key>>,
+    time: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl Cache {
+    fn new(capacity: usize) -> Self {
+        Cache {
+            capacity,
+            entries: HashMap::new(),
+            recent: BTreeMap::new(),
+            time: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    fn get(&mut self, key: &Key) -> Option<SimpleExpr> {
+        self.time += 1;
+        let Some((value, used)) = self.entries.get_mut(key) else {
+            self.misses += 1;
+            return None;
+        };
+        self.hits += 1;
+        let key = self.recent.remove(used).expect("entries are in `recent`");
+        *used = self.time;
+        self.recent.insert(self.time, key);
+        Some(value.clone())
+    }
+
+    fn insert(&mut self, key: Key, value: SimpleExpr) {
+        if self.capacity == 0 || self.entries.contains_key(&key) {
+            return;
+        }
+        if self.entries.len() == self.capacity
+            && let Some((_, oldest)) = self.recent.pop_first()
+        {
+            self.entries.remove(&oldest);
+        }
+        self.time += 1;
+        let key = Rc::new(key);
+        self.recent.insert(self.time, key.clone());
+        self.entries.insert(key, (value, self.time));
+    }
+}
+
+thread_local! {
+    // the cache of the session that is simplifying on this thread
+    static CACHE: RefCell<Option<Cache>> = const { RefCell::new(None) };
+}
+
+// `simplify(x)`, with the result taken from or stored in the cache of the current session
+pub(crate) fn memoize(
+    x: BasicAlgebraicExpr,
+    ctx: &Assumptions,
+    simplify: impl FnOnce(BasicAlgebraicExpr) -> ComputeResult,
+) -> ComputeResult {
+    // steps are not recorded for cached answers
+    let enabled = CACHE.with(|cache| cache.borrow().is_some()) && !trace::is_enabled();
+    if !enabled
+        || matches!(
+            x,
+            BasicAlgebraicExpr::Const(_) | BasicAlgebraicExpr::Symbol(_)
+        )
+    {
+        return simplify(x);
+    }
+    let key = (x, ctx.clone());
+    let cached = CACHE.with(|cache| cache.borrow_mut().as_mut().and_then(|x| x.get(&key)));
+    if let Some(value) = cached {
+        return Ok(value);
+    }
+    let value = simplify(key.0.clone())?;
+    CACHE.with(|cache| {
+        if let Some(cache) = &mut *cache.borrow_mut() {
+            cache.insert(key, value.clone());
+        }
+    });
+    Ok(value)
+}
+
+// gives the cache back to its session and restores the enclosing one when dropped, also when
+// simplifying panics
+struct Restore<'a> {
+    cache: &'a mut Option<Cache>,
+    outer: Option<Cache>,
+}
+
+impl Drop for Restore<'_> {
+    fn drop(&mut self) {
+        let outer = self.outer.take();
+        *self.cache = CACHE.with(|cache| cache.replace(outer));
+    }
+}
+
+pub struct Session {
+    assumptions: Assumptions,
+    cache: Option<Cache>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Session {
+            assumptions: Assumptions::new(),
+            cache: Some(Cache::new(capacity)),
+        }
+    }
+
+    pub fn without_cache() -> Self {
+        Session {
+            assumptions: Assumptions::new(),
+            cache: None,
+        }
+    }
+
+    pub fn assumptions(&self) -> &Assumptions {
+        &self.assumptions
+    }
+
+    pub fn assume(&mut self, symbol: &str, assumption: Assumption) {
+        self.assumptions.assume(symbol, assumption);
+    }
+
+    pub fn simplify(&mut self, x: BasicAlgebraicExpr) -> ComputeResult {
+        self.with_cache(|assumptions| x.simplify_with(assumptions))
+    }
+
+    // runs `f` with the cache of this session installed on the current thread
+    pub(crate) fn with_cache<T>(&mut self, f: impl FnOnce(&Assumptions) -> T) -> T {
+        let outer = CACHE.with(|cache| cache.replace(self.cache.take()));
+        let _restore = Restore {
+            cache: &mut self.cache,
+            outer,
+        };
+        f(&self.assumptions)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.cache
+            .as_ref()
+            .map_or_else(CacheStats::default, |x| CacheStats {
+                hits: x.hits,
+                misses: x.misses,
+                len: x.entries.len(),
+            })
+    }
+
+    pub fn clear_cache(&mut self) {
+        if let Some(cache) = &mut self.cache {
+            *cache = Cache::new(cache.capacity);
+        }
+    }
+}
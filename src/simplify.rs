@@ -1,9 +1,20 @@
 use crate::rational_expressions::RationalExpr;
-use crate::Constant;
+use crate::relation::RelOp;
+use crate::{BasicAlgebraicExpr, ComputeResult, Constant};
 
+mod expand;
+mod functions;
+mod list;
 mod ops;
 
+pub(crate) use ops::{Operation, Product, Sum};
+
 #[derive(PartialEq, Eq, Debug, Clone, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", content = "args")
+)]
 pub enum SimpleExpr {
     Const(Constant),
     Symbol(String),
@@ -12,6 +23,8 @@ pub enum SimpleExpr {
     Pow(Box<(SimpleExpr, SimpleExpr)>),
     Factorial(Box<SimpleExpr>),
     Function(String, Vec<SimpleExpr>),
+    List(Vec<SimpleExpr>),
+    Relation(RelOp, Box<(SimpleExpr, SimpleExpr)>),
 }
 
 impl SimpleExpr {
@@ -58,6 +71,57 @@ impl SimpleExpr {
         })
     }
 
+    // the Mathematica-style head of this expression, e.g. `Plus` for sums
+    pub fn head(&self) -> &str {
+        match self {
+            SimpleExpr::Const(c) if c.is_integer() => "Integer",
+            SimpleExpr::Const(_) => "Rational",
+            SimpleExpr::Symbol(_) => "Symbol",
+            SimpleExpr::Product(_) => "Times",
+            SimpleExpr::Sum(_) => "Plus",
+            SimpleExpr::Pow(_) => "Power",
+            SimpleExpr::Factorial(_) => "Factorial",
+            SimpleExpr::Function(name, _) => name,
+            SimpleExpr::List(_) => "List",
+            SimpleExpr::Relation(op, _) => op.name(),
+        }
+    }
+
+    // `Infinity`, `ComplexInfinity` and `Indeterminate` are symbols, like `Pi`. They are
+    // the results of limits. Sums and products of them follow `Operation::simplify_infinite`.
+    pub fn infinity() -> Self {
+        SimpleExpr::Symbol("Infinity".into())
+    }
+
+    pub fn negative_infinity() -> Self {
+        SimpleExpr::Product(vec![(-1).into(), Self::infinity()])
+    }
+
+    pub fn complex_infinity() -> Self {
+        SimpleExpr::Symbol("ComplexInfinity".into())
+    }
+
+    pub fn indeterminate() -> Self {
+        SimpleExpr::Symbol("Indeterminate".into())
+    }
+
+    // multiplies by -1, distributing over sums so that like terms can cancel afterwards
+    pub fn negate(self) -> ComputeResult {
+        match self {
+            SimpleExpr::Sum(x) => Sum.simplify(
+                x.into_iter()
+                    .map(Self::negate)
+                    .collect::<ComputeResult<_>>()?,
+            ),
+            x => Product.simplify(vec![(-1).into(), x]),
+        }
+    }
+
+    // replaces `var` by `value` and simplifies the result
+    pub fn substitute(&self, var: &str, value: &SimpleExpr) -> ComputeResult {
+        BasicAlgebraicExpr::from(list::substitute(self.clone(), var, value)).simplify()
+    }
+
     pub fn exponent(&self) -> Option<SimpleExpr> {
         Some(match self {
             SimpleExpr::Pow(x) => x.1.clone(),
@@ -0,0 +1,70 @@
+use num::{Signed, ToPrimitive};
+
+use super::{Operation, Product, SimpleExpr, Sum};
+use crate::{BasicAlgebraicExpr, ComputeResult};
+
+impl SimpleExpr {
+    // Distributes products over sums and expands positive integer powers of sums.
+    // Arguments of functions are left alone.
+    pub fn expand(self) -> ComputeResult {
+        match self {
+            SimpleExpr::Sum(x) => Sum.simplify(
+                x.into_iter()
+                    .map(Self::expand)
+                    .collect::<ComputeResult<_>>()?,
+            ),
+            SimpleExpr::Product(x) => {
+                let mut factors = x.into_iter().map(Self::expand);
+                let first = factors.next().expect("products are never empty")?;
+                factors.try_fold(first, |acc, x| expand_product(acc, x?))
+            }
+            SimpleExpr::Pow(x) => {
+                let (base, exp) = *x;
+                let base = base.expand()?;
+                let power = match &exp {
+                    SimpleExpr::Const(n) if n.is_positive() => {
+                        n.as_integer().and_then(|n| n.to_usize())
+                    }
+                    _ => None,
+                };
+                match (&base, power) {
+                    (SimpleExpr::Sum(_), Some(n)) => {
+                        let mut acc = base.clone();
+                        for _ in 1..n {
+                            acc = expand_product(acc, base.clone())?;
+                        }
+                        Ok(acc)
+                    }
+                    _ => BasicAlgebraicExpr::simplify_power(base, exp),
+                }
+            }
+            SimpleExpr::List(x) => Ok(SimpleExpr::List(
+                x.into_iter()
+                    .map(Self::expand)
+                    .collect::<ComputeResult<_>>()?,
+            )),
+            SimpleExpr::Relation(op, x) => {
+                let (lhs, rhs) = *x;
+                Ok(SimpleExpr::relation(op, lhs.expand()?, rhs.expand()?))
+            }
+            x => Ok(x),
+        }
+    }
+}
+
+// multiplies two expanded expressions, keeping the result expanded
+fn expand_product(a: SimpleExpr, b: SimpleExpr) -> ComputeResult {
+    match (a, b) {
+        (SimpleExpr::Sum(x), b) => Sum.simplify(
+            x.into_iter()
+                .map(|a| expand_product(a, b.clone()))
+                .collect::<ComputeResult<_>>()?,
+        ),
+        (a, SimpleExpr::Sum(x)) => Sum.simplify(
+            x.into_iter()
+                .map(|b| expand_product(a.clone(), b))
+                .collect::<ComputeResult<_>>()?,
+        ),
+        (a, b) => Product.simplify(vec![a, b]),
+    }
+}
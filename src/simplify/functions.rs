@@ -0,0 +1,356 @@
+// Evaluation of built-in functions such as `Solve[eqs, vars]`. Calls that are not
+// recognized, or whose arguments have the wrong shape, are kept as they are.
+use num::ToPrimitive;
+
+use super::{list, Operation, Product, SimpleExpr};
+use crate::assumptions::{is_integer, is_positive, is_real, Assumption, Assumptions};
+use crate::calculus::{
+    derivative, integrate, integrate_definite, limit, product, series, sum, Direction, Series,
+};
+use crate::constant::Constant;
+use crate::linear::{solve_linear, LinearSolution};
+use crate::matrix::Matrix;
+use crate::poly::apart;
+use crate::relation::RelOp;
+use crate::solve::solve;
+use crate::trace::{self, record};
+use crate::visit::Node;
+use crate::{BasicAlgebraicExpr, ComputeError, ComputeResult};
+
+fn as_list(x: &SimpleExpr) -> Option<&[SimpleExpr]> {
+    match x {
+        SimpleExpr::List(elements) => Some(elements),
+        _ => None,
+    }
+}
+
+// exact values of elementary functions at a few points
+fn special_value(
+    name: &str,
+    x: &SimpleExpr,
+    ctx: &Assumptions,
+) -> ComputeResult<Option<SimpleExpr>> {
+    let pi = |n: i128, d: i128| {
+        let coefficient = SimpleExpr::Const(Constant::from(n) / Constant::from(d));
+        Product.simplify(vec![coefficient, SimpleExpr::Symbol("Pi".into())])
+    };
+    let pi_symbol = SimpleExpr::Symbol("Pi".into());
+    let is_pi = *x == pi_symbol;
+    // `x = n Pi/2` for an integer `n`, as `n mod 4`
+    let quarter_turn = match x {
+        SimpleExpr::Product(factors) if let [SimpleExpr::Const(c), SimpleExpr::Symbol(s)] =
+            factors.as_slice() && s == "Pi" =>
+        {
+            (c.clone() * Constant::from(2))
+                .as_integer()
+                .and_then(|n| (n % num::BigInt::from(4)).to_i64())
+                .map(|n| n.rem_euclid(4))
+        }
+        _ => None,
+    };
+    // `x = n Pi` for a symbolic integer `n`
+    let turns = match x {
+        SimpleExpr::Product(factors)
+            if let Some(i) = factors.iter().position(|x| *x == pi_symbol) =>
+        {
+            let mut n = factors.clone();
+            n.remove(i);
+            let n = Product.simplify(n)?;
+            is_integer(&n, ctx).then_some(n)
+        }
+        _ => None,
+    };
+    Ok(Some(match name {
+        "Exp" | "Cos" | "Cosh" if *x == 0 => 1.into(),
+        "Sin" | "Tan" | "Sinh" | "Tanh" | "ArcSin" | "ArcTan" if *x == 0 => 0.into(),
+        "Log" if *x == 1 => 0.into(),
+        "Sin" | "Tan" if is_pi => 0.into(),
+        "Cos" if is_pi => (-1).into(),
+        "Sin" | "Cos" if let Some(n) = quarter_turn => {
+            let n = if name == "Cos" { n + 1 } else { n };
+            [0, 1, 0, -1][(n % 4) as usize].into()
+        }
+        "Sin" | "Tan" if turns.is_some() => 0.into(),
+        "Cos" if let Some(n) = turns => {
+            BasicAlgebraicExpr::simplify_power_with((-1).into(), n, ctx)?
+        }
+        "Sqrt" if let SimpleExpr::Pow(p) = x
+            && is_positive(&p.0, ctx)
+            && is_real(&p.1, ctx) =>
+        {
+            let half = SimpleExpr::Const(Constant::from(1) / Constant::from(2));
+            let exponent = Product.simplify(vec![p.1.clone(), half])?;
+            BasicAlgebraicExpr::simplify_power_with(p.0.clone(), exponent, ctx)?
+        }
+        "Log" if let SimpleExpr::Function(f, y) = x
+            && f == "Exp"
+            && let [y] = y.as_slice()
+            && is_real(y, ctx) =>
+        {
+            y.clone()
+        }
+        "ArcCos" if *x == 0 => pi(1, 2)?,
+        "ArcCos" if *x == 1 => 0.into(),
+        "ArcTan" if *x == 1 => pi(1, 4)?,
+        "ArcTan" if *x == -1 => pi(-1, 4)?,
+        _ => return Ok(None),
+    }))
+}
+
+// `Rule[x, value]` from `x -> value` with a symbol on the left
+fn as_rule(x: &SimpleExpr) -> Option<(&str, &SimpleExpr)> {
+    match x {
+        SimpleExpr::Function(name, args) if name == "Rule" && let [lhs, rhs] = args.as_slice() => {
+            Some((as_symbol(lhs)?, rhs))
+        }
+        _ => None,
+    }
+}
+
+fn as_symbol(x: &SimpleExpr) -> Option<&str> {
+    match x {
+        SimpleExpr::Symbol(s) => Some(s),
+        _ => None,
+    }
+}
+
+// `args` have already been simplified
+pub(crate) fn evaluate(
+    name: String,
+    args: Vec<SimpleExpr>,
+    ctx: &Assumptions,
+) -> ComputeResult {
+    let result = match (name.as_str(), args.as_slice()) {
+        ("Refine", [x, conditions]) => refine(x, conditions, ctx)?,
+        ("Solve", [equations, vars]) => evaluate_solve(equations, vars)?,
+        ("Length", [x]) => Some(list::length(x)),
+        ("Part", [SimpleExpr::List(x), index]) => list::part(x, index)?,
+        ("Table", [expr, SimpleExpr::List(iterator)]) => list::table(expr, iterator)?,
+        ("Map", [SimpleExpr::Symbol(f), SimpleExpr::List(x)]) => Some(list::map(f, x)?),
+        ("Dot", [a, b]) => dot(a, b)?,
+        ("IdentityMatrix", [SimpleExpr::Const(n)]) => {
+            match n.as_integer().and_then(|n| n.to_usize()) {
+                Some(n) => Some(Matrix::identity(n).to_expr()),
+                None => return Err(ComputeError::domain("IdentityMatrix", args[0].clone())),
+            }
+        }
+        ("CharacteristicPolynomial", [m, SimpleExpr::Symbol(x)]) => match Matrix::from_expr(m) {
+            Some(m) => Some(m.characteristic_polynomial(x)?),
+            None => None,
+        },
+        ("D", [f, SimpleExpr::Symbol(x)]) => Some(derivative(f, x)?),
+        ("Integrate", [f, SimpleExpr::Symbol(x)]) => Some(integrate(f, x)?),
+        ("Integrate", [f, SimpleExpr::List(spec)]) => match spec.as_slice() {
+            [SimpleExpr::Symbol(x), a, b] => {
+                integrate_definite(f, x, a, b)?.map(|integral| integral.to_expr())
+            }
+            _ => None,
+        },
+        ("Limit", [f, point, direction @ ..]) if let Some((x, point)) = as_rule(point) => {
+            let direction = match direction {
+                [] => Direction::TwoSided,
+                [rule] => match as_rule(rule) {
+                    Some(("Direction", SimpleExpr::Symbol(s))) if s == "FromAbove" => {
+                        Direction::FromAbove
+                    }
+                    Some(("Direction", SimpleExpr::Symbol(s))) if s == "FromBelow" => {
+                        Direction::FromBelow
+                    }
+                    _ => return Ok(SimpleExpr::Function(name, args)),
+                },
+                _ => return Ok(SimpleExpr::Function(name, args)),
+            };
+            limit(f, x, point, direction)?
+        }
+        ("Series", [f, SimpleExpr::List(spec)]) => match spec.as_slice() {
+            [SimpleExpr::Symbol(x), x0, SimpleExpr::Const(n)]
+                if let Some(n) = n.as_integer().and_then(|n| n.to_i64()) =>
+            {
+                series(f, x, x0, n)?.map(|s| s.to_series_data())
+            }
+            _ => None,
+        },
+        ("Normal", [s]) if let Some(s) = Series::from_series_data(s) => Some(s.to_expr()?),
+        ("Sum" | "Product", [f, SimpleExpr::List(spec)]) => {
+            // `{k, n}` runs from 1 to `n`
+            let (k, a, b) = match spec.as_slice() {
+                [SimpleExpr::Symbol(k), b] => (k, &SimpleExpr::from(1), b),
+                [SimpleExpr::Symbol(k), a, b] => (k, a, b),
+                _ => return Ok(SimpleExpr::Function(name, args)),
+            };
+            match name.as_str() {
+                "Sum" => sum(f, k, a, b)?,
+                _ => product(f, k, a, b)?,
+            }
+        }
+        ("Factorial", [n]) => Some(BasicAlgebraicExpr::simplify_factorial(n.clone())?),
+        ("Apart", [f, SimpleExpr::Symbol(x)]) => Some(apart(f, x)?),
+        ("Apart", [f]) => match f.free_symbols().into_iter().collect::<Vec<_>>().as_slice() {
+            [x] => Some(apart(f, x)?),
+            _ => None,
+        },
+        (_, [x]) if let Some(value) = special_value(&name, x, ctx)? => Some(value),
+        (_, [m]) if let Some(m) = Matrix::from_expr(m) => evaluate_matrix(&name, &m)?,
+        _ => None,
+    };
+    let Some(result) = result else {
+        return Ok(SimpleExpr::Function(name, args));
+    };
+    let before = trace::before(|| SimpleExpr::Function(name, args));
+    record("evaluate function", before, || result.clone());
+    Ok(result)
+}
+
+// `Refine[expr, {x > 0, Element[n, Integers]}]` simplifies again under the conditions
+fn refine(
+    x: &SimpleExpr,
+    conditions: &SimpleExpr,
+    ctx: &Assumptions,
+) -> ComputeResult<Option<SimpleExpr>> {
+    let conditions = match conditions {
+        SimpleExpr::List(x) => x.as_slice(),
+        x => std::slice::from_ref(x),
+    };
+    let mut ctx = ctx.clone();
+    for condition in conditions {
+        let (symbol, assumption) = match condition {
+            SimpleExpr::Relation(op, x) if let (SimpleExpr::Symbol(s), rhs) = (&x.0, &x.1) => {
+                match op {
+                    RelOp::Greater if *rhs == 0 => (s, Assumption::Positive),
+                    RelOp::NotEqual if *rhs == 0 => (s, Assumption::Nonzero),
+                    _ => return Ok(None),
+                }
+            }
+            SimpleExpr::Function(f, args)
+                if f == "Element"
+                    && let [SimpleExpr::Symbol(s), SimpleExpr::Symbol(domain)] = args.as_slice() =>
+            {
+                match domain.as_str() {
+                    "Integers" => (s, Assumption::Integer),
+                    "Reals" => (s, Assumption::Real),
+                    _ => return Ok(None),
+                }
+            }
+            _ => return Ok(None),
+        };
+        ctx.assume(symbol, assumption);
+    }
+    BasicAlgebraicExpr::from(x.clone()).simplify_with(&ctx).map(Some)
+}
+
+// `Solve[eq, x]` gives `{x = a, x = b, ..}`, and `Solve[{eqs}, {x, y}]` gives
+// `{x = a, y = b}`, or `{}` when there is no solution.
+// `None` for an operation the solvers do not support, such as solving `Sin[x] = 0`,
+// so that the call is kept unevaluated
+fn supported<T>(result: ComputeResult<T>) -> ComputeResult<Option<T>> {
+    match result {
+        Err(ComputeError::Unsupported(_)) => Ok(None),
+        result => result.map(Some),
+    }
+}
+
+fn evaluate_solve(equations: &SimpleExpr, vars: &SimpleExpr) -> ComputeResult<Option<SimpleExpr>> {
+    let equals = |var: &str, value| {
+        SimpleExpr::relation(RelOp::Equal, SimpleExpr::Symbol(var.into()), value)
+    };
+    let vars = match as_list(vars) {
+        Some(vars) => vars.iter().map(as_symbol).collect::<Option<Vec<_>>>(),
+        None => as_symbol(vars).map(|v| vec![v]),
+    };
+    let Some(vars) = vars else { return Ok(None) };
+    if let [var] = vars[..]
+        && as_list(equations).is_none()
+    {
+        let Some(solutions) = supported(solve(equations.clone(), var))? else {
+            return Ok(None);
+        };
+        return Ok(Some(SimpleExpr::List(
+            solutions
+                .into_iter()
+                .map(|s| equals(var, s.value))
+                .collect(),
+        )));
+    }
+
+    let equations = match as_list(equations) {
+        Some(equations) => equations.to_vec(),
+        None => vec![equations.clone()],
+    };
+    // systems are only solved when they are linear in `vars`
+    let Some(solution) = supported(solve_linear(equations, &vars))? else {
+        return Ok(None);
+    };
+    Ok(Some(match solution {
+        LinearSolution::Inconsistent => SimpleExpr::List(vec![]),
+        LinearSolution::Solved { values, .. } => SimpleExpr::List(
+            values
+                .into_iter()
+                .map(|(var, value)| equals(&var, value))
+                .collect(),
+        ),
+    }))
+}
+
+fn evaluate_matrix(name: &str, m: &Matrix) -> ComputeResult<Option<SimpleExpr>> {
+    Ok(Some(match name {
+        "Transpose" => m.transpose().to_expr(),
+        "Det" => m.det()?,
+        "Inverse" => m.inverse()?.to_expr(),
+        "MatrixRank" => (m.rank()? as i128).into(),
+        "RowReduce" => m.rref()?.to_expr(),
+        // eigenvalues that cannot be found exactly keep the call unevaluated
+        "Eigenvalues" => match supported(m.eigenvalues())? {
+            Some(eigenvalues) => SimpleExpr::List(
+                eigenvalues
+                    .into_iter()
+                    .flat_map(|s| vec![s.value; s.multiplicity])
+                    .collect(),
+            ),
+            None => return Ok(None),
+        },
+        "Eigenvectors" => match supported(m.eigenvectors())? {
+            Some(eigenvectors) => SimpleExpr::List(
+                eigenvectors
+                    .into_iter()
+                    .flat_map(|(_, basis)| basis)
+                    .map(SimpleExpr::List)
+                    .collect(),
+            ),
+            None => return Ok(None),
+        },
+        "NullSpace" => SimpleExpr::List(m.nullspace()?.into_iter().map(SimpleExpr::List).collect()),
+        _ => return Ok(None),
+    }))
+}
+
+// `Dot[a, b]` multiplies matrices and vectors, where vectors are lists of scalars
+fn dot(a: &SimpleExpr, b: &SimpleExpr) -> ComputeResult<Option<SimpleExpr>> {
+    let vector = |x: &SimpleExpr| match x {
+        SimpleExpr::List(x) if !x.iter().any(|x| matches!(x, SimpleExpr::List(_))) => {
+            Some(x.clone())
+        }
+        _ => None,
+    };
+    let (lhs, rhs) = match (Matrix::from_expr(a), Matrix::from_expr(b)) {
+        (Some(a), Some(b)) => return Ok(Some(a.mul(&b)?.to_expr())),
+        (Some(a), None) => match vector(b) {
+            Some(b) => (a, Matrix::new(b.len(), 1, b)),
+            None => return Ok(None),
+        },
+        (None, Some(b)) => match vector(a) {
+            Some(a) => (Matrix::new(1, a.len(), a), b),
+            None => return Ok(None),
+        },
+        (None, None) => match (vector(a), vector(b)) {
+            (Some(a), Some(b)) => (Matrix::new(1, a.len(), a), Matrix::new(b.len(), 1, b)),
+            _ => return Ok(None),
+        },
+    };
+    let product = lhs.mul(&rhs)?;
+    // vectors stay vectors and the product of two vectors is a scalar
+    let entries: Vec<_> = product.to_rows().into_iter().flatten().collect();
+    Ok(Some(match (vector(a), vector(b)) {
+        (Some(_), Some(_)) => entries.into_iter().next().expect("1 x 1 product"),
+        _ => SimpleExpr::List(entries),
+    }))
+}
@@ -0,0 +1,134 @@
+// Lists and the built-in functions working on them.
+use num::{Signed, ToPrimitive, Zero};
+
+use super::functions::evaluate;
+use super::SimpleExpr;
+use crate::assumptions::Assumptions;
+use crate::limits;
+use crate::visit::Node;
+use crate::{ComputeError, ComputeResult};
+
+// Applies `f` element-wise when some of `args` are lists, repeating the other arguments
+// for every element. This is how `+`, `*` and `^` act on lists. All lists must have the
+// same length.
+pub(crate) fn thread(
+    args: Vec<SimpleExpr>,
+    mut f: impl FnMut(Vec<SimpleExpr>) -> ComputeResult,
+) -> ComputeResult {
+    let mut len = None;
+    for arg in &args {
+        if let SimpleExpr::List(x) = arg {
+            match len {
+                Some(n) if n != x.len() => {
+                    return Err(ComputeError::Unsupported(SimpleExpr::List(args.clone())));
+                }
+                _ => len = Some(x.len()),
+            }
+        }
+    }
+    let Some(len) = len else { return f(args) };
+    (0..len)
+        .map(|i| {
+            f(args
+                .iter()
+                .map(|x| match x {
+                    SimpleExpr::List(x) => x[i].clone(),
+                    x => x.clone(),
+                })
+                .collect())
+        })
+        .collect::<ComputeResult<_>>()
+        .map(SimpleExpr::List)
+}
+
+// Like Mathematica, the length of a non-list is its number of operands.
+pub(crate) fn length(x: &SimpleExpr) -> SimpleExpr {
+    (x.children().len() as i128).into()
+}
+
+// `Part[list, i]`, counting from 1. Negative indices count from the end.
+pub(crate) fn part(list: &[SimpleExpr], index: &SimpleExpr) -> ComputeResult<Option<SimpleExpr>> {
+    let out_of_range = || ComputeError::domain("Part", index.clone());
+    let SimpleExpr::Const(index) = index else {
+        return Ok(None);
+    };
+    let Some(i) = index.as_integer().and_then(|i| i.to_isize()) else {
+        return Err(out_of_range());
+    };
+    let i = if i < 0 {
+        list.len() as isize + i
+    } else {
+        i - 1
+    };
+    match usize::try_from(i).ok().and_then(|i| list.get(i)) {
+        Some(x) => Ok(Some(x.clone())),
+        None => Err(out_of_range()),
+    }
+}
+
+pub(super) fn substitute(expr: SimpleExpr, var: &str, value: &SimpleExpr) -> SimpleExpr {
+    match expr {
+        SimpleExpr::Symbol(s) if s == var => value.clone(),
+        x => x.map_children(|x| substitute(x, var, value)),
+    }
+}
+
+// the most elements a table is expanded into
+const MAX_ELEMENTS: usize = 1_000_000;
+
+// `Table[expr, {i, n}]`, `Table[expr, {i, a, b}]` or `Table[expr, {i, a, b, step}]`
+pub(crate) fn table(
+    expr: &SimpleExpr,
+    iterator: &[SimpleExpr],
+) -> ComputeResult<Option<SimpleExpr>> {
+    let (var, bounds) = match iterator {
+        [SimpleExpr::Symbol(var), bounds @ ..] => (var, bounds),
+        _ => return Ok(None),
+    };
+    let bounds = bounds
+        .iter()
+        .map(|x| match x {
+            SimpleExpr::Const(c) => Some(c.clone()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>();
+    let (start, end, step) = match bounds.as_deref() {
+        Some([n]) => (1.into(), n.clone(), 1.into()),
+        Some([a, b]) => (a.clone(), b.clone(), 1.into()),
+        Some([a, b, step]) if step.is_positive() => (a.clone(), b.clone(), step.clone()),
+        Some([_, _, step]) if step.is_zero() => {
+            return Err(ComputeError::domain(
+                "Table",
+                SimpleExpr::List(iterator.to_vec()),
+            ));
+        }
+        _ => return Ok(None),
+    };
+    // the number of elements is floor((end - start) / step) + 1
+    let last = ((end.clone() - start.clone()) / step.clone()).floor();
+    if last.to_integer() >= MAX_ELEMENTS.into() {
+        let call = SimpleExpr::Function(
+            "Table".into(),
+            vec![expr.clone(), SimpleExpr::List(iterator.to_vec())],
+        );
+        return Err(ComputeError::Overflow(call));
+    }
+
+    let mut elements = Vec::new();
+    let mut i = start;
+    while i <= end {
+        limits::step(|| SimpleExpr::Const(i.clone()))?;
+        let value = SimpleExpr::Const(i.clone());
+        elements.push(expr.substitute(var, &value)?);
+        i = i + step.clone();
+    }
+    Ok(Some(SimpleExpr::List(elements)))
+}
+
+// `Map[f, {a, b}]` gives `{f[a], f[b]}`
+pub(crate) fn map(f: &str, list: &[SimpleExpr]) -> ComputeResult {
+    list.iter()
+        .map(|x| evaluate(f.into(), vec![x.clone()], &Assumptions::default()))
+        .collect::<ComputeResult<_>>()
+        .map(SimpleExpr::List)
+}
@@ -1,9 +1,17 @@
 use std::fmt::Debug;
+use std::slice;
 
+use super::list::thread;
+use crate::assumptions::{is_even, is_negative, is_odd, is_positive, is_real, Assumptions};
 use crate::constant::Constant;
-use crate::rational_expressions::RationalExpr;
-use crate::{BasicAlgebraicExpr, ComputeResult, SimpleExpr, Undefined};
-use num::{BigInt, One, Signed, Zero};
+use crate::limits::{self, check_power};
+use crate::rational_expressions::{divided_by_zero, zero_power, RationalExpr};
+use crate::session;
+use crate::trace::{self, record};
+use crate::visit::Node;
+use crate::{BasicAlgebraicExpr, ComputeError, ComputeResult, SimpleExpr};
+use num::traits::Pow;
+use num::{BigInt, BigRational, One, Signed, ToPrimitive, Zero};
 use smallvec::{smallvec, SmallVec};
 use tracing::debug;
 
@@ -31,106 +39,165 @@ pub trait Operation: Copy + Debug {
 
     fn do_constant(self, x: Constant, y: Constant) -> Constant;
 
+    // the result for operands with `Infinity`, `ComplexInfinity` or `Indeterminate`, which do
+    // not follow the rules for finite values, or `None` without such operands
+    fn simplify_infinite(
+        self,
+        x: Vec<&SimpleExpr>,
+        ctx: &Assumptions,
+    ) -> ComputeResult<Option<SimpleExpr>>;
+
+    // names of the rewrites for `trace`: `2 + 3 = 5`, `x + 0 = x`, `x + 2x = 3x`
+    const CONSTANT_RULE: &'static str;
+    const IDENTITY_RULE: &'static str;
+    const COLLECT_RULE: &'static str;
+
+    // the operation applied to `x`, which may have fewer than two operands
+    fn apply(self, mut x: Vec<SimpleExpr>) -> SimpleExpr {
+        match x.len() {
+            0 => self.identity(),
+            1 => x.pop().expect("len == 1"),
+            _ => self.make_list(x),
+        }
+    }
+
     /// The backbone of simplify_pair. If we can simplify by collecting like terms in addition or powers in multiplication, we do so.
     /// if not, we return `Ok(None)`.
     fn simplify_pair_collect(
         self,
-        a: SimpleExpr,
-        b: SimpleExpr,
+        a: &SimpleExpr,
+        b: &SimpleExpr,
+        ctx: &Assumptions,
     ) -> ComputeResult<Option<SmallVec<[SimpleExpr; 2]>>>;
 
     fn simplify_pair(
         self,
         a: SimpleExpr,
         b: SimpleExpr,
+        ctx: &Assumptions,
     ) -> ComputeResult<SmallVec<[SimpleExpr; 2]>> {
         if self.is_list(&a) || self.is_list(&b) {
             let a = self.extract_or_make_list(a);
             let b = self.extract_or_make_list(b);
-            return self.merge(a, b).map(Into::into);
+            return self.merge(a, b, ctx).map(Into::into);
         }
 
-        Ok(match (a, b) {
+        limits::step(|| self.make_list(vec![a.clone(), b.clone()]))?;
+        let before = trace::before(|| self.make_list(vec![a.clone(), b.clone()]));
+        let (rule, result) = match (a, b) {
             (SimpleExpr::Const(a), SimpleExpr::Const(b)) => {
                 let result = self.do_constant(a, b);
-                if result.is_one() {
+                let result = if self.is_identity(&result) {
                     SmallVec::new()
                 } else {
                     smallvec![result.into()]
-                }
+                };
+                (Some(Self::CONSTANT_RULE), result)
             }
             (SimpleExpr::Const(a), b) | (b, SimpleExpr::Const(a)) if self.is_identity(&a) => {
-                smallvec![b]
+                (Some(Self::IDENTITY_RULE), smallvec![b])
             }
             (a, b) => {
                 // NOTE: when in addition, we merge x + x = 2x, 3x + 4x = 7x, etc.
                 // but when in multiplication, we merge x * x = x^2, x^3 * x^4 = x^7, etc.
 
-                if let Some(res) = self.simplify_pair_collect(a.clone(), b.clone())? {
-                    res
+                if let Some(res) = self.simplify_pair_collect(&a, &b, ctx)? {
+                    (Some(Self::COLLECT_RULE), res)
                 } else if b < a {
-                    smallvec![b, a]
+                    (None, smallvec![b, a])
                 } else {
-                    smallvec![a, b]
+                    (None, smallvec![a, b])
                 }
             }
-        })
+        };
+        if let Some(rule) = rule {
+            record(rule, before, || self.apply(result.to_vec()));
+        }
+        Ok(result)
     }
 
     // requirement: `exprs.len() >= 2`
     #[tracing::instrument(level = "debug", ret)]
-    fn simplify_rec(self, list: Vec<SimpleExpr>) -> ComputeResult<Vec<SimpleExpr>> {
+    fn simplify_rec(
+        self,
+        list: Vec<SimpleExpr>,
+        ctx: &Assumptions,
+    ) -> ComputeResult<Vec<SimpleExpr>> {
         let res: Result<[SimpleExpr; 2], _> = list.try_into();
         match res {
-            Ok([a, b]) => self.simplify_pair(a, b).map(|x| x.into_vec()),
+            Ok([a, b]) => self.simplify_pair(a, b, ctx).map(|x| x.into_vec()),
             Err(mut v) => {
                 assert!(v.len() > 2);
                 let first = v.remove(0);
+                let rest = self.simplify_rec(v, ctx)?;
 
                 let first = self.extract_or_make_list(first);
 
-                self.merge(first, v)
+                self.merge(first, rest, ctx)
             }
         }
     }
 
     #[tracing::instrument(level = "debug")]
-    fn simplify_entry(self, exprs: Vec<BasicAlgebraicExpr>) -> ComputeResult {
+    fn simplify_entry(self, exprs: Vec<BasicAlgebraicExpr>, ctx: &Assumptions) -> ComputeResult {
         let mut exprs: Vec<_> = exprs
             .into_iter()
-            .map(BasicAlgebraicExpr::simplify)
+            .map(|x| x.simplify_with(ctx))
             .collect::<Result<_, _>>()?;
-        exprs.sort_unstable_by(|a, b| a.cmp(b).reverse());
-        self.simplify(exprs)
+        exprs.sort_unstable();
+        self.simplify_with(exprs, ctx)
+    }
+
+    fn simplify(self, exprs: Vec<SimpleExpr>) -> ComputeResult {
+        self.simplify_with(exprs, &Assumptions::default())
     }
 
     #[tracing::instrument(level = "debug", ret)]
-    fn simplify(self, mut exprs: Vec<SimpleExpr>) -> ComputeResult {
-        if Self::HAS_ABSORBING_ELEMENT {
-            for exp in &exprs {
-                if self.is_absorbing_element(exp) {
-                    return Ok(0.into());
-                }
-            }
+    fn simplify_with(self, mut exprs: Vec<SimpleExpr>, ctx: &Assumptions) -> ComputeResult {
+        if exprs.iter().any(|x| matches!(x, SimpleExpr::List(_))) {
+            return thread(exprs, |x| self.simplify_with(x, ctx));
+        }
+
+        let operands = exprs.iter().flat_map(|x| match x {
+            SimpleExpr::Sum(y) | SimpleExpr::Product(y) if self.is_list(x) => y.as_slice(),
+            x => slice::from_ref(x),
+        });
+        if operands.clone().any(has_infinite_factor)
+            && let Some(result) = self.simplify_infinite(operands.collect(), ctx)?
+        {
+            let before = (exprs.len() > 1).then(|| self.make_list(exprs));
+            record("infinity", before, || result.clone());
+            return Ok(result);
+        }
+
+        if Self::HAS_ABSORBING_ELEMENT && exprs.iter().any(|x| self.is_absorbing_element(x)) {
+            let before = (exprs.len() > 1).then(|| self.make_list(exprs));
+            record("multiply by zero", before, || 0.into());
+            return Ok(0.into());
         }
 
         if exprs.len() == 1 {
             return Ok(exprs.pop().expect("len >= 1"));
         }
 
-        let mut list = self.simplify_rec(exprs)?;
-        // TODO replace with deref patterns
-        Ok(match list.len() {
-            0 => self.identity(),
-            1 => list.pop().expect("len == 1"),
-            _ => self.make_list(list),
-        })
+        let mut list = self.simplify_rec(exprs, ctx)?;
+        // merging can produce a constant after other operands, as in 2 * 3^(1/2) * 3^(1/2)
+        if list.iter().skip(1).any(SimpleExpr::is_constant) {
+            list.sort_unstable();
+            return self.simplify_with(list, ctx);
+        }
+        Ok(self.apply(list))
     }
 
     // entry point. Do not call in recursion. Call `merge_into` instead.
-    fn merge(self, a: Vec<SimpleExpr>, b: Vec<SimpleExpr>) -> ComputeResult<Vec<SimpleExpr>> {
+    fn merge(
+        self,
+        a: Vec<SimpleExpr>,
+        b: Vec<SimpleExpr>,
+        ctx: &Assumptions,
+    ) -> ComputeResult<Vec<SimpleExpr>> {
         let mut out = Vec::with_capacity(a.len() + b.len());
-        self.merge_into(a, b, &mut out)?;
+        self.merge_into(a, b, &mut out, ctx)?;
         Ok(out)
     }
 
@@ -140,6 +207,7 @@ pub trait Operation: Copy + Debug {
         mut a: Vec<SimpleExpr>,
         mut b: Vec<SimpleExpr>,
         out: &mut Vec<SimpleExpr>,
+        ctx: &Assumptions,
     ) -> ComputeResult<()> {
         if b.is_empty() {
             out.extend(a);
@@ -158,13 +226,13 @@ pub trait Operation: Copy + Debug {
 
         let would_swap = a > b;
 
-        let simplified = self.simplify_pair(a, b)?;
+        let simplified = self.simplify_pair(a, b, ctx)?;
 
         match simplified.len() {
-            0 => self.merge_into(a_rest, b_rest, out)?,
+            0 => self.merge_into(a_rest, b_rest, out, ctx)?,
             1 => {
                 out.extend(simplified);
-                self.merge_into(a_rest, b_rest, out)?;
+                self.merge_into(a_rest, b_rest, out, ctx)?;
             }
             2 => {
                 let [first, second]: [_; 2] = simplified.into_inner().unwrap();
@@ -176,7 +244,7 @@ pub trait Operation: Copy + Debug {
                 };
 
                 out.push(first);
-                self.merge_into(a_rest, b_rest, out)?;
+                self.merge_into(a_rest, b_rest, out, ctx)?;
             }
             _ => unreachable!("nested operations should have been flattened already"),
         }
@@ -222,18 +290,70 @@ impl Operation for Product {
         x * y
     }
 
+    // `0 Infinity` is indeterminate, `c Infinity` is `Infinity` or `-Infinity`
+    fn simplify_infinite(
+        self,
+        x: Vec<&SimpleExpr>,
+        ctx: &Assumptions,
+    ) -> ComputeResult<Option<SimpleExpr>> {
+        let (infinite, finite): (Vec<_>, Vec<_>) = x.into_iter().partition(|x| is_infinite(x));
+        if infinite.is_empty() {
+            return Ok(None);
+        }
+        if infinite.contains(&&SimpleExpr::indeterminate())
+            || finite.iter().any(|x| self.is_absorbing_element(x))
+        {
+            return Ok(Some(SimpleExpr::indeterminate()));
+        }
+        // `c x Infinity` as `Sign[c] x Infinity`, the sign does not matter for `ComplexInfinity`
+        let is_complex = infinite.contains(&&SimpleExpr::complex_infinity());
+        let finite = match finite.is_empty() {
+            true => self.identity(),
+            false => self.simplify_with(finite.into_iter().cloned().collect(), ctx)?,
+        };
+        let (negative, mut factors) = match finite {
+            SimpleExpr::Const(c) => (c.is_negative(), vec![]),
+            SimpleExpr::Product(mut x) if x[0].is_constant() => {
+                let rest = x.split_off(1);
+                (
+                    matches!(&x[0], SimpleExpr::Const(c) if c.is_negative()),
+                    rest,
+                )
+            }
+            SimpleExpr::Product(x) => (false, x),
+            x => (false, vec![x]),
+        };
+        factors.push(match is_complex {
+            true => SimpleExpr::complex_infinity(),
+            false => SimpleExpr::infinity(),
+        });
+        factors.sort_unstable();
+        if negative && !is_complex {
+            factors.insert(0, (-1).into());
+        }
+        Ok(Some(self.apply(factors)))
+    }
+
+    const CONSTANT_RULE: &'static str = "multiply constants";
+    const IDENTITY_RULE: &'static str = "multiply by one";
+    const COLLECT_RULE: &'static str = "add exponents";
+
     fn simplify_pair_collect(
         self,
-        a: SimpleExpr,
-        b: SimpleExpr,
+        a: &SimpleExpr,
+        b: &SimpleExpr,
+        ctx: &Assumptions,
     ) -> ComputeResult<Option<SmallVec<[SimpleExpr; 2]>>> {
         Ok(
             if let Some(base) = a.base().filter(|x| Some(*x) == b.base()) {
-                let exponent = Sum.simplify(vec![
-                    a.exponent().expect("base() is not None"),
-                    b.exponent().expect("base() is not None"),
-                ])?;
-                let result = BasicAlgebraicExpr::simplify_power(base.clone(), exponent)?;
+                let exponent = Sum.simplify_with(
+                    vec![
+                        a.exponent().expect("base() is not None"),
+                        b.exponent().expect("base() is not None"),
+                    ],
+                    ctx,
+                )?;
+                let result = BasicAlgebraicExpr::simplify_power_with(base.clone(), exponent, ctx)?;
                 Some(if let SimpleExpr::Const(c) = &result && c.is_one() {
                 smallvec![]
             } else {
@@ -246,6 +366,33 @@ impl Operation for Product {
     }
 }
 
+// `Infinity`, `ComplexInfinity` or `Indeterminate`
+fn is_infinite(x: &SimpleExpr) -> bool {
+    matches!(x, SimpleExpr::Symbol(s) if matches!(
+        s.as_str(),
+        "Infinity" | "ComplexInfinity" | "Indeterminate"
+    ))
+}
+
+// whether `x` is infinite or a product with an infinite factor
+fn has_infinite_factor(x: &SimpleExpr) -> bool {
+    match x {
+        SimpleExpr::Product(x) => x.iter().any(is_infinite),
+        x => is_infinite(x),
+    }
+}
+
+// the constant and symbolic factors of a term, `2 x y` as `([2], [x, y])`
+fn split_term(x: &SimpleExpr) -> (&[SimpleExpr], &[SimpleExpr]) {
+    match x {
+        SimpleExpr::Product(factors) => {
+            let i = factors.iter().position(|x| !x.is_constant()).unwrap_or(factors.len());
+            factors.split_at(i)
+        }
+        x => (&[], slice::from_ref(x)),
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Sum;
 
@@ -278,22 +425,81 @@ impl Operation for Sum {
         x + y
     }
 
+    // `Infinity - Infinity` is indeterminate, `Infinity + x` is `Infinity` for finite `x`
+    fn simplify_infinite(
+        self,
+        x: Vec<&SimpleExpr>,
+        _: &Assumptions,
+    ) -> ComputeResult<Option<SimpleExpr>> {
+        let (mut infinite, finite): (Vec<_>, Vec<_>) =
+            x.into_iter().partition(|x| x.pre_order().any(is_infinite));
+        if finite.is_empty() && infinite.len() < 2 {
+            return Ok(None);
+        }
+        infinite.sort_unstable();
+        infinite.dedup();
+        let negated = |x: &SimpleExpr| match x {
+            SimpleExpr::Product(x) if x[0] == -1 => Some(Product.apply(x[1..].to_vec())),
+            _ => None,
+        };
+        let is_complex = infinite.contains(&&SimpleExpr::complex_infinity());
+        if infinite.contains(&&SimpleExpr::indeterminate())
+            || (is_complex && infinite.len() > 1)
+            || infinite
+                .iter()
+                .any(|x| negated(x).is_some_and(|y| infinite.contains(&&y)))
+        {
+            return Ok(Some(SimpleExpr::indeterminate()));
+        }
+        Ok(Some(self.apply(infinite.into_iter().cloned().collect())))
+    }
+
+    const CONSTANT_RULE: &'static str = "add constants";
+    const IDENTITY_RULE: &'static str = "add zero";
+    const COLLECT_RULE: &'static str = "collect like terms";
+
     #[tracing::instrument(level = "debug")]
     // TODO should return smallvec?
     fn simplify_pair_collect(
         self,
-        a: SimpleExpr,
-        b: SimpleExpr,
+        a: &SimpleExpr,
+        b: &SimpleExpr,
+        ctx: &Assumptions,
     ) -> ComputeResult<Option<SmallVec<[SimpleExpr; 2]>>> {
-        let Ok((rationala, a_sym)) = a.split_product() else { return Ok(None) };
-        let Ok((rationalb, b_sym)) = b.split_product() else { return Ok(None) };
+        if a.is_constant() || b.is_constant() {
+            return Ok(None);
+        }
+        let (rationala, a_sym) = split_term(a);
+        let (rationalb, b_sym) = split_term(b);
 
         debug!(?rationala, ?rationalb, ?a_sym, ?b_sym);
 
-        Ok(if a_sym == b_sym {
-            let sum = (rationala + rationalb).simplify().into_algebraic_expr()?;
+        // compare the symbolic parts before building anything
+        Ok(if a_sym == b_sym && !a_sym.is_empty() {
+            let coefficient = |c: &[SimpleExpr]| {
+                RationalExpr::Mul(
+                    c.iter()
+                        .map(|x| match x {
+                            SimpleExpr::Const(c) => c.clone().into(),
+                            _ => unreachable!("constants come first"),
+                        })
+                        .collect(),
+                )
+            };
+            let sum = (coefficient(rationala) + coefficient(rationalb))
+                .simplify()?
+                .into_algebraic_expr()?;
+            let a_sym = match a_sym {
+                [x] => x.clone(),
+                x => SimpleExpr::Product(x.to_vec()),
+            };
             debug!(?sum, ?a_sym);
-            Some(smallvec![Product.simplify(vec![sum, a_sym])?])
+            let product = Product.simplify_with(vec![sum, a_sym], ctx)?;
+            Some(if product == 0 {
+                smallvec![]
+            } else {
+                smallvec![product]
+            })
         } else {
             None
         })
@@ -306,60 +512,195 @@ impl BasicAlgebraicExpr {
         matches!(self, BasicAlgebraicExpr::Const(_))
     }
 
-    fn simplify_integer_power(base: SimpleExpr, exp: &BigInt) -> ComputeResult {
+    fn simplify_integer_power(base: SimpleExpr, exp: &BigInt, ctx: &Assumptions) -> ComputeResult {
         match base {
             _ if exp.is_zero() => Ok(1.into()),
             _ if exp.is_one() => Ok(base),
             SimpleExpr::Const(base) => RationalExpr::Pow(Box::new(base.into()), exp.clone())
-                .simplify()
+                .simplify()?
                 .into(),
             SimpleExpr::Pow(x) => {
                 let (base, exp2) = *x;
-                let exp = Product.simplify(vec![SimpleExpr::Const(exp.clone().into()), exp2])?;
+                let exp =
+                    Product.simplify_with(vec![SimpleExpr::Const(exp.clone().into()), exp2], ctx)?;
                 if let SimpleExpr::Const(n) = &exp && let Some(n) = n.as_integer() {
-                    Self::simplify_integer_power(base, n)
+                    Self::simplify_integer_power(base, n, ctx)
                 } else {
                     Ok(SimpleExpr::Pow(Box::new((base, exp))))
                 }
             }
-            SimpleExpr::Product(exprs) => {
-                Ok(SimpleExpr::Product(exprs.into_iter().map(|x| Self::simplify_integer_power(x, exp)).collect::<ComputeResult<Vec<_>>>()?))
-            }
+            // the factors are re-simplified, since a power such as (-1)^2 can become 1
+            SimpleExpr::Product(exprs) => Product.simplify_with(
+                exprs
+                    .into_iter()
+                    .map(|x| Self::simplify_integer_power(x, exp, ctx))
+                    .collect::<ComputeResult<Vec<_>>>()?,
+                ctx,
+            ),
             _ => Ok(SimpleExpr::Pow(Box::new((base, SimpleExpr::Const(exp.clone().into())))))
         }
     }
-    fn simplify_power(base: SimpleExpr, exponent: SimpleExpr) -> ComputeResult {
-        if base == 0 {
-            match exponent {
-                SimpleExpr::Const(i) if i.is_positive() => Ok(0.into()),
-                // 0^0 or 0^(-n) is undefined
-                SimpleExpr::Const(_) => Err(Undefined),
-                _ => Ok(SimpleExpr::Pow(Box::new((base, exponent)))),
+    // c^(k + r/n) = c^k * (c^r)^(1/n), with perfect n-th powers pulled out of the radical.
+    // Radicals too large to expand, as in 3^(99999999/100000000), are kept as they are.
+    fn simplify_radical(base: &Constant, exp: &Constant, n: u32) -> ComputeResult {
+        const MAX_RADICAL_BITS: u64 = 1 << 16;
+
+        let k = exp.floor().to_integer();
+        let r = exp.numer() - &k * exp.denom();
+        // c^r = p/q is rationalized to (p * q^(n-1))^(1/n) / q before extracting the root
+        let bits = r.to_u64().and_then(|r| {
+            let per_power = base.numer().bits() + base.denom().bits().checked_mul(u64::from(n))?;
+            r.checked_mul(per_power)
+        });
+        if !bits.is_some_and(|bits| bits <= MAX_RADICAL_BITS) {
+            return Ok(SimpleExpr::Pow(Box::new((
+                SimpleExpr::Const(base.clone()),
+                SimpleExpr::Const(exp.clone()),
+            ))));
+        }
+        check_power(base, &k)?;
+        let whole = base.clone().pow(&k);
+        let (outside, inside) = base.clone().pow(&r).extract_root(n)?;
+        let coefficient = SimpleExpr::Const(whole * outside);
+        if inside.is_one() {
+            Ok(coefficient)
+        } else {
+            let radical = SimpleExpr::Pow(Box::new((
+                SimpleExpr::Const(inside.into()),
+                SimpleExpr::Const(BigRational::new(1.into(), n.into()).into()),
+            )));
+            Product.simplify(vec![coefficient, radical])
+        }
+    }
+
+    // n! for integers up to `MAX_FACTORIAL`, undefined for negative integers
+    pub(crate) fn simplify_factorial(x: SimpleExpr) -> ComputeResult {
+        const MAX_FACTORIAL: u32 = 10_000;
+        match &x {
+            SimpleExpr::List(_) => thread(vec![x], |x| {
+                let [x] = <[_; 1]>::try_from(x).expect("one argument");
+                Self::simplify_factorial(x)
+            }),
+            SimpleExpr::Const(n) if let Some(n) = n.as_integer() => {
+                if n.is_negative() {
+                    return Err(ComputeError::domain("Factorial", x));
+                }
+                match n.to_u32() {
+                    Some(n) if n <= MAX_FACTORIAL => {
+                        let bits = u64::from(n) * u64::from(u32::BITS - n.leading_zeros());
+                        limits::check_bits(bits, || SimpleExpr::Factorial(Box::new(x.clone())))?;
+                        let product = (1..=n).map(BigInt::from).product::<BigInt>();
+                        let result = SimpleExpr::Const(product.into());
+                        let before = trace::before(|| SimpleExpr::Factorial(Box::new(x.clone())));
+                        record("evaluate factorial", before, || result.clone());
+                        Ok(result)
+                    }
+                    _ => Ok(SimpleExpr::Factorial(Box::new(x))),
+                }
+            }
+            _ => Ok(SimpleExpr::Factorial(Box::new(x))),
+        }
+    }
+
+    pub(crate) fn simplify_power(base: SimpleExpr, exponent: SimpleExpr) -> ComputeResult {
+        Self::simplify_power_with(base, exponent, &Assumptions::default())
+    }
+
+    pub(crate) fn simplify_power_with(
+        base: SimpleExpr,
+        exponent: SimpleExpr,
+        ctx: &Assumptions,
+    ) -> ComputeResult {
+        if matches!(base, SimpleExpr::List(_)) || matches!(exponent, SimpleExpr::List(_)) {
+            return thread(vec![base, exponent], |x| {
+                let [base, exponent] = <[_; 2]>::try_from(x).expect("two arguments");
+                Self::simplify_power_with(base, exponent, ctx)
+            });
+        }
+        let power = || SimpleExpr::Pow(Box::new((base.clone(), exponent.clone())));
+        limits::step(power)?;
+        let before = trace::before(power);
+        let (rule, result) = if base == 0 {
+            match &exponent {
+                SimpleExpr::Const(i) if i.is_positive() => ("zero to a positive power", 0.into()),
+                SimpleExpr::Const(i) if i.is_zero() => {
+                    return Err(ComputeError::IndeterminateForm(SimpleExpr::Pow(Box::new((
+                        base, exponent,
+                    )))));
+                }
+                SimpleExpr::Const(_) => return Err(zero_power(exponent)),
+                _ if is_positive(&exponent, ctx) => ("zero to a positive power", 0.into()),
+                _ if is_negative(&exponent, ctx) => return Err(zero_power(exponent)),
+                _ => return Ok(SimpleExpr::Pow(Box::new((base, exponent)))),
             }
         } else if base == 1 {
             // 1^x = 1
-            Ok(SimpleExpr::Const(One::one()))
+            ("one to any power", SimpleExpr::Const(One::one()))
         } else if let SimpleExpr::Const(exp) = &exponent && let Some(exp) = exp.as_integer() {
-            Self::simplify_integer_power(base, exp)
+            ("integer power", Self::simplify_integer_power(base, exp, ctx)?)
+        } else if let (SimpleExpr::Const(b), SimpleExpr::Const(e)) = (&base, &exponent)
+            && b.is_positive()
+            && let Some(root) = e.denom().to_u32()
+        {
+            ("simplify radical", Self::simplify_radical(b, e, root)?)
+        } else if base == -1 && is_even(&exponent, ctx) {
+            ("minus one to an even power", 1.into())
+        } else if base == -1 && is_odd(&exponent, ctx) {
+            ("minus one to an odd power", (-1).into())
+        } else if let SimpleExpr::Pow(inner) = &base
+            && is_positive(&inner.0, ctx)
+            && is_real(&inner.1, ctx)
+            && is_real(&exponent, ctx)
+        {
+            // (a^b)^c = a^(b c) for a positive `a`
+            let (a, b) = (inner.0.clone(), inner.1.clone());
+            let exponent = Product.simplify_with(vec![b, exponent], ctx)?;
+            ("multiply exponents", Self::simplify_power_with(a, exponent, ctx)?)
         } else {
-            Ok(SimpleExpr::Pow(Box::new((base, exponent))))
-        }
+            return Ok(SimpleExpr::Pow(Box::new((base, exponent))));
+        };
+        record(rule, before, || result.clone());
+        Ok(result)
     }
+
     pub fn simplify(self) -> ComputeResult {
+        self.simplify_with(&Assumptions::default())
+    }
+
+    // simplifies with what is known about the symbols
+    pub fn simplify_with(self, ctx: &Assumptions) -> ComputeResult {
+        session::memoize(self, ctx, |x| x.simplify_uncached(ctx))
+    }
+
+    fn simplify_uncached(self, ctx: &Assumptions) -> ComputeResult {
         use BasicAlgebraicExpr::*;
         use SimpleExpr as E;
+        let simplify = |x: BasicAlgebraicExpr| x.simplify_with(ctx);
         Ok(match self {
-            Const(c) if c.denom().is_zero() => return Err(Undefined),
+            Const(c) if c.denom().is_zero() => {
+                return Err(divided_by_zero(E::Const(c.numer().clone().into())));
+            }
             Const(c) => E::Const(c),
             Symbol(s) => E::Symbol(s),
-            Pow(x) => Self::simplify_power((*x).0.simplify()?, (*x).1.simplify()?)?,
-            Sum(x) => self::Sum.simplify_entry(x)?,
-            Product(x) => self::Product.simplify_entry(x)?,
-            Neg(x) => {
-                self::Product.simplify_entry(vec![BasicAlgebraicExpr::Const((-1).into()), *x])?
+            Pow(x) => {
+                let (base, exponent) = *x;
+                Self::simplify_power_with(simplify(base)?, simplify(exponent)?, ctx)?
+            }
+            Sum(x) => self::Sum.simplify_entry(x, ctx)?,
+            Product(x) => self::Product.simplify_entry(x, ctx)?,
+            Neg(x) => self::Product
+                .simplify_entry(vec![BasicAlgebraicExpr::Const((-1).into()), *x], ctx)?,
+            Factorial(x) => Self::simplify_factorial(simplify(*x)?)?,
+            Relation(op, x) => {
+                let (lhs, rhs) = *x;
+                E::Relation(op, Box::new((simplify(lhs)?, simplify(rhs)?)))
             }
-            Factorial(_) => todo!(),
-            Function(..) => todo!(),
+            List(x) => E::List(x.into_iter().map(simplify).collect::<ComputeResult<_>>()?),
+            Function(name, args) => super::functions::evaluate(
+                name,
+                args.into_iter().map(simplify).collect::<ComputeResult<_>>()?,
+                ctx,
+            )?,
         })
     }
 }
@@ -0,0 +1,267 @@
+// Exact solutions of univariate polynomial equations.
+//
+// Rational roots are found with the rational root theorem. What remains of each
+// square free factor is solved in radicals up to degree four when the result is real
+// and reasonably small, and otherwise represented by `Root[p, k]`, the `k`-th root of
+// the polynomial `p`.
+use num::{BigRational, Signed, Zero};
+
+use crate::constant::Constant;
+use crate::poly::{coefficients, Polynomial};
+use crate::relation::RelOp;
+use crate::simplify::{Operation, Product, SimpleExpr, Sum};
+use crate::{BasicAlgebraicExpr, ComputeError, ComputeResult};
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Solution {
+    pub value: SimpleExpr,
+    pub multiplicity: usize,
+}
+
+fn constant(c: Constant) -> SimpleExpr {
+    SimpleExpr::Const(c)
+}
+
+fn fraction(numer: i128, denom: i128) -> Constant {
+    BigRational::new(numer.into(), denom.into()).into()
+}
+
+fn pow(base: SimpleExpr, exp: Constant) -> ComputeResult {
+    BasicAlgebraicExpr::simplify_power(base, SimpleExpr::Const(exp))
+}
+
+fn sqrt(x: SimpleExpr) -> ComputeResult {
+    pow(x, fraction(1, 2))
+}
+
+// the real cube root, given the sign of `x`
+fn real_cbrt(x: SimpleExpr, negative: bool) -> ComputeResult {
+    if negative {
+        pow(x.negate()?, fraction(1, 3))?.negate()
+    } else {
+        pow(x, fraction(1, 3))
+    }
+}
+
+fn root_placeholders(
+    p: &Polynomial,
+    var: &str,
+    ks: impl Iterator<Item = usize>,
+) -> ComputeResult<Vec<SimpleExpr>> {
+    let p = p.to_expr(var)?;
+    Ok(ks
+        .map(|k| SimpleExpr::Function("Root".into(), vec![p.clone(), (k as i128).into()]))
+        .collect())
+}
+
+// Solves `equation` for `var`. The equation may also be given as an expression that equals zero.
+// Solutions are sorted and paired with their multiplicity.
+pub fn solve(equation: SimpleExpr, var: &str) -> ComputeResult<Vec<Solution>> {
+    let expr = match equation.to_one_side()? {
+        SimpleExpr::Relation(RelOp::Equal, sides) => sides.0,
+        SimpleExpr::Relation(op, sides) => {
+            return Err(ComputeError::Unsupported(SimpleExpr::Relation(op, sides)));
+        }
+        x => x,
+    };
+    let expr = expr.expand()?;
+
+    let mut solutions = if let Some(p) = Polynomial::from_expr(&expr, var) {
+        solve_polynomial(&p, var)?
+    } else if let Some(coeffs) = coefficients(&expr, var)? {
+        solve_symbolic(coeffs, &expr)?
+            .into_iter()
+            .map(|value| Solution {
+                value,
+                multiplicity: 1,
+            })
+            .collect()
+    } else {
+        return Err(ComputeError::Unsupported(expr));
+    };
+    solutions.sort_by(|a, b| a.value.cmp(&b.value));
+    Ok(solutions)
+}
+
+fn solve_polynomial(p: &Polynomial, var: &str) -> ComputeResult<Vec<Solution>> {
+    match p.degree() {
+        // every value is a solution
+        None => return Err(ComputeError::Unsupported(0.into())),
+        Some(0) => return Ok(vec![]),
+        _ => {}
+    }
+    let mut solutions = Vec::new();
+    for (factor, multiplicity) in p.square_free_decomposition() {
+        for value in solve_square_free(&factor, var)? {
+            solutions.push(Solution {
+                value,
+                multiplicity,
+            });
+        }
+    }
+    Ok(solutions)
+}
+
+fn solve_square_free(p: &Polynomial, var: &str) -> ComputeResult<Vec<SimpleExpr>> {
+    let roots = p.rational_roots()?;
+    let mut rest = p.clone();
+    for root in &roots {
+        rest = rest.div_rem(&Polynomial::linear_factor(root.clone())).0;
+    }
+
+    let mut out: Vec<_> = roots.into_iter().map(constant).collect();
+    let c = rest.monic().coeffs().to_vec();
+    out.extend(match rest.degree() {
+        None | Some(0) => vec![],
+        Some(1) => vec![constant(-c[0].clone())],
+        Some(2) => quadratic_roots(1.into(), constant(c[1].clone()), constant(c[0].clone()))?,
+        Some(3) => cubic_roots(&rest, var)?,
+        Some(4) => quartic_roots(&rest, var)?,
+        Some(n) => root_placeholders(&rest, var, 1..=n)?,
+    });
+    Ok(out)
+}
+
+// the roots of `expr`, given by its coefficients
+fn solve_symbolic(
+    mut coeffs: Vec<SimpleExpr>,
+    expr: &SimpleExpr,
+) -> ComputeResult<Vec<SimpleExpr>> {
+    while coeffs.len() > 1 && coeffs.last() == Some(&0.into()) {
+        coeffs.pop();
+    }
+    match coeffs.as_slice() {
+        [c] if *c == 0 => Err(ComputeError::Unsupported(expr.clone())),
+        [_] => Ok(vec![]),
+        // c0 + c1 x = 0
+        [c0, c1] => Ok(vec![Product
+            .simplify(vec![c0.clone().negate()?, pow(c1.clone(), (-1).into())?])?
+            .expand()?]),
+        [c0, c1, c2] => quadratic_roots(c2.clone(), c1.clone(), c0.clone()),
+        _ => Err(ComputeError::Unsupported(expr.clone())),
+    }
+}
+
+// (-b ± sqrt(b^2 - 4ac)) / 2a
+fn quadratic_roots(a: SimpleExpr, b: SimpleExpr, c: SimpleExpr) -> ComputeResult<Vec<SimpleExpr>> {
+    let discriminant = Sum.simplify(vec![
+        pow(b.clone(), 2.into())?,
+        Product.simplify(vec![(-4).into(), a.clone(), c])?,
+    ])?;
+    let root = sqrt(discriminant)?;
+    let denominator = pow(Product.simplify(vec![2.into(), a])?, (-1).into())?;
+    [root.clone().negate()?, root]
+        .into_iter()
+        .map(|root| {
+            let numerator = Sum.simplify(vec![b.clone().negate()?, root])?;
+            Product
+                .simplify(vec![numerator, denominator.clone()])?
+                .expand()
+        })
+        .collect()
+}
+
+// Cardano's formula, used when there is exactly one real root.
+fn cubic_roots(p: &Polynomial, var: &str) -> ComputeResult<Vec<SimpleExpr>> {
+    let c = p.monic().coeffs().to_vec();
+    let (a, b, c) = (c[2].clone(), c[1].clone(), c[0].clone());
+
+    // x = t - a/3 gives t^3 + pt + q = 0
+    let shift = a.clone() / 3.into();
+    let dp = b.clone() - a.clone() * a.clone() / 3.into();
+    let dq = fraction(2, 27) * a.clone() * a.clone() * a.clone() - a * b / 3.into() + c;
+
+    // three real roots cannot be written with real radicals
+    let cube = dp.clone() * dp.clone() * dp.clone();
+    let discriminant =
+        -(Constant::from(4) * cube.clone() + Constant::from(27) * dq.clone() * dq.clone());
+    if !discriminant.is_negative() {
+        return root_placeholders(p, var, 1..=3);
+    }
+
+    // t = cbrt(-q/2 + sqrt(R)) + cbrt(-q/2 - sqrt(R)) where R = q^2/4 + p^3/27
+    let r = dq.clone() * dq.clone() / 4.into() + cube / 27.into();
+    let sqrt_r = sqrt(constant(r))?;
+    let half_q = constant(-dq.clone() / 2.into());
+    // sqrt(R) >= |q/2| exactly when p >= 0
+    let (u_negative, v_negative) = if dp.is_negative() {
+        (dq.is_positive(), dq.is_positive())
+    } else {
+        (false, true)
+    };
+    let u = real_cbrt(
+        Sum.simplify(vec![half_q.clone(), sqrt_r.clone()])?,
+        u_negative,
+    )?;
+    let v = real_cbrt(Sum.simplify(vec![half_q, sqrt_r.negate()?])?, v_negative)?;
+    let real = Sum.simplify(vec![u, v, constant(-shift)])?;
+
+    let mut roots = vec![real];
+    roots.extend(root_placeholders(p, var, 2..=3)?);
+    Ok(roots)
+}
+
+// Ferrari's method, used when the resolvent cubic has a positive rational root.
+fn quartic_roots(p: &Polynomial, var: &str) -> ComputeResult<Vec<SimpleExpr>> {
+    let c = p.monic().coeffs().to_vec();
+    let (a, b, c, d) = (c[3].clone(), c[2].clone(), c[1].clone(), c[0].clone());
+    let a2 = a.clone() * a.clone();
+
+    // x = y - a/4 gives y^4 + py^2 + qy + r = 0
+    let shift = constant(-a.clone() / 4.into());
+    let dp = b.clone() - fraction(3, 8) * a2.clone();
+    let dq = c.clone() - a.clone() * b.clone() / 2.into() + a2.clone() * a.clone() / 8.into();
+    let dr = d - a.clone() * c / 4.into() + a2.clone() * b / 16.into()
+        - fraction(3, 256) * a2.clone() * a2;
+
+    let shifted = |y: SimpleExpr| Sum.simplify(vec![y, shift.clone()])?.expand();
+
+    if dq.is_zero() {
+        // biquadratic: y^2 = z where z^2 + pz + r = 0
+        let mut roots = Vec::new();
+        for z in quadratic_roots(1.into(), constant(dp), constant(dr))? {
+            let y = sqrt(z)?;
+            roots.push(shifted(y.clone().negate()?)?);
+            roots.push(shifted(y)?);
+        }
+        return Ok(roots);
+    }
+
+    // resolvent cubic 8m^3 + 8pm^2 + (2p^2 - 8r)m - q^2 = 0
+    let resolvent = Polynomial::new(vec![
+        -dq.clone() * dq.clone(),
+        Constant::from(2) * dp.clone() * dp.clone() - Constant::from(8) * dr,
+        Constant::from(8) * dp.clone(),
+        8.into(),
+    ]);
+    let Some(m) = resolvent
+        .rational_roots()?
+        .into_iter()
+        .find(Signed::is_positive)
+    else {
+        return root_placeholders(p, var, 1..=4);
+    };
+
+    // y = (±1 sqrt(2m) ±2 sqrt(-(2p + 2m ±1 2q / sqrt(2m)))) / 2
+    let s = sqrt(constant(Constant::from(2) * m.clone()))?;
+    let mut roots = Vec::new();
+    for sign in [-1, 1] {
+        let inner = Sum.simplify(vec![
+            constant(-(Constant::from(2) * dp.clone() + Constant::from(2) * m.clone())),
+            Product.simplify(vec![
+                constant(Constant::from(-2 * sign) * dq.clone()),
+                pow(s.clone(), (-1).into())?,
+            ])?,
+        ])?;
+        let inner = sqrt(inner)?;
+        let outer = Product.simplify(vec![sign.into(), s.clone()])?;
+        for inner in [inner.clone().negate()?, inner] {
+            let y = Product.simplify(vec![
+                Sum.simplify(vec![outer.clone(), inner])?,
+                constant(fraction(1, 2)),
+            ])?;
+            roots.push(shifted(y)?);
+        }
+    }
+    Ok(roots)
+}
@@ -4,9 +4,35 @@ use std::ops::{Add, Mul};
 use tracing::Level;
 use tracing_subscriber::fmt::format::FmtSpan;
 
+use crate::parse::parse_into_expression;
 use crate::{BasicAlgebraicExpr, SimpleExpr};
 
+mod apart;
+mod assumptions;
+mod calculus;
+mod calculus_limit;
+mod codegen;
+mod eigen;
+mod errors;
+mod full_form;
+mod intern;
+mod latex;
+mod linear;
+mod list;
+mod mathml;
+mod matrix;
 mod parse;
+mod pattern;
+mod relation;
+mod resource_limits;
+#[cfg(feature = "serde")]
+mod serialize;
+mod series;
+mod session;
+mod solve;
+mod sum;
+mod trace;
+mod visit;
 
 #[derive(Debug, Clone)]
 pub enum TestExpr {
@@ -162,6 +188,11 @@ fn simplify(a: BasicAlgebraicExpr) -> SimpleExpr {
     a.simplify().unwrap()
 }
 
+// parses and simplifies `s`
+fn expr(s: &str) -> SimpleExpr {
+    parse_into_expression(s).unwrap().simplify().unwrap()
+}
+
 #[test]
 pub fn simplify_power() {
     // n^0 = 1
@@ -177,6 +208,13 @@ pub fn simplify_power() {
         SimpleExpr::Pow(Box::new((sn(0), sopaque()))),
         simplify(n(0) ^ opaque())
     );
+    // (-x)^2 = x^2, without a leftover factor of 1
+    assert_eq!(
+        SimpleExpr::Pow(Box::new((ss("x"), sn(2)))),
+        simplify((-s("x")) ^ n(2))
+    );
+    // x^-y^2 = x^(-(y^2))
+    assert_eq!(expr("x^-y^2"), expr("1/x^(y^2)"));
 }
 
 macro_rules! assert_simplified_eq {
@@ -190,4 +228,16 @@ pub fn simplify_addition() {
     init();
     assert_simplified_eq!(3 * sx, x + 2 * x);
     assert_simplified_eq!(6 * sx, x + 2 * x + 3 * x);
+    assert_simplified_eq!(5 + sx, 3 + x + 2);
+}
+
+#[test]
+pub fn simplify_cancellation() {
+    let e = BasicAlgebraicExpr::Sum(vec![s("a"), s("b"), s("c"), -s("b")]);
+    assert_eq!(simplify(e), SimpleExpr::Sum(vec![ss("a"), ss("c")]));
+    // x^2 * x^-1 = x
+    assert_eq!(simplify((s("x") ^ n(2)) * (s("x") ^ n(-1))), ss("x"));
+    // 2 * 3^(1/2) * 3^(1/2) = 6, the constant appears only after merging
+    let root = || n(3) ^ (n(1) / n(2));
+    assert_eq!(simplify(n(2) * root() * root() + s("x")), SimpleExpr::Sum(vec![sn(6), ss("x")]));
 }
@@ -0,0 +1,74 @@
+use crate::constant::Constant;
+use crate::poly::{apart, Polynomial};
+
+use super::expr;
+
+fn poly(coeffs: &[i128]) -> Polynomial {
+    Polynomial::new(coeffs.iter().map(|&c| Constant::from(c)).collect())
+}
+
+#[test]
+pub fn factor() {
+    // 2 (x - 1)^2 (x^2 + 2) (x^2 + 3)
+    let p = poly(&[-1, 2, -1]).scale(&Constant::from(-2)) * poly(&[6, 0, 5, 0, 1]);
+    assert_eq!(
+        p.factor().unwrap(),
+        (
+            Constant::from(2),
+            vec![
+                (poly(&[-1, 1]), 2),
+                (poly(&[2, 0, 1]), 1),
+                (poly(&[3, 0, 1]), 1)
+            ]
+        )
+    );
+    // irreducible over the rationals
+    assert_eq!(
+        poly(&[1, 0, -1, 0, 1]).factor().unwrap().1,
+        vec![(poly(&[1, 0, -1, 0, 1]), 1)]
+    );
+}
+
+#[test]
+pub fn decomposition() {
+    assert_eq!(
+        expr("Apart[1/(x^2 - 1)]"),
+        expr("1/2 (x - 1)^-1 - 1/2 (x + 1)^-1")
+    );
+    assert_eq!(
+        expr("Apart[(2x + 3)/(x^3 - x)]"),
+        expr("-3/x + 5/2 (x - 1)^-1 + 1/2 (x + 1)^-1")
+    );
+    // a polynomial part and an irreducible quadratic
+    assert_eq!(
+        expr("Apart[(x^3 + 1)/(x^2 + 1), x]"),
+        expr("x + (1 - x) (x^2 + 1)^-1")
+    );
+    // repeated factors
+    assert_eq!(
+        expr("Apart[1/((x + 1)^2 (x^2 + 1))]"),
+        expr("1/2 (x + 1)^-2 + 1/2 (x + 1)^-1 - 1/2 x (x^2 + 1)^-1")
+    );
+    assert_eq!(expr("Apart[x/(x - 1)^3]"), expr("(x - 1)^-3 + (x - 1)^-2"));
+    assert_eq!(
+        expr("Apart[1/(x^4 + 5x^2 + 6)]"),
+        expr("(x^2 + 2)^-1 - (x^2 + 3)^-1")
+    );
+}
+
+#[test]
+pub fn symbolic_factors_and_unchanged() {
+    assert_eq!(
+        expr("Apart[a (1 - x)/((x + 1)(x^2 + 1)), x]"),
+        expr("a (x + 1)^-1 - a x (x^2 + 1)^-1")
+    );
+    assert_eq!(
+        expr("Apart[1/((s + 1)(s + 2) s)]"),
+        expr("1/2 s^-1 - (s + 1)^-1 + 1/2 (s + 2)^-1")
+    );
+    assert_eq!(expr("Apart[1/(x^2 - 2)]"), expr("(x^2 - 2)^-1"));
+    // too large to search for factors, kept as it is
+    let large = "(x^2 - 2000000000000000000000000000000)^-1";
+    assert_eq!(expr(&format!("Apart[{large}]")), expr(large));
+    assert_eq!(apart(&expr("Sin[x]/x"), "x").unwrap(), expr("Sin[x]/x"));
+}
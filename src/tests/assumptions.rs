@@ -0,0 +1,62 @@
+use crate::assumptions::{
+    is_even, is_negative, is_nonnegative, is_positive, Assumption, Assumptions,
+};
+use crate::parse::parse_into_expression;
+use crate::simplify::SimpleExpr;
+
+use super::expr;
+
+fn expr_with(s: &str, ctx: &Assumptions) -> SimpleExpr {
+    parse_into_expression(s)
+        .unwrap()
+        .simplify_with(ctx)
+        .unwrap()
+}
+
+#[test]
+pub fn sign_and_parity() {
+    let ctx = Assumptions::new()
+        .with("x", Assumption::Positive)
+        .with("n", Assumption::Integer);
+    assert!(is_positive(&expr("x^2 + 3x + Pi"), &ctx));
+    assert!(is_negative(&expr("-2x Exp[n]"), &ctx));
+    assert!(is_nonnegative(&expr("n^2"), &ctx));
+    assert!(!is_positive(&expr("n^2"), &ctx));
+    assert!(!is_positive(&expr("y"), &ctx));
+    assert!(is_even(&expr("2n + 4"), &ctx));
+    assert!(!is_even(&expr("n + 1"), &ctx));
+}
+
+#[test]
+pub fn powers() {
+    let ctx = Assumptions::new()
+        .with("x", Assumption::Positive)
+        .with("n", Assumption::Integer);
+    assert_eq!(expr_with("0^x", &ctx), 0);
+    assert!(parse_into_expression("0^(-x)")
+        .unwrap()
+        .simplify_with(&ctx)
+        .is_err());
+    assert_eq!(expr_with("(x^2)^(1/2)", &ctx), expr("x"));
+    assert_eq!(expr_with("Sqrt[x^6]", &ctx), expr("x^3"));
+    assert_eq!(expr_with("(-1)^(2n)", &ctx), 1);
+    assert_eq!(expr_with("(-1)^(2n + 1)", &ctx), -1);
+    // nothing is known without assumptions
+    assert_eq!(
+        expr("0^x"),
+        SimpleExpr::Pow(Box::new((0.into(), expr("x"))))
+    );
+    assert_ne!(expr("(x^2)^(1/2)"), expr("x"));
+}
+
+#[test]
+pub fn refine() {
+    assert_eq!(expr("Refine[Sqrt[x^2], x > 0]"), expr("x"));
+    assert_eq!(expr("Refine[Sin[n Pi], Element[n, Integers]]"), 0);
+    assert_eq!(
+        expr("Refine[Cos[2 n Pi] + Log[Exp[y]], {Element[n, Integers], Element[y, Reals]}]"),
+        expr("1 + y")
+    );
+    assert_eq!(expr("Sin[n Pi]"), expr("Sin[n Pi]"));
+    assert_ne!(expr("Sin[n Pi]"), 0);
+}
@@ -0,0 +1,130 @@
+use crate::calculus::{derivative, integrate, integrate_definite, DefiniteIntegral};
+use crate::parse::parse_into_expression;
+use crate::print::to_latex;
+use crate::simplify::SimpleExpr;
+
+use super::expr;
+
+fn antiderivative(s: &str) -> SimpleExpr {
+    integrate(&expr(s), "x").unwrap()
+}
+
+#[test]
+pub fn derivatives() {
+    assert_eq!(
+        derivative(&expr("x^3 Sin[x]"), "x").unwrap(),
+        expr("3x^2 Sin[x] + x^3 Cos[x]")
+    );
+    assert_eq!(
+        derivative(&expr("Log[x^2 + 1]"), "x").unwrap(),
+        expr("2x (x^2 + 1)^-1")
+    );
+    assert_eq!(
+        derivative(&expr("f[x]"), "x").unwrap(),
+        SimpleExpr::Function("D".into(), vec![expr("f[x]"), expr("x")])
+    );
+    assert_eq!(expr("D[a x^2 + Exp[2x], x]"), expr("2a x + 2 Exp[2x]"));
+}
+
+#[test]
+pub fn power_rule_and_elementary() {
+    assert_eq!(antiderivative("x^3 + 2x"), expr("x^4/4 + x^2"));
+    assert_eq!(antiderivative("1/x"), expr("Log[x]"));
+    assert_eq!(antiderivative("(2x + 1)^-1"), expr("Log[2x + 1]/2"));
+    assert_eq!(antiderivative("x^n"), expr("x^(n + 1) (n + 1)^-1"));
+    assert_eq!(antiderivative("a x + b"), expr("a x^2/2 + b x"));
+    assert_eq!(antiderivative("Sin[2x]"), expr("-Cos[2x]/2"));
+    assert_eq!(antiderivative("2^x"), expr("2^x Log[2]^-1"));
+    assert_eq!(antiderivative("x Exp[x]"), expr("x Exp[x] - Exp[x]"));
+    assert_eq!(
+        antiderivative("x^2 Cos[x]"),
+        expr("x^2 Sin[x] + 2x Cos[x] - 2Sin[x]")
+    );
+    assert_eq!(expr("Integrate[Log[x], x]"), expr("x Log[x] - x"));
+}
+
+#[test]
+pub fn rational_functions() {
+    assert_eq!(antiderivative("1/(x^2 + 1)"), expr("ArcTan[x]"));
+    assert_eq!(antiderivative("x/(x^2 + 1)"), expr("Log[x^2 + 1]/2"));
+    assert_eq!(
+        antiderivative("1/(x^2 - 1)"),
+        expr("Log[x - 1]/2 - Log[x + 1]/2")
+    );
+    // Hermite reduction gives the rational part
+    assert_eq!(antiderivative("1/(x + 1)^2"), expr("-(x + 1)^-1"));
+    assert_eq!(
+        antiderivative("1/((x - 1)^2 (x + 2))"),
+        expr("-Log[x - 1]/9 + Log[x + 2]/9 - (x - 1)^-1/3")
+    );
+    assert_eq!(
+        antiderivative("(x^3 + 1)/(x^2 + x + 1)"),
+        expr("x^2/2 - x + 4/3 * 3^(1/2) ArcTan[2/3 * 3^(1/2) x + 1/3 * 3^(1/2)]")
+    );
+}
+
+#[test]
+pub fn unevaluated() {
+    assert_eq!(
+        antiderivative("Exp[x^2]"),
+        SimpleExpr::Function("Integrate".into(), vec![expr("Exp[x^2]"), expr("x")])
+    );
+    assert_eq!(
+        expr("Integrate[Exp[x^2] + x, x]"),
+        expr("x^2/2 + Integrate[Exp[x^2], x]")
+    );
+    assert_eq!(
+        antiderivative("1/(x^3 + x + 1)"),
+        expr("Integrate[1/(x^3 + x + 1), x]")
+    );
+}
+
+#[test]
+pub fn definite_integrals() {
+    assert_eq!(expr("Integrate[x^2, {x, 0, 3}]"), expr("9"));
+    assert_eq!(expr("Integrate[1/(1 + x^2), {x, 0, 1}]"), expr("Pi/4"));
+    assert_eq!(expr("Integrate[Sin[x], {x, 0, Pi}]"), expr("2"));
+    assert_eq!(expr("Integrate[x^2, {x, a, b}]"), expr("b^3/3 - a^3/3"));
+    // the logarithms of negative values at both bounds combine to a real result
+    assert_eq!(expr("Integrate[1/(x - 2), {x, 0, 1}]"), expr("-Log[2]"));
+    assert_eq!(expr("Integrate[x^(-1/2), {x, 0, 1}]"), expr("2"));
+}
+
+#[test]
+pub fn poles_and_quadrature() {
+    let integral = |s| parse_into_expression(s).unwrap().simplify();
+    assert!(integral("Integrate[1/x, {x, -1, 1}]").is_err());
+    assert!(integral("Integrate[(x - 1/2)^-2, {x, 0, 1}]").is_err());
+    // the poles at -2^(1/2) and 2^(1/2) are irrational
+    assert!(integral("Integrate[1/(x^2 - 2), {x, 0, 2}]").is_err());
+    assert!(integral("Integrate[1/(x^2 - 2), {x, 0, 1}]").is_ok());
+    // Tan has a pole at Pi/2, and Log is not real for negative arguments
+    assert!(integral("Integrate[Tan[x], {x, 0, 2}]").is_err());
+    assert!(integral("Integrate[Log[x], {x, -1, 1}]").is_err());
+    assert_eq!(expr("Integrate[Tan[x], {x, 0, 1}]"), expr("-Log[Cos[1]]"));
+
+    match integrate_definite(&expr("Exp[x^2]"), "x", &expr("0"), &expr("1")) {
+        Ok(Some(DefiniteIntegral::Approximate(x))) => {
+            assert!((x - 1.4626517459071816).abs() < 1e-9)
+        }
+        x => panic!("expected an approximation, got {x:?}"),
+    }
+    assert_eq!(
+        expr("Integrate[Exp[x^2], {x, 0, 1}]"),
+        expr("Approximate[146265174591/100000000000]")
+    );
+    assert_eq!(
+        to_latex(&expr("Integrate[Exp[x^2], {x, 0, 1}]")),
+        "\\approx 1.46265174591"
+    );
+    // the tolerance is relative to the size of the integral
+    assert_eq!(
+        expr("Integrate[Exp[x^2], {x, 0, 6}]"),
+        expr("Approximate[364483107789000]")
+    );
+    // beyond the range of i128
+    assert_eq!(
+        expr("Integrate[Exp[Exp[x]], {x, 0, 5}]"),
+        expr("Approximate[193419408786 10^51]")
+    );
+}
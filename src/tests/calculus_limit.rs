@@ -0,0 +1,83 @@
+use crate::calculus::{limit, Direction};
+use crate::print::to_latex;
+use crate::simplify::SimpleExpr;
+
+use super::expr;
+
+#[test]
+pub fn finite_points() {
+    assert_eq!(expr("Limit[Sin[x]/x, x -> 0]"), 1);
+    assert_eq!(expr("Limit[(x^2 - 1)/(x - 1), x -> 1]"), 2);
+    assert_eq!(expr("Limit[(1 - Cos[x])/x^2, x -> 0]"), expr("1/2"));
+    assert_eq!(expr("Limit[1/x - 1/Sin[x], x -> 0]"), 0);
+    assert_eq!(expr("Limit[x^2 + a x, x -> 2]"), expr("4 + 2a"));
+    assert_eq!(expr("Limit[x Log[x], x -> 0, Direction -> FromAbove]"), 0);
+    assert_eq!(expr("Limit[x^x, x -> 0, Direction -> FromAbove]"), 1);
+}
+
+#[test]
+pub fn infinities() {
+    assert_eq!(
+        expr("Limit[(x^2 + 1)/(2x^2 + 3), x -> Infinity]"),
+        expr("1/2")
+    );
+    assert_eq!(expr("Limit[(1 + 1/x)^x, x -> Infinity]"), expr("Exp[1]"));
+    assert_eq!(expr("Limit[Exp[-x] x^5, x -> Infinity]"), 0);
+    assert_eq!(expr("Limit[ArcTan[x], x -> -Infinity]"), expr("-Pi/2"));
+    assert_eq!(
+        expr("Limit[Exp[x]/x^3, x -> Infinity]"),
+        SimpleExpr::infinity()
+    );
+    assert_eq!(
+        expr("Limit[(x^3 + 1)/(x^2 + 3), x -> -Infinity]"),
+        SimpleExpr::negative_infinity()
+    );
+    assert_eq!(to_latex(&expr("Limit[1/x^2, x -> 0]")), "\\infty");
+}
+
+#[test]
+pub fn directions_and_unknown_limits() {
+    let f = expr("1/x");
+    let at = |direction| limit(&f, "x", &0.into(), direction).unwrap();
+    assert_eq!(at(Direction::FromAbove), Some(SimpleExpr::infinity()));
+    assert_eq!(
+        at(Direction::FromBelow),
+        Some(SimpleExpr::negative_infinity())
+    );
+    assert_eq!(
+        at(Direction::TwoSided),
+        Some(SimpleExpr::complex_infinity())
+    );
+    assert_eq!(
+        expr("Limit[Sin[x], x -> Infinity]"),
+        SimpleExpr::indeterminate()
+    );
+    assert_eq!(
+        expr("Limit[f[x], x -> 0]"),
+        SimpleExpr::Function(
+            "Limit".into(),
+            vec![
+                expr("f[x]"),
+                SimpleExpr::Function("Rule".into(), vec![expr("x"), 0.into()]),
+            ]
+        )
+    );
+}
+
+#[test]
+pub fn infinite_arithmetic() {
+    let indeterminate = SimpleExpr::indeterminate();
+    assert_eq!(expr("Infinity - Infinity"), indeterminate);
+    assert_eq!(expr("0 Infinity"), indeterminate);
+    assert_eq!(expr("Indeterminate + 1"), indeterminate);
+    assert_eq!(expr("ComplexInfinity + Infinity"), indeterminate);
+    // the sign of a constant factor is kept
+    assert_eq!(expr("-3 Infinity"), SimpleExpr::negative_infinity());
+    assert_eq!(expr("1/2 Infinity Infinity"), SimpleExpr::infinity());
+    assert_eq!(expr("-2 ComplexInfinity"), SimpleExpr::complex_infinity());
+    assert_eq!(expr("-2 x Infinity"), expr("-(x Infinity)"));
+    // finite terms are absorbed
+    assert_eq!(expr("Infinity + 2 + x"), SimpleExpr::infinity());
+    assert_eq!(expr("2 Infinity + Infinity"), SimpleExpr::infinity());
+    assert_eq!(expr("-Infinity - Pi"), SimpleExpr::negative_infinity());
+}
@@ -0,0 +1,69 @@
+use crate::codegen::{to_c, to_python, to_rust};
+use crate::simplify::SimpleExpr;
+use crate::ComputeError;
+
+use super::expr;
+
+#[test]
+pub fn rust_fn() {
+    assert_eq!(
+        to_rust(
+            "f",
+            &["x", "y"],
+            &expr("2^(1/2) x - y^(1/3)/(x + 1) + x^3/3")
+        )
+        .unwrap(),
+        "pub fn f(x: f64, y: f64) -> f64 {\n    \
+         2.0_f64.sqrt() * x + x.powi(3) / 3.0 - y.powf(1.0/3.0) / (1.0 + x)\n}\n"
+    );
+    assert_eq!(
+        to_rust("g", &["x"], &expr("Exp[-x] Pi - 3/(2x)")).unwrap(),
+        "pub fn g(x: f64) -> f64 {\n    \
+         (-x).exp() * std::f64::consts::PI - 3.0 / (2.0 * x)\n}\n"
+    );
+    // powi takes an i32
+    assert_eq!(
+        to_rust("h", &["x"], &expr("x^3000000000")).unwrap(),
+        "pub fn h(x: f64) -> f64 {\n    x.powf(3000000000.0)\n}\n"
+    );
+}
+
+#[test]
+pub fn common_subexpressions() {
+    let x = expr("Sin[x + y]^2 + Cos[x + y] Sin[x + y]");
+    assert_eq!(
+        to_c("f", &["x", "y"], &x).unwrap(),
+        "double f(double x, double y) {\n    \
+         const double t0 = x + y;\n    \
+         const double t1 = sin(t0);\n    \
+         return cos(t0) * t1 + pow(t1, 2);\n}\n"
+    );
+    assert_eq!(
+        to_python("f", &["x", "y"], &x).unwrap(),
+        "def f(x, y):\n    \
+         t0 = x + y\n    \
+         t1 = np.sin(t0)\n    \
+         return np.cos(t0) * t1 + t1**2\n"
+    );
+    // temporaries do not shadow arguments
+    let t0 = SimpleExpr::Symbol("t0".into());
+    let x = expr("Sin[a + y]^2 + Sin[a + y]").substitute("a", &t0).unwrap();
+    assert_eq!(
+        to_python("f", &["t0", "y"], &x).unwrap(),
+        "def f(t0, y):\n    \
+         t1 = np.sin(t0 + y)\n    \
+         return t1 + t1**2\n"
+    );
+}
+
+#[test]
+pub fn unsupported() {
+    assert_eq!(
+        to_c("f", &["x"], &expr("x + y")),
+        Err(ComputeError::Unsupported(expr("y")))
+    );
+    assert_eq!(
+        to_python("f", &["n"], &expr("n! + 1")),
+        Err(ComputeError::Unsupported(expr("n!")))
+    );
+}
@@ -0,0 +1,86 @@
+use crate::matrix::Matrix;
+use crate::simplify::SimpleExpr;
+use crate::solve::Solution;
+
+use super::sn;
+
+use super::expr;
+
+fn matrix(s: &str) -> Matrix {
+    Matrix::from_expr(&expr(s)).unwrap()
+}
+
+#[test]
+pub fn characteristic_polynomial() {
+    assert_eq!(
+        matrix("{{1, 2}, {3, 4}}")
+            .characteristic_polynomial("x")
+            .unwrap(),
+        expr("x^2 - 5x - 2")
+    );
+    assert_eq!(
+        expr("CharacteristicPolynomial[{{a, b}, {c, d}}, t]"),
+        expr("t^2 - a t - d t + a d - b c")
+    );
+    // det(A - xI) changes sign with the dimension
+    assert_eq!(
+        expr("CharacteristicPolynomial[{{1, 0, 0}, {0, 2, 0}, {0, 0, 3}}, x]"),
+        expr("-x^3 + 6x^2 - 11x + 6")
+    );
+}
+
+#[test]
+pub fn eigenvalues() {
+    assert_eq!(
+        matrix("{{2, 0, 0}, {0, 2, 0}, {0, 0, 3}}")
+            .eigenvalues()
+            .unwrap(),
+        vec![
+            Solution {
+                value: sn(2),
+                multiplicity: 2
+            },
+            Solution {
+                value: sn(3),
+                multiplicity: 1
+            },
+        ]
+    );
+    assert_eq!(
+        expr("Eigenvalues[{{1, 2}, {3, 4}}]"),
+        expr("{5/2 - 1/2 * 33^(1/2), 5/2 + 1/2 * 33^(1/2)}")
+    );
+    // the variable of the characteristic polynomial must not clash with the entries
+    assert_eq!(
+        expr("Eigenvalues[{{x, 1}, {1, x}}]"),
+        expr("{x - 1, x + 1}")
+    );
+}
+
+#[test]
+pub fn eigenvectors() {
+    assert_eq!(
+        matrix("{{2, 1}, {1, 2}}").eigenvectors().unwrap(),
+        vec![
+            (sn(1), vec![vec![sn(-1), sn(1)]]),
+            (sn(3), vec![vec![sn(1), sn(1)]]),
+        ]
+    );
+    // a defective matrix has fewer eigenvectors than its dimension
+    assert_eq!(expr("Eigenvectors[{{1, 1}, {0, 1}}]"), expr("{{1, 0}}"));
+    assert_eq!(
+        expr("Eigenvectors[{{2, 0, 0}, {0, 2, 0}, {0, 0, 3}}]"),
+        expr("{{1, 0, 0}, {0, 1, 0}, {0, 0, 1}}")
+    );
+    // eigenvalues without exact real eigenvectors keep the call unevaluated
+    for s in [
+        "Eigenvectors[{{1, 2, 0}, {0, 3, 1}, {1, 0, 1}}]",
+        "Eigenvectors[{{0, -1}, {1, 0}}]",
+        "Eigenvalues[{{0, -1}, {1, 0}}]",
+    ] {
+        let SimpleExpr::Function(name, _) = expr(s) else {
+            panic!("{s} should stay unevaluated");
+        };
+        assert!(name.starts_with("Eigen"));
+    }
+}
@@ -0,0 +1,97 @@
+use num::BigRational;
+
+use crate::constant::Constant;
+use crate::parse::parse_into_expression;
+use crate::rational_expressions::RationalExpr;
+use crate::simplify::SimpleExpr;
+use crate::solve::solve;
+use crate::{BasicAlgebraicExpr, ComputeError};
+
+use super::expr;
+
+fn error(s: &str) -> ComputeError {
+    parse_into_expression(s).unwrap().simplify().unwrap_err()
+}
+
+fn pow(base: i32, exp: i32) -> SimpleExpr {
+    SimpleExpr::Pow(Box::new((base.into(), exp.into())))
+}
+
+#[test]
+pub fn causes() {
+    assert_eq!(error("1/0"), ComputeError::DivisionByZero(pow(0, -1)));
+    assert_eq!(
+        error("x + 0^(-2)"),
+        ComputeError::DivisionByZero(pow(0, -2))
+    );
+    assert_eq!(error("0^0"), ComputeError::IndeterminateForm(pow(0, 0)));
+    assert_eq!(
+        error("(-3)!"),
+        ComputeError::domain("Factorial", (-3).into())
+    );
+    assert_eq!(
+        error("Part[{1, 2}, 3]"),
+        ComputeError::domain("Part", 3.into())
+    );
+    assert_eq!(
+        error("2^(10^9)"),
+        ComputeError::Overflow(pow(2, 1_000_000_000))
+    );
+    assert_eq!(
+        error("Inverse[{{1, 2}, {2, 4}}]"),
+        ComputeError::domain("Inverse", expr("{{1, 2}, {2, 4}}"))
+    );
+}
+
+#[test]
+pub fn zero_denominators() {
+    let c = |x: i128| RationalExpr::Const(x.into());
+    // the reciprocal of `1 - 1`, before it is evaluated
+    let power = RationalExpr::Pow(Box::new(RationalExpr::Add(vec![c(1), c(-1)])), (-2).into());
+    let base = SimpleExpr::Sum(vec![1.into(), (-1).into()]);
+    assert_eq!(
+        power.simplify().err(),
+        Some(ComputeError::DivisionByZero(SimpleExpr::Pow(Box::new((
+            base,
+            (-2).into()
+        )))))
+    );
+    let quotient = RationalExpr::Div(vec![c(2), c(0)]);
+    assert_eq!(
+        quotient.simplify().err(),
+        Some(ComputeError::DivisionByZero(pow(0, -1)))
+    );
+    // a constant with a zero denominator keeps its numerator
+    let c = Constant::from(BigRational::new_raw(3.into(), 0.into()));
+    assert_eq!(
+        BasicAlgebraicExpr::Const(c).simplify(),
+        Err(ComputeError::DivisionByZero(SimpleExpr::Product(vec![
+            3.into(),
+            pow(0, -1)
+        ])))
+    );
+}
+
+#[test]
+pub fn unsupported() {
+    // `Solve[x^5 + a x = 1, x]` stays unevaluated, the solver reports why
+    assert_eq!(
+        solve(expr("x^5 + a x = 1"), "x").unwrap_err(),
+        ComputeError::Unsupported(expr("-1 + a x + x^5"))
+    );
+    assert_eq!(
+        error("{1, 2} + {1, 2, 3}"),
+        ComputeError::Unsupported(expr("{{1, 2}, {1, 2, 3}}"))
+    );
+}
+
+#[test]
+pub fn display() {
+    assert_eq!(error("1/0").to_string(), "division by zero in (0)^{-1}");
+    assert_eq!(
+        error("(-1)!").to_string(),
+        "-1 is outside the domain of Factorial"
+    );
+    let e: Box<dyn std::error::Error> = Box::new(error("0^0"));
+    assert_eq!(e.to_string(), "indeterminate form (0)^{0}");
+}
@@ -0,0 +1,58 @@
+use crate::parse::{parse_full_form, parse_latex};
+use crate::print::to_full_form;
+use crate::simplify::SimpleExpr;
+
+use super::expr;
+
+fn full_form(s: &str) -> SimpleExpr {
+    parse_full_form(s).unwrap().simplify().unwrap()
+}
+
+#[test]
+pub fn export() {
+    assert_eq!(
+        to_full_form(&expr("3x + y^2")),
+        "Plus[Times[3, x], Power[y, 2]]"
+    );
+    assert_eq!(
+        to_full_form(&expr("-x/3 + Sin[x]! <= {1, 2}")),
+        "LessEqual[Plus[Factorial[Sin[x]], Times[Rational[-1, 3], x]], List[1, 2]]"
+    );
+}
+
+#[test]
+pub fn round_trip() {
+    for s in [
+        "3x + y^2",
+        "(x + 1)^(-1/2) - 2 Sqrt[y]",
+        "{g[a -> 0], n!, Integrate[f[x], x]}",
+        "x != 2 Pi",
+    ] {
+        let x = expr(s);
+        assert_eq!(full_form(&to_full_form(&x)), x, "{s}");
+    }
+    // symbols from LaTeX input may be unicode letters
+    let x = parse_latex(r"\alpha^2 + \theta")
+        .unwrap()
+        .simplify()
+        .unwrap();
+    assert_eq!(full_form(&to_full_form(&x)), x);
+}
+
+#[test]
+pub fn import_errors() {
+    assert_eq!(
+        full_form("Times[-2, Power[x, Rational[1, 2]]]"),
+        expr("-2 x^(1/2)")
+    );
+    assert_eq!(full_form("Plus[]"), SimpleExpr::from(0));
+    assert_eq!(full_form("Times[x, Plus[], Times[]]"), SimpleExpr::from(0));
+    assert_eq!(full_form("Times[]"), SimpleExpr::from(1));
+    assert!(parse_full_form("Power[x]").is_err());
+    assert!(parse_full_form("Rational[1, 0]").is_err());
+    assert_eq!(parse_full_form("Plus[x, 1").unwrap_err().span(), 5..6);
+    // characters outside FullForm are parse errors
+    for s in ["Plus[1.5, x]", "f[\"abc\"]", "`"] {
+        assert!(parse_full_form(s).is_err(), "{s}");
+    }
+}
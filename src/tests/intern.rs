@@ -0,0 +1,47 @@
+use std::collections::HashSet;
+
+use crate::intern::{Expr, Kind};
+
+use super::expr;
+
+#[test]
+pub fn round_trip() {
+    for s in [
+        "x^2 + 3 x y - 1/2",
+        "{Sin[x]!, f[x, y] = 2, a < b}",
+        "Integrate[Exp[x], x]",
+    ] {
+        let x = expr(s);
+        assert_eq!(Expr::from(&x).to_simple(), x);
+    }
+}
+
+#[test]
+pub fn sharing() {
+    let a = Expr::from(expr("(x + 1)^2 + Sin[x + 1]"));
+    let b = Expr::from(expr("Sin[1 + x] + (1 + x)^2"));
+    assert_eq!(a, b);
+    assert_ne!(a, Expr::from(expr("(x + 1)^3 + Sin[x + 1]")));
+    // both occurrences of `x + 1` are the same node
+    let x1 = Expr::from(expr("x + 1"));
+    let f = Expr::from(expr("f[x + 1, (x + 1)^2]"));
+    let Kind::Function(_, args) = f.kind() else {
+        panic!()
+    };
+    assert_eq!(args[0], x1);
+    assert!(matches!(args[1].kind(), Kind::Pow(base, _) if *base == x1));
+    let set: HashSet<_> = [a.clone(), b, a.clone()].into_iter().collect();
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+pub fn freeing() {
+    let before = Expr::interned();
+    let x = Expr::from(expr("Sum[f[k], {k, 1, n}] + unique"));
+    let y = x.clone();
+    assert!(Expr::interned() > before);
+    drop(x);
+    assert!(Expr::interned() > before);
+    drop(y);
+    assert_eq!(Expr::interned(), before);
+}
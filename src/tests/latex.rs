@@ -0,0 +1,63 @@
+use chumsky::error::SimpleReason;
+
+use crate::parse::{parse_latex, LatexToken};
+use crate::simplify::SimpleExpr;
+
+use super::expr;
+
+fn latex(s: &str) -> SimpleExpr {
+    parse_latex(s).unwrap().simplify().unwrap()
+}
+
+#[test]
+pub fn pasted_formulas() {
+    assert_eq!(
+        latex(r"\frac{x^2+1}{2} \cdot \sqrt{y}"),
+        expr("(x^2 + 1)/2 y^(1/2)")
+    );
+    assert_eq!(latex(r"\sqrt[3]{x} \times 2ab"), expr("2 a b x^(1/3)"));
+    assert_eq!(
+        latex(r"\left( a + b \right)^{2} - \frac{1}{b}"),
+        expr("(a + b)^2 - 1/b")
+    );
+    assert_eq!(latex(r"\frac 1 2 x_{1}"), expr("Subscript[x, 1]/2"));
+    // scripts and fractions take a single digit without braces
+    assert_eq!(latex(r"\frac12 + \frac{1}{2}3"), expr("1/2 + 3/2"));
+    assert_eq!(latex(r"x^23 + 10^{12}"), expr("3x^2 + 10^12"));
+    assert_eq!(latex(r"x_12"), expr("2 Subscript[x, 1]"));
+    assert_eq!(
+        latex(r"\left\{ 1, n! \right\} \leq 3"),
+        expr("{1, n!} <= 3")
+    );
+}
+
+#[test]
+pub fn functions_and_greek_letters() {
+    assert_eq!(latex(r"\sin^2 x + \cos(x)^2"), expr("Sin[x]^2 + Cos[x]^2"));
+    assert_eq!(latex(r"\log_{2} y - \ln{y}"), expr("Log[2, y] - Log[y]"));
+    assert_eq!(latex(r"2\pi r"), expr("2 Pi r"));
+    assert_eq!(
+        latex(r"\alpha + \Omega"),
+        SimpleExpr::Sum(vec![
+            SimpleExpr::Symbol("Ω".into()),
+            SimpleExpr::Symbol("α".into())
+        ])
+    );
+}
+
+#[test]
+pub fn error_spans() {
+    let err = parse_latex(r"x + y ? 2").unwrap_err();
+    assert_eq!(err.span(), 6..7);
+    assert_eq!(err.found(), Some(&LatexToken::Unknown('?')));
+    // the end of input
+    assert_eq!(parse_latex(r"\frac{x}{").unwrap_err().span(), 9..10);
+    // unknown commands and characters are reported as such
+    let err = parse_latex(r"\unknown{x}").unwrap_err();
+    assert_eq!(err.span(), 0..8);
+    assert_eq!(err.found(), Some(&LatexToken::Command("unknown".into())));
+    assert_eq!(err.reason(), &SimpleReason::Unexpected);
+    let err = parse_latex(r"|x|").unwrap_err();
+    assert_eq!(err.span(), 0..1);
+    assert_eq!(err.found(), Some(&LatexToken::Unknown('|')));
+}
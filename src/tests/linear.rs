@@ -0,0 +1,107 @@
+use crate::linear::{solve_linear, LinearSolution};
+use crate::simplify::SimpleExpr;
+
+use super::expr;
+
+fn solved(values: &[(&str, &str)], free: &[&str]) -> LinearSolution {
+    LinearSolution::Solved {
+        values: values
+            .iter()
+            .map(|(var, value)| (var.to_string(), expr(value)))
+            .collect(),
+        free: free.iter().map(|x| x.to_string()).collect(),
+    }
+}
+
+#[test]
+pub fn rational_systems() {
+    let system = vec![expr("2x + 3y = 5"), expr("x - y = 1")];
+    assert_eq!(
+        solve_linear(system, &["x", "y"]).unwrap(),
+        solved(&[("x", "8/5"), ("y", "3/5")], &[])
+    );
+    let system = vec![
+        expr("x/2 + y/3 = -2/3"),
+        expr("x + y + z = 0"),
+        expr("2z - x = 4"),
+    ];
+    assert_eq!(
+        solve_linear(system, &["x", "y", "z"]).unwrap(),
+        solved(&[("x", "-4 + 2z"), ("y", "4 - 3z")], &["z"])
+    );
+    let system = vec![expr("x + y = 1"), expr("2x + 2y = 3")];
+    assert_eq!(
+        solve_linear(system, &["x", "y"]).unwrap(),
+        LinearSolution::Inconsistent
+    );
+    assert!(solve_linear(vec![expr("x y = 1")], &["x", "y"]).is_err());
+}
+
+#[test]
+pub fn symbolic_coefficients() {
+    let system = vec![expr("a x + b y = c"), expr("d x + e y = f")];
+    let det = "(a e - b d)^-1";
+    assert_eq!(
+        solve_linear(system, &["x", "y"]).unwrap(),
+        solved(
+            &[
+                ("x", &format!("c e {det} - b f {det}")),
+                ("y", &format!("a f {det} - c d {det}")),
+            ],
+            &[]
+        )
+    );
+    // the eliminated entries divide exactly, so no nested inverses are left
+    let system = vec![
+        expr("a x + y + z = 1"),
+        expr("x + a y + z = 1"),
+        expr("x + y + a z = 1"),
+    ];
+    let value = "(a + 2)^-1";
+    assert_eq!(
+        solve_linear(system, &["x", "y", "z"]).unwrap(),
+        solved(&[("x", value), ("y", value), ("z", value)], &[])
+    );
+    let system = vec![
+        expr("a x + b y + z = 1"),
+        expr("x + y + c z = 2"),
+        expr("x - y + z = d"),
+    ];
+    let quotient = |numerator: &str| {
+        let value = expr(&format!("({numerator}) (a - b + a c + b c - 2)^-1"));
+        value.expand().unwrap()
+    };
+    assert_eq!(
+        solve_linear(system, &["x", "y", "z"]).unwrap(),
+        LinearSolution::Solved {
+            values: vec![
+                ("x".into(), quotient("c - 1 - 2b - d + b c d")),
+                ("y".into(), quotient("2a + c - 3 + d - a c d")),
+                ("z".into(), quotient("2a + 2b - 2 + a d - b d")),
+            ],
+            free: vec![],
+        }
+    );
+}
+
+#[test]
+pub fn solve_in_parsed_input() {
+    assert_eq!(
+        expr("Solve[{2x + 3y = 5, x - y = 1}, {x, y}]"),
+        expr("{x = 8/5, y = 3/5}")
+    );
+    assert_eq!(expr("Solve[{x + y = 1, x + y = 2}, {x, y}]"), expr("{}"));
+    assert_eq!(expr("Solve[x^2 = 4, x]"), expr("{x = -2, x = 2}"));
+    assert_eq!(expr("Solve[x^2 = 4, {x}]"), expr("{x = -2, x = 2}"));
+    // equations the solvers do not handle are kept
+    for s in [
+        "Solve[Sin[x] = 0, x]",
+        "Solve[{x^2 + y = 1, x - y = 2}, {x, y}]",
+    ] {
+        let SimpleExpr::Function(name, _) = expr(s) else {
+            panic!("{s} should stay unevaluated");
+        };
+        assert_eq!(name, "Solve");
+    }
+    assert_eq!(expr("2x + 3(x + 1)"), expr("2 * x + 3 * (x + 1)"));
+}
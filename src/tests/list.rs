@@ -0,0 +1,61 @@
+use crate::parse::parse_into_expression;
+use crate::print::to_latex;
+use crate::simplify::SimpleExpr;
+use crate::ComputeError;
+
+use super::{sn, ss};
+
+use super::expr;
+
+#[test]
+pub fn listable_arithmetic() {
+    assert_eq!(
+        expr("{1, 2, 3} + 1"),
+        SimpleExpr::List(vec![sn(2), sn(3), sn(4)])
+    );
+    assert_eq!(expr("{1, 2} * {x, y}"), expr("{x, 2y}"));
+    assert_eq!(expr("{1, 2, 3}^2"), expr("{1, 4, 9}"));
+    assert_eq!(expr("2^{1, 2}"), expr("{2, 4}"));
+    assert_eq!(expr("{x, {y, z}} * 2"), expr("{2x, {2y, 2z}}"));
+    assert!(parse_into_expression("{1, 2} + {1, 2, 3}")
+        .unwrap()
+        .simplify()
+        .is_err());
+}
+
+#[test]
+pub fn list_functions() {
+    assert_eq!(expr("Length[{a, b, c}]"), sn(3));
+    assert_eq!(expr("Length[a + b]"), sn(2));
+    assert_eq!(expr("Part[{a, b, c}, 2]"), ss("b"));
+    assert_eq!(expr("Part[{a, b, c}, -1]"), ss("c"));
+    assert!(parse_into_expression("Part[{a, b}, 3]")
+        .unwrap()
+        .simplify()
+        .is_err());
+    assert_eq!(expr("Table[i^2, {i, 1, 4}]"), expr("{1, 4, 9, 16}"));
+    assert_eq!(expr("Table[x^i, {i, 3}]"), expr("{x, x^2, x^3}"));
+    assert_eq!(expr("Table[i, {i, 0, 1, 1/2}]"), expr("{0, 1/2, 1}"));
+    assert_eq!(expr("Table[i, {i, 2, 1}]"), expr("{}"));
+    assert!(matches!(
+        parse_into_expression("Table[i, {i, 1, 100000000}]")
+            .unwrap()
+            .simplify(),
+        Err(ComputeError::Overflow(_))
+    ));
+    assert_eq!(
+        expr("Map[Sin, {x, y}]"),
+        SimpleExpr::List(vec![
+            SimpleExpr::Function("Sin".into(), vec![ss("x")]),
+            SimpleExpr::Function("Sin".into(), vec![ss("y")]),
+        ])
+    );
+}
+
+#[test]
+pub fn ordering_and_latex() {
+    assert_eq!(expr("{b, a} + {a, b}"), expr("{a + b, a + b}"));
+    assert!(expr("{1, 2}") < expr("{1, 3}"));
+    assert!(expr("x") < expr("{x}"));
+    assert_eq!(to_latex(&expr("{1, x}")), "\\left\\{ 1, x \\right\\}");
+}
@@ -0,0 +1,56 @@
+use crate::print::{to_latex, to_mathml, MathMl};
+
+use super::expr;
+
+fn presentation(s: &str) -> String {
+    let out = to_mathml(&expr(s), MathMl::Presentation);
+    out.strip_prefix(r#"<math xmlns="http://www.w3.org/1998/Math/MathML">"#)
+        .and_then(|out| out.strip_suffix("</math>"))
+        .unwrap()
+        .to_owned()
+}
+
+#[test]
+pub fn presentation_markup() {
+    assert_eq!(
+        presentation("2 x/(3 y^2)"),
+        "<mfrac><mrow><mn>2</mn><mo>⋅</mo><mi>x</mi></mrow>\
+         <mrow><mn>3</mn><mo>⋅</mo><msup><mi>y</mi><mn>2</mn></msup></mrow></mfrac>"
+    );
+    assert_eq!(
+        presentation("(a + b)^2 c"),
+        "<mrow><msup><mrow><mo>(</mo><mrow><mi>a</mi><mo>+</mo><mi>b</mi></mrow><mo>)</mo></mrow>\
+         <mn>2</mn></msup><mo>⋅</mo><mi>c</mi></mrow>"
+    );
+    assert_eq!(
+        presentation("x^(1/3)"),
+        "<mroot><mi>x</mi><mn>3</mn></mroot>"
+    );
+    assert_eq!(
+        presentation("x < 2"),
+        "<mrow><mi>x</mi><mo>&lt;</mo><mn>2</mn></mrow>"
+    );
+}
+
+#[test]
+pub fn content_markup() {
+    assert_eq!(
+        to_mathml(&expr("x^2 + Sin[x]"), MathMl::Content),
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><apply><plus/>\
+         <apply><sin/><ci>x</ci></apply>\
+         <apply><power/><ci>x</ci><cn type=\"integer\">2</cn></apply></apply></math>"
+    );
+    assert!(to_mathml(&expr("-2/3 f[x]"), MathMl::Content).contains(
+        "<apply><times/><cn type=\"rational\">-2<sep/>3</cn><apply><ci>f</ci><ci>x</ci></apply>"
+    ));
+}
+
+#[test]
+pub fn latex_parentheses() {
+    assert_eq!(
+        to_latex(&expr("(x + 1) (x - 1)")),
+        "(-1 + x) \\cdot (1 + x)"
+    );
+    assert_eq!(to_latex(&expr("(n + 1)!")), "(1 + n)!");
+    assert_eq!(to_latex(&expr("Sin[x]!")), "Sin(x)!");
+}
@@ -0,0 +1,73 @@
+use crate::matrix::Matrix;
+use crate::print::to_latex;
+
+use super::sn;
+
+use super::expr;
+
+fn matrix(s: &str) -> Matrix {
+    Matrix::from_expr(&expr(s)).unwrap()
+}
+
+#[test]
+pub fn determinant_and_inverse() {
+    assert_eq!(matrix("{{1, 2}, {3, 4}}").det().unwrap(), sn(-2));
+    assert_eq!(
+        matrix("{{0, 1, 2}, {1, 0, 3}, {4, -3, 8}}").det().unwrap(),
+        sn(-2)
+    );
+    assert_eq!(matrix("{{a, b}, {c, d}}").det().unwrap(), expr("a d - b c"));
+    // the symbolic path agrees with the rational one
+    assert_eq!(
+        matrix("{{x, 1, 0}, {2, 3, 1}, {0, 1, 4}}").det().unwrap(),
+        expr("11x - 8")
+    );
+
+    let m = matrix("{{1, 2}, {3, 4}}");
+    assert_eq!(m.inverse().unwrap(), matrix("{{-2, 1}, {3/2, -1/2}}"));
+    assert_eq!(m.mul(&m.inverse().unwrap()).unwrap(), Matrix::identity(2));
+    assert!(matrix("{{1, 2}, {2, 4}}").inverse().is_err());
+    assert_eq!(
+        expr("Inverse[{{a, 0}, {0, b}}]"),
+        expr("{{a^-1, 0}, {0, b^-1}}")
+    );
+}
+
+#[test]
+pub fn elimination() {
+    let m = matrix("{{1, 2, 3}, {4, 5, 6}, {7, 8, 9}}");
+    assert_eq!(m.rank().unwrap(), 2);
+    assert_eq!(
+        m.rref().unwrap(),
+        matrix("{{1, 0, -1}, {0, 1, 2}, {0, 0, 0}}")
+    );
+    assert_eq!(m.nullspace().unwrap(), vec![vec![sn(1), sn(-2), sn(1)]]);
+    assert_eq!(
+        matrix("{{1, 2, 3}, {4, 5, 6}}").transpose(),
+        matrix("{{1, 4}, {2, 5}, {3, 6}}")
+    );
+    assert!(matrix("{{1, 2}}").mul(&matrix("{{1, 2}}")).is_err());
+}
+
+#[test]
+pub fn parsed_input_and_latex() {
+    assert_eq!(expr("Det[{{1, 2}, {3, 4}}]"), sn(-2));
+    assert_eq!(expr("MatrixRank[{{1, 2}, {2, 4}}]"), sn(1));
+    assert_eq!(
+        expr("Dot[{{1, 2}, {3, 4}}, {{0, 1}, {1, 0}}]"),
+        expr("{{2, 1}, {4, 3}}")
+    );
+    assert_eq!(
+        expr("Dot[{{1, 2}, {3, 4}}, {x, y}]"),
+        expr("{x + 2y, 3x + 4y}")
+    );
+    assert_eq!(expr("Dot[{1, 2}, {3, 4}]"), sn(11));
+    assert_eq!(
+        to_latex(&expr("{{1, 2}, {3, 4}}")),
+        "\\begin{pmatrix} 1 & 2 \\\\ 3 & 4 \\end{pmatrix}"
+    );
+    assert_eq!(
+        to_latex(&expr("{{1}, 2}")),
+        "\\left\\{ \\left\\{ 1 \\right\\}, 2 \\right\\}"
+    );
+}
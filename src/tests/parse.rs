@@ -1,4 +1,6 @@
-use crate::parse::{Token, Tokenizer};
+use crate::parse::{parse_into_expression, Token, Tokenizer};
+use crate::relation::RelOp;
+use crate::BasicAlgebraicExpr;
 
 #[test]
 pub fn tokenize() {
@@ -49,3 +51,70 @@ pub fn tokenize() {
         ]
     );
 }
+
+#[test]
+pub fn tokenize_relations() {
+    let mut tokenizer = Tokenizer::new("a = b == c != d < e <= f > g >= h -> i!");
+    let symbol = |s: &str| Token::Symbol(s.to_string());
+    assert_eq!(
+        tokenizer.scan_tokens(),
+        vec![
+            symbol("a"),
+            Token::Relation(RelOp::Equal),
+            symbol("b"),
+            Token::Relation(RelOp::Equal),
+            symbol("c"),
+            Token::Relation(RelOp::NotEqual),
+            symbol("d"),
+            Token::Relation(RelOp::Less),
+            symbol("e"),
+            Token::Relation(RelOp::LessEqual),
+            symbol("f"),
+            Token::Relation(RelOp::Greater),
+            symbol("g"),
+            Token::Relation(RelOp::GreaterEqual),
+            symbol("h"),
+            Token::Arrow,
+            symbol("i"),
+            Token::Factorial,
+        ]
+    );
+    // other characters become error tokens instead of panicking
+    assert_eq!(
+        Tokenizer::new("1.5").scan_tokens(),
+        vec![
+            Token::Number(1.into()),
+            Token::Unknown('.'),
+            Token::Number(5.into())
+        ]
+    );
+    assert!(parse_into_expression("x $ y").is_err());
+}
+
+#[test]
+pub fn juxtaposition() {
+    let parse = |s| parse_into_expression(s).unwrap();
+    assert_eq!(parse("2x"), parse("2 * x"));
+    assert_eq!(parse("3(x + 1)"), parse("3 * (x + 1)"));
+    assert_eq!(parse("2x^2"), parse("2 * x^2"));
+    // a minus after a factor is still subtraction
+    assert_eq!(parse("a -b"), parse("a - b"));
+}
+
+#[test]
+pub fn capitalized_symbols() {
+    let parse = |s| parse_into_expression(s).unwrap();
+    assert_eq!(parse("Pi"), BasicAlgebraicExpr::Symbol("Pi".into()));
+    assert_eq!(parse("Sin"), BasicAlgebraicExpr::Symbol("Sin".into()));
+    // lowercase runs are still products of single letters
+    assert_eq!(parse("xy"), parse("x y"));
+}
+
+#[test]
+pub fn powers() {
+    let parse = |s| parse_into_expression(s).unwrap();
+    assert_eq!(parse("a^b^c"), parse("a^(b^c)"));
+    // a minus in the exponent applies to the whole tower after it
+    assert_eq!(parse("x^-y^2"), parse("x^(-(y^2))"));
+    assert_eq!(parse("x^-2 y"), parse("x^(-2) y"));
+}
@@ -0,0 +1,75 @@
+use crate::pattern::{replace_all, replace_repeated, Rule};
+use crate::ComputeError;
+
+use super::expr;
+
+fn rules(rules: &[&str]) -> Vec<Rule> {
+    rules.iter().map(|r| Rule::parse(r).unwrap()).collect()
+}
+
+#[test]
+pub fn commutative_match() {
+    let pythagoras = rules(&["Sin[x_]^2 + Cos[x_]^2 -> 1"]);
+    assert_eq!(
+        replace_all(expr("Cos[y]^2 + z + Sin[y]^2"), &pythagoras).unwrap(),
+        expr("1 + z")
+    );
+    // both wildcards must bind to the same expression
+    let mismatched = expr("Cos[y]^2 + Sin[z]^2");
+    assert_eq!(
+        replace_all(mismatched.clone(), &pythagoras).unwrap(),
+        mismatched
+    );
+}
+
+#[test]
+pub fn constraints_and_sequences() {
+    let double = rules(&["f[n_Integer] -> 2 * n"]);
+    assert_eq!(
+        replace_all(expr("f[3] + f[y]"), &double).unwrap(),
+        expr("6 + f[y]")
+    );
+
+    let drop_first = rules(&["g[a_, xs__] -> h[xs__]"]);
+    assert_eq!(
+        replace_all(expr("g[1, 2, 3]"), &drop_first).unwrap(),
+        expr("h[2, 3]")
+    );
+
+    let square_rest = rules(&["a_^2 + xs__ -> xs__"]);
+    assert_eq!(
+        replace_all(expr("y^2 + x + z"), &square_rest).unwrap(),
+        expr("x + z")
+    );
+
+    // a sequence bound by an earlier operand restricts what the later one can take
+    let leftover = rules(&["f[xs__] + xs__ + ys__ -> g[ys__]"]);
+    assert_eq!(
+        replace_all(expr("f[a, b] + a + b + c"), &leftover).unwrap(),
+        expr("g[c]")
+    );
+    // repeated names never match distinct operands, and must fail without trying every split
+    let twice = rules(&["xs__ + xs__Symbol -> h[xs__]"]);
+    let symbols: Vec<_> = ('a'..='x').map(String::from).collect();
+    let distinct = expr(&symbols.join(" + "));
+    assert_eq!(replace_all(distinct.clone(), &twice).unwrap(), distinct);
+}
+
+#[test]
+pub fn repeated_rewriting() {
+    // rules are tried in order, so the more specific one must come first to end the rewriting
+    let shift = rules(&["f[a_, 0] -> a", "f[a_, b_] -> f[a + 1, b - 1]"]);
+    assert_eq!(
+        replace_repeated(expr("f[1, 3]"), &shift).unwrap(),
+        expr("4")
+    );
+}
+
+#[test]
+pub fn non_terminating_rewriting() {
+    let grow = rules(&["f[a_] -> f[a + 1]"]);
+    assert!(matches!(
+        replace_repeated(expr("f[0]"), &grow),
+        Err(ComputeError::Unsupported(_))
+    ));
+}
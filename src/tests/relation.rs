@@ -0,0 +1,41 @@
+use crate::parse::parse_into_expression;
+use crate::print::to_latex;
+use crate::relation::RelOp;
+use crate::simplify::SimpleExpr;
+
+use super::{sn, ss};
+
+use super::expr;
+
+#[test]
+pub fn parse_and_simplify() {
+    let equation = expr("x^2 + 1 + 1 = 5");
+    assert_eq!(
+        equation,
+        SimpleExpr::relation(
+            RelOp::Equal,
+            SimpleExpr::Sum(vec![sn(2), SimpleExpr::Pow(Box::new((ss("x"), sn(2))))]),
+            sn(5)
+        )
+    );
+    assert!(parse_into_expression("a < b < c").is_err());
+}
+
+#[test]
+pub fn move_to_one_side() {
+    assert_eq!(
+        expr("x^2 + 1 = 5").to_one_side().unwrap(),
+        expr("x^2 - 4 = 0")
+    );
+    assert_eq!(
+        expr("2 * x >= x + y").to_one_side().unwrap(),
+        expr("x - y >= 0")
+    );
+}
+
+#[test]
+pub fn latex() {
+    assert_eq!(to_latex(&expr("x = 5")), "x = 5");
+    assert_eq!(to_latex(&expr("x != 5")), "x \\neq 5");
+    assert_eq!(to_latex(&expr("x <= y")), "x \\leq y");
+}
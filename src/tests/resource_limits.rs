@@ -0,0 +1,137 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::limits::{with_limits, Limit, Limits};
+use crate::parse::parse_into_expression;
+use crate::simplify::SimpleExpr;
+use crate::{ComputeError, ComputeResult};
+
+fn simplify(s: &str) -> ComputeResult {
+    parse_into_expression(s).unwrap().simplify()
+}
+
+fn limit(e: ComputeError) -> Limit {
+    match e {
+        ComputeError::LimitExceeded { limit, .. } => limit,
+        e => panic!("expected a limit, got {e:?}"),
+    }
+}
+
+#[test]
+pub fn steps_and_bits() {
+    let sum = "Sum[k^2, {k, 1, 200}]";
+    let steps = |n| with_limits(Limits::new().with_steps(n), || simplify(sum));
+    assert_eq!(limit(steps(10).unwrap_err()), Limit::Steps);
+    assert_eq!(steps(1_000_000).unwrap(), simplify(sum).unwrap());
+
+    let bits = Limits::new().with_max_bits(64);
+    assert_eq!(
+        with_limits(bits.clone(), || simplify("2^30")).unwrap(),
+        SimpleExpr::from(1 << 30)
+    );
+    let e = with_limits(bits.clone(), || simplify("3^100")).unwrap_err();
+    assert_eq!(e.expr(), &SimpleExpr::Pow(Box::new((3.into(), 100.into()))));
+    assert_eq!(limit(e), Limit::Bits);
+    assert_eq!(
+        limit(with_limits(bits, || simplify("100!")).unwrap_err()),
+        Limit::Bits
+    );
+    // without limits
+    assert!(simplify("3^100").is_ok());
+    assert!(matches!(
+        simplify("7^(10^12)"),
+        Err(ComputeError::Overflow(_))
+    ));
+}
+
+#[test]
+pub fn deadline_and_cancellation() {
+    let past = Limits::new().with_deadline(Instant::now() - Duration::from_millis(1));
+    assert_eq!(
+        limit(with_limits(past, || simplify("x + x")).unwrap_err()),
+        Limit::Deadline
+    );
+    let later = Limits::new().with_timeout(Duration::from_secs(60));
+    assert!(with_limits(later, || simplify("x + x")).is_ok());
+
+    let flag = Arc::new(AtomicBool::new(true));
+    let cancelled = Limits::new().with_cancellation(flag);
+    assert_eq!(
+        limit(with_limits(cancelled, || simplify("x + x")).unwrap_err()),
+        Limit::Cancelled
+    );
+}
+
+#[test]
+pub fn deadline_in_loops() {
+    let timeout = |ms| Limits::new().with_timeout(Duration::from_millis(ms));
+    // trial division near the 40 bit cap, Kronecker's method and a long table
+    for s in [
+        "Solve[x^2 - 1099511627689 = 0, x]",
+        "Apart[1/(x^2 - 1099511627689), x]",
+        "Table[i, {i, 1, 1000000}]",
+    ] {
+        let start = Instant::now();
+        let e = with_limits(timeout(50), || simplify(s)).unwrap_err();
+        assert_eq!(limit(e), Limit::Deadline, "{s}");
+        // the loops check the deadline, each would take about a second otherwise
+        assert!(start.elapsed() < Duration::from_millis(500), "{s}");
+    }
+    // inputs past the caps give up early by themselves
+    for s in [
+        "Solve[x^2 - 2*10^30 = 0, x]",
+        "Apart[1/(x^2 - 2*10^30), x]",
+        "3^(99999999/100000000)",
+        "Table[i, {i, 1, 100000000}]",
+    ] {
+        let start = Instant::now();
+        let result = with_limits(timeout(1000), || simplify(s));
+        assert!(
+            !matches!(result, Err(ComputeError::LimitExceeded { .. })),
+            "{s}"
+        );
+        assert!(start.elapsed() < Duration::from_secs(5), "{s}");
+    }
+}
+
+#[test]
+pub fn nested() {
+    let result = with_limits(Limits::new().with_steps(1000), || {
+        // the inner limits apply until the closure returns
+        let inner = with_limits(Limits::new().with_steps(0), || simplify("x + x"));
+        assert_eq!(limit(inner.unwrap_err()), Limit::Steps);
+        simplify("x + x")
+    });
+    assert_eq!(result.unwrap(), simplify("2x").unwrap());
+}
+
+#[test]
+pub fn restored_after_panic() {
+    let panicked = std::panic::catch_unwind(|| {
+        with_limits(Limits::new().with_steps(0), || panic!("inside the limits"))
+    });
+    assert!(panicked.is_err());
+    assert!(simplify("x + x").is_ok());
+}
+
+#[test]
+pub fn numeric_loops() {
+    let steps = |n, s| with_limits(Limits::new().with_steps(n), || simplify(s)).unwrap_err();
+    // quadrature, sampling for poles and the terms of a numeric sum
+    for (s, inner) in [
+        ("Integrate[Sin[x^2], {x, 0, 30}]", "Sin[x^2]"),
+        (
+            "Integrate[Exp[x^2]/(2 + Sin[x]), {x, 0, 1}]",
+            "Exp[x^2]/(2 + Sin[x])",
+        ),
+        (
+            "Integrate[Exp[x^2] Sum[x^k/k^2, {k, 1, Infinity}], {x, 0, 1/2}]",
+            "Sum[x^k/k^2, {k, 1, Infinity}]",
+        ),
+    ] {
+        let e = steps(1000, s);
+        assert_eq!(e.expr(), &simplify(inner).unwrap(), "{s}");
+        assert_eq!(limit(e), Limit::Steps, "{s}");
+    }
+}
@@ -0,0 +1,60 @@
+use serde_json::json;
+
+use crate::constant::Constant;
+use crate::parse::parse_into_expression;
+use crate::simplify::SimpleExpr;
+use crate::BasicAlgebraicExpr;
+
+use super::expr;
+
+#[test]
+pub fn format() {
+    let x = json!({"type": "Symbol", "args": "x"});
+    assert_eq!(
+        serde_json::to_value(expr("x^2 + 1/3")).unwrap(),
+        json!({"type": "Sum", "args": [
+            {"type": "Const", "args": "1/3"},
+            {"type": "Pow", "args": [x, {"type": "Const", "args": "2"}]},
+        ]})
+    );
+    assert_eq!(
+        serde_json::to_value(expr("Sin[x] < 1")).unwrap(),
+        json!({"type": "Relation", "args": ["Less", [
+            {"type": "Function", "args": ["Sin", [x]]},
+            {"type": "Const", "args": "1"},
+        ]]})
+    );
+    let big = Constant::from(num::BigInt::from(10).pow(40u32)) / Constant::from(3);
+    assert_eq!(
+        serde_json::to_string(&big).unwrap(),
+        format!("\"{}/3\"", "1".to_owned() + &"0".repeat(40))
+    );
+}
+
+#[test]
+pub fn round_trip() {
+    for s in ["{a, 2 b!, -3/7 Sqrt[c]}", "x = y^(1/3)", "D[f[x], x]"] {
+        let x = expr(s);
+        let json = serde_json::to_string(&x).unwrap();
+        assert_eq!(serde_json::from_str::<SimpleExpr>(&json).unwrap(), x);
+    }
+    let x = parse_into_expression("-(x + 1)/2").unwrap();
+    let json = serde_json::to_string(&x).unwrap();
+    assert_eq!(
+        serde_json::from_str::<BasicAlgebraicExpr>(&json).unwrap(),
+        x
+    );
+}
+
+#[test]
+pub fn invalid_constants() {
+    let constant = |s: &str| serde_json::from_value::<Constant>(json!(s));
+    assert_eq!(
+        constant("-4/6").unwrap(),
+        Constant::from(-2) / Constant::from(3)
+    );
+    assert_eq!(constant("12").unwrap(), Constant::from(12));
+    assert!(constant("1/0").is_err());
+    assert!(constant("1.5").is_err());
+    assert!(serde_json::from_value::<Constant>(json!(1)).is_err());
+}
@@ -0,0 +1,81 @@
+use crate::calculus::{series, Series};
+use crate::print::to_latex;
+use crate::simplify::SimpleExpr;
+
+use super::expr;
+
+fn normal(s: &str) -> SimpleExpr {
+    expr(&format!("Normal[{s}]"))
+}
+
+#[test]
+pub fn elementary_functions() {
+    assert_eq!(
+        normal("Series[Exp[x], {x, 0, 3}]"),
+        expr("1 + x + x^2/2 + x^3/6")
+    );
+    assert_eq!(
+        normal("Series[Sin[x]/x, {x, 0, 4}]"),
+        expr("1 - x^2/6 + x^4/120")
+    );
+    assert_eq!(
+        normal("Series[Tan[x], {x, 0, 5}]"),
+        expr("x + x^3/3 + 2x^5/15")
+    );
+    assert_eq!(
+        normal("Series[ArcTan[x], {x, 0, 5}]"),
+        expr("x - x^3/3 + x^5/5")
+    );
+    assert_eq!(
+        normal("Series[Sqrt[1 + x], {x, 0, 2}]"),
+        expr("1 + x/2 - x^2/8")
+    );
+    assert_eq!(
+        normal("Series[Exp[a x], {x, 0, 2}]"),
+        expr("1 + a x + a^2 x^2/2")
+    );
+    // Laurent series
+    assert_eq!(normal("Series[1/Sin[x], {x, 0, 1}]"), expr("1/x + x/6"));
+    // no power series at a branch point
+    assert_eq!(
+        expr("Series[Sqrt[x], {x, 0, 2}]"),
+        SimpleExpr::Function("Series".into(), vec![expr("Sqrt[x]"), expr("{x, 0, 2}")])
+    );
+}
+
+#[test]
+pub fn order_term() {
+    let s = series(&expr("Log[x]"), "x", &1.into(), 2).unwrap().unwrap();
+    assert_eq!(s.order(), 3);
+    assert_eq!(s.valuation(), 1);
+    assert_eq!(Series::from_series_data(&s.to_series_data()), Some(s));
+    assert_eq!(
+        to_latex(&expr("Series[Exp[x], {x, 0, 2}]")),
+        "1 + x + \\frac { 1 } { 2 } \\cdot (x)^{2} + O((x)^{3})"
+    );
+    assert_eq!(
+        to_latex(&expr("Series[x^2, {x, 2, 3}]")),
+        "4 + 4 \\cdot (-2 + x) + (-2 + x)^{2} + O((-2 + x)^{4})"
+    );
+}
+
+#[test]
+pub fn operations() {
+    let at_zero = |s: &str| series(&expr(s), "x", &0.into(), 5).unwrap().unwrap();
+    let (sin, cos) = (at_zero("Sin[x]"), at_zero("Cos[x]"));
+    assert_eq!(sin.div(&cos).unwrap(), at_zero("Tan[x]"));
+    assert_eq!(
+        sin.mul(&sin)
+            .unwrap()
+            .add(&cos.mul(&cos).unwrap())
+            .unwrap()
+            .to_expr()
+            .unwrap(),
+        1
+    );
+    // Exp[Sin[x]] as a composition
+    let exp = series(&expr("Exp[y]"), "y", &0.into(), 5).unwrap().unwrap();
+    assert_eq!(exp.compose(&sin).unwrap(), at_zero("Exp[Sin[x]]"));
+    // the inverse of Sin is ArcSin
+    assert_eq!(sin.revert().unwrap(), at_zero("ArcSin[x]"));
+}
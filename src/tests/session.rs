@@ -0,0 +1,73 @@
+use crate::assumptions::Assumption;
+use crate::parse::parse_into_expression;
+use crate::session::{CacheStats, Session};
+use crate::BasicAlgebraicExpr;
+
+use super::expr;
+
+fn parse(s: &str) -> BasicAlgebraicExpr {
+    parse_into_expression(s).unwrap()
+}
+
+#[test]
+pub fn repeated_subexpressions() {
+    let mut session = Session::new();
+    let s = "Sin[(x + 1)^2 + 2 (x + 1)] + Cos[(x + 1)^2 + 2 (x + 1)]";
+    assert_eq!(session.simplify(parse(s)).unwrap(), expr(s));
+    let stats = session.stats();
+    assert!(stats.hits > 0);
+    // everything is cached the second time
+    assert_eq!(session.simplify(parse(s)).unwrap(), expr(s));
+    assert_eq!(session.stats().hits, stats.hits + 1);
+    assert_eq!(session.stats().misses, stats.misses);
+
+    session.clear_cache();
+    assert_eq!(session.stats(), CacheStats::default());
+    let mut session = Session::without_cache();
+    assert_eq!(session.simplify(parse(s)).unwrap(), expr(s));
+    assert_eq!(session.stats(), CacheStats::default());
+}
+
+#[test]
+pub fn assumptions() {
+    let mut session = Session::new();
+    let s = "(x^2)^(1/2) + 1";
+    assert_eq!(session.simplify(parse(s)).unwrap(), expr(s));
+    session.assume("x", Assumption::Positive);
+    assert_eq!(session.simplify(parse(s)).unwrap(), expr("x + 1"));
+    assert_eq!(session.stats().hits, 0);
+    // what is simplified under assumptions is not reused without them
+    assert_eq!(
+        session
+            .simplify(parse("Refine[Sqrt[y^2], y > 0] + Sqrt[y^2]"))
+            .unwrap(),
+        expr("y + Sqrt[y^2]")
+    );
+}
+
+#[test]
+pub fn eviction() {
+    let mut session = Session::with_capacity(2);
+    for s in ["a + b", "b + c", "a + b", "c + d", "a + b", "b + c"] {
+        session.simplify(parse(s)).unwrap();
+    }
+    // `b + c` was evicted by `c + d`
+    let stats = session.stats();
+    assert_eq!((stats.hits, stats.misses, stats.len), (2, 4, 2));
+}
+
+#[test]
+pub fn restored_after_panic() {
+    let mut session = Session::new();
+    session.simplify(parse("x + x")).unwrap();
+    let stats = session.stats();
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        session.with_cache(|_| panic!("while simplifying"))
+    }));
+    assert!(panicked.is_err());
+    // the cache is back in its session instead of being left to the next one
+    assert_eq!(session.stats(), stats);
+    let mut other = Session::new();
+    other.simplify(parse("x + x")).unwrap();
+    assert_eq!(other.stats().hits, 0);
+}
@@ -0,0 +1,86 @@
+use crate::simplify::SimpleExpr;
+use crate::solve::{solve, Solution};
+
+use super::sn;
+
+use super::expr;
+
+fn values(s: &str) -> Vec<SimpleExpr> {
+    solve(expr(s), "x")
+        .unwrap()
+        .into_iter()
+        .map(|s| s.value)
+        .collect()
+}
+
+fn root(p: &str, k: i128) -> SimpleExpr {
+    SimpleExpr::Function("Root".into(), vec![expr(p), sn(k)])
+}
+
+#[test]
+pub fn rational_roots() {
+    assert_eq!(values("2 * x + 3 = 0"), vec![expr("-3/2")]);
+    assert_eq!(
+        solve(expr("(x - 1)^2 * (x + 2) = 0"), "x").unwrap(),
+        vec![
+            Solution {
+                value: sn(-2),
+                multiplicity: 1
+            },
+            Solution {
+                value: sn(1),
+                multiplicity: 2
+            },
+        ]
+    );
+    assert_eq!(values("x^2 = 2 * x"), vec![sn(0), sn(2)]);
+    assert!(values("x + 1 = x + 2").is_empty());
+    assert!(solve(expr("x = x"), "x").is_err());
+}
+
+#[test]
+pub fn radicals() {
+    assert_eq!(expr("8^(1/2)"), expr("2 * 2^(1/2)"));
+    assert_eq!(expr("4^(1/2)"), sn(2));
+    assert_eq!(
+        values("x^2 + x - 1 = 0"),
+        vec![expr("-1/2 - 1/2 * 5^(1/2)"), expr("-1/2 + 1/2 * 5^(1/2)")]
+    );
+    assert_eq!(
+        values("x^4 - 5 * x^2 + 6 = 0"),
+        vec![
+            expr("2^(1/2)"),
+            expr("-1 * 2^(1/2)"),
+            expr("3^(1/2)"),
+            expr("-1 * 3^(1/2)"),
+        ]
+    );
+    assert_eq!(
+        values("x^3 = 2"),
+        vec![expr("2^(1/3)"), root("x^3 - 2", 2), root("x^3 - 2", 3)]
+    );
+    // the constant is too large to search for rational roots
+    assert_eq!(
+        values("x^2 - 2000000000000000000000000000000 = 0"),
+        vec![
+            expr("-1000000000000000 * 2^(1/2)"),
+            expr("1000000000000000 * 2^(1/2)")
+        ]
+    );
+    // radicals too large to expand are kept
+    let power = |b: &str, e: &str| SimpleExpr::Pow(Box::new((expr(b), expr(e))));
+    assert_eq!(
+        expr("3^(99999999/100000000)"),
+        power("3", "99999999/100000000")
+    );
+    assert_eq!(expr("(1/3)^(1/100000)"), power("1/3", "1/100000"));
+}
+
+#[test]
+pub fn placeholders_and_symbolic() {
+    let p = "x^5 - x - 1";
+    assert_eq!(values(p), (1..=5).map(|k| root(p, k)).collect::<Vec<_>>());
+    assert_eq!(values("a * x + b = c"), vec![expr("c * a^-1 - b * a^-1")]);
+    assert_eq!(values("a * x^2 + b * x + c = 0").len(), 2);
+    assert!(solve(expr("x^3 + a * x = 1"), "x").is_err());
+}
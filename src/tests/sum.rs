@@ -0,0 +1,67 @@
+use crate::numeric::{approximate, to_f64};
+use crate::parse::parse_into_expression;
+use crate::print::to_latex;
+use crate::simplify::SimpleExpr;
+
+use super::expr;
+
+#[test]
+pub fn sums() {
+    // Faulhaber
+    assert_eq!(expr("Sum[k, {k, 1, 100}]"), 5050);
+    assert_eq!(expr("Sum[k, {k, n}]"), expr("n/2 + n^2/2"));
+    assert_eq!(expr("Sum[k^3, {k, 1, n}]"), expr("n^2/4 + n^3/2 + n^4/4"));
+    assert_eq!(expr("Sum[a k + 1, {k, 1, n}]"), expr("n + a n/2 + a n^2/2"));
+    // the range stops at the last integer step below the upper bound
+    assert_eq!(expr("Sum[k, {k, 1, 1/2}]"), 0);
+    assert_eq!(expr("Sum[k, {k, 1, 7/2}]"), 6);
+    assert_eq!(expr("Sum[k, {k, n, n + 3/2}]"), expr("2n + 1"));
+    assert_eq!(expr("Product[k, {k, 1, 5/2}]"), 2);
+    assert!(matches!(
+        expr("Sum[k, {k, 1/2, n}]"),
+        SimpleExpr::Function(name, _) if name == "Sum"
+    ));
+    // geometric
+    assert_eq!(expr("Sum[2^k, {k, 0, n}]"), expr("2^(n + 1) - 1"));
+    assert_eq!(expr("Sum[(1/3)^k, {k, 0, Infinity}]"), expr("3/2"));
+    // telescoping
+    assert_eq!(expr("Sum[1/(k(k + 1)), {k, 1, n}]"), expr("1 - 1/(n + 1)"));
+    assert_eq!(
+        expr("Sum[1/(k(k + 1)(k + 2)), {k, 1, Infinity}]"),
+        expr("1/4")
+    );
+    assert_eq!(
+        expr("Sum[Log[k + 1] - Log[k], {k, 1, n}]"),
+        expr("Log[n + 1]")
+    );
+}
+
+#[test]
+pub fn products_and_factorials() {
+    assert_eq!(expr("5!"), 120);
+    assert_eq!(expr("Product[k, {k, 1, 10}]"), expr("10!"));
+    assert_eq!(expr("Product[k, {k, 1, n}]"), expr("n!"));
+    assert_eq!(expr("Product[2k, {k, 1, n}]"), expr("2^n n!"));
+    // the falling factorial
+    assert_eq!(expr("Product[x - k, {k, 0, m - 1}]"), expr("x!/(x - m)!"));
+    assert_eq!(
+        expr("Product[k^2 + 3k + 2, {k, 1, n}]"),
+        expr("(n + 1)! (n + 2)!/2")
+    );
+    assert_eq!(expr("Product[2^k, {k, 1, n}]"), expr("2^(n/2 + n^2/2)"));
+    assert_eq!(to_latex(&expr("(n + 1)!")), "(1 + n)!");
+    assert!(parse_into_expression("(-1)!").unwrap().simplify().is_err());
+}
+
+#[test]
+pub fn symbolic_sums() {
+    let basel = expr("Sum[1/k^2, {k, 1, Infinity}]");
+    assert!(matches!(&basel, SimpleExpr::Function(name, _) if name == "Sum"));
+    let pi = std::f64::consts::PI;
+    assert!((to_f64(&basel).unwrap().unwrap() - pi * pi / 6.0).abs() < 1e-10);
+    let e = to_f64(&expr("Sum[1/k!, {k, 0, Infinity}]")).unwrap().unwrap();
+    assert!((e - std::f64::consts::E).abs() < 1e-12);
+    let product = expr("Product[k^2 + 1, {k, 1, n}]");
+    let value = approximate(&product, &|s| (s == "n").then_some(3.0));
+    assert_eq!(value, Ok(Some(100.0)));
+}
@@ -0,0 +1,91 @@
+use crate::parse::parse_into_expression;
+use crate::simplify::SimpleExpr;
+use crate::trace::{is_enabled, trace, Step};
+
+use super::expr;
+
+fn traced(s: &str) -> (SimpleExpr, Vec<Step>) {
+    let (result, steps) = parse_into_expression(s).unwrap().simplify_traced();
+    (result.unwrap(), steps)
+}
+
+fn step(rule: &'static str, before: SimpleExpr, after: &str) -> Step {
+    Step {
+        rule,
+        before,
+        after: expr(after),
+    }
+}
+
+#[test]
+pub fn like_terms() {
+    let x = || SimpleExpr::Symbol("x".into());
+    let times = |c: i32| SimpleExpr::Product(vec![c.into(), x()]);
+    let (result, steps) = traced("x + 2x + 3x");
+    assert_eq!(result, expr("6x"));
+    assert_eq!(
+        steps,
+        [
+            step(
+                "collect like terms",
+                SimpleExpr::Sum(vec![x(), times(2)]),
+                "3x"
+            ),
+            step(
+                "collect like terms",
+                SimpleExpr::Sum(vec![times(3), times(3)]),
+                "6x"
+            ),
+        ]
+    );
+}
+
+#[test]
+pub fn rules() {
+    let rules = |s: &str| traced(s).1.into_iter().map(|x| x.rule).collect::<Vec<_>>();
+    assert_eq!(
+        rules("2 * 3 * x * x^2"),
+        ["multiply constants", "add constants", "add exponents"]
+    );
+    assert_eq!(
+        rules("Sin[Pi] + 0 y + (2^2)!"),
+        [
+            "evaluate function",
+            "multiply by zero",
+            "add constants",
+            "integer power",
+            "evaluate factorial",
+            "add constants"
+        ]
+    );
+    // nothing to rewrite
+    assert!(rules("x + y").is_empty());
+}
+
+#[test]
+pub fn nested() {
+    let ((inner, result), outer) = trace(|| {
+        let before = expr("1 + 1");
+        let (result, inner) = traced("x x");
+        assert_eq!(before, 2);
+        (inner, result)
+    });
+    assert_eq!(result, expr("x^2"));
+    assert_eq!(inner.len(), 2);
+    // the outer trace also sees the steps of the inner one
+    assert_eq!(outer.len(), 3);
+    assert_eq!(outer[1..], inner[..]);
+    // steps are only recorded while tracing
+    let (_, steps) = trace(|| ());
+    assert!(steps.is_empty());
+}
+
+#[test]
+pub fn restored_after_panic() {
+    let panicked = std::panic::catch_unwind(|| trace(|| panic!("while tracing")));
+    assert!(panicked.is_err());
+    // nothing is recorded outside of `trace` again
+    assert!(!is_enabled());
+    let (_, steps) = trace(|| expr("1 + 1"));
+    assert_eq!(steps.len(), 1);
+}
@@ -0,0 +1,74 @@
+use crate::simplify::SimpleExpr;
+use crate::visit::{fold_children, walk, Folder, Node, Visitor};
+
+use super::{n, s, sn, ss};
+
+fn sample() -> SimpleExpr {
+    // 2 * x + Sin[y]^2
+    SimpleExpr::Sum(vec![
+        SimpleExpr::Product(vec![sn(2), ss("x")]),
+        SimpleExpr::Pow(Box::new((
+            SimpleExpr::Function("Sin".into(), vec![ss("y")]),
+            sn(2),
+        ))),
+    ])
+}
+
+#[test]
+pub fn queries() {
+    let e = sample();
+    assert_eq!(e.node_count(), 8);
+    assert_eq!(e.depth(), 4);
+    assert_eq!(e.free_symbols().into_iter().collect::<Vec<_>>(), ["x", "y"]);
+    assert!(e.contains(&ss("y")));
+    assert!(!e.contains(&ss("z")));
+
+    let basic = -(s("x") + n(1));
+    assert_eq!(basic.free_symbols().into_iter().collect::<Vec<_>>(), ["x"]);
+    assert_eq!(basic.depth(), 3);
+}
+
+#[test]
+pub fn traversal_order() {
+    let e = SimpleExpr::Product(vec![ss("a"), SimpleExpr::Sum(vec![ss("b"), ss("c")])]);
+    let pre: Vec<_> = e.pre_order().filter_map(Node::as_symbol).collect();
+    assert_eq!(pre, ["a", "b", "c"]);
+
+    let post: Vec<_> = e.post_order().collect();
+    assert_eq!(post.len(), 5);
+    assert_eq!(post[0], &ss("a"));
+    assert_eq!(post[4], &e);
+}
+
+#[test]
+pub fn visitor_and_folder() {
+    struct CountConstants(usize);
+
+    impl Visitor<SimpleExpr> for CountConstants {
+        fn visit(&mut self, expr: &SimpleExpr) {
+            if expr.is_constant() {
+                self.0 += 1;
+            }
+            walk(self, expr)
+        }
+    }
+
+    struct Rename;
+
+    impl Folder<SimpleExpr> for Rename {
+        fn fold(&mut self, expr: SimpleExpr) -> SimpleExpr {
+            match expr {
+                SimpleExpr::Symbol(x) if x == "x" => ss("z"),
+                expr => fold_children(self, expr),
+            }
+        }
+    }
+
+    let mut counter = CountConstants(0);
+    counter.visit(&sample());
+    assert_eq!(counter.0, 2);
+
+    let renamed = Rename.fold(sample());
+    assert!(renamed.contains(&ss("z")));
+    assert!(!renamed.contains(&ss("x")));
+}
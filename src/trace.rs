@@ -0,0 +1,77 @@
+// A record of the rewrites made while simplifying, for explaining a result to users. This is
+// opt-in: steps are only recorded inside `trace`, and nothing is cloned otherwise.
+use std::cell::RefCell;
+
+use crate::simplify::SimpleExpr;
+use crate::{BasicAlgebraicExpr, ComputeResult};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Step {
+    pub rule: &'static str,
+    pub before: SimpleExpr,
+    pub after: SimpleExpr,
+}
+
+thread_local! {
+    static STEPS: RefCell<Option<Vec<Step>>> = const { RefCell::new(None) };
+}
+
+// restores the enclosing recorder when dropped, also when `f` panics
+struct Restore(Option<Vec<Step>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let outer = self.0.take();
+        STEPS.with(|steps| steps.replace(outer));
+    }
+}
+
+// runs `f`, returning its result with the steps taken, in the order they were made
+pub fn trace<T>(f: impl FnOnce() -> T) -> (T, Vec<Step>) {
+    let restore = Restore(STEPS.with(|steps| steps.replace(Some(Vec::new()))));
+    let result = f();
+    let steps = STEPS.with(|steps| steps.take()).unwrap_or_default();
+    drop(restore);
+    // an enclosing trace sees the steps too
+    STEPS.with(|outer| {
+        if let Some(outer) = &mut *outer.borrow_mut() {
+            outer.extend(steps.iter().cloned());
+        }
+    });
+    (result, steps)
+}
+
+pub(crate) fn is_enabled() -> bool {
+    STEPS.with(|steps| steps.borrow().is_some())
+}
+
+// the expression before a rewrite, only built while tracing
+pub(crate) fn before(f: impl FnOnce() -> SimpleExpr) -> Option<SimpleExpr> {
+    is_enabled().then(f)
+}
+
+pub(crate) fn record(
+    rule: &'static str,
+    before: Option<SimpleExpr>,
+    after: impl FnOnce() -> SimpleExpr,
+) {
+    let Some(before) = before else { return };
+    let after = after();
+    if before != after {
+        STEPS.with(|steps| {
+            if let Some(steps) = &mut *steps.borrow_mut() {
+                steps.push(Step {
+                    rule,
+                    before,
+                    after,
+                });
+            }
+        });
+    }
+}
+
+impl BasicAlgebraicExpr {
+    pub fn simplify_traced(self) -> (ComputeResult, Vec<Step>) {
+        trace(|| self.simplify())
+    }
+}
@@ -0,0 +1,174 @@
+// Generic traversal utilities shared by `SimpleExpr` and `BasicAlgebraicExpr`.
+//
+// Implementors only need to describe their immediate children; everything else
+// (iterators, visitors, folders, queries) is derived from that.
+use std::collections::BTreeSet;
+use std::convert::Infallible;
+
+use smallvec::SmallVec;
+
+use crate::simplify::SimpleExpr;
+use crate::BasicAlgebraicExpr;
+
+pub trait Node: Sized {
+    /// The direct subexpressions of this node, in order.
+    fn children(&self) -> SmallVec<[&Self; 2]>;
+
+    /// Rebuild this node by replacing each direct subexpression with `f(child)`.
+    fn try_map_children<E>(self, f: impl FnMut(Self) -> Result<Self, E>) -> Result<Self, E>;
+
+    fn as_symbol(&self) -> Option<&str>;
+
+    fn map_children(self, mut f: impl FnMut(Self) -> Self) -> Self {
+        match self.try_map_children(|x| Ok::<_, Infallible>(f(x))) {
+            Ok(x) => x,
+            Err(e) => match e {},
+        }
+    }
+
+    fn pre_order(&self) -> PreOrder<'_, Self> {
+        PreOrder { stack: vec![self] }
+    }
+
+    fn post_order(&self) -> PostOrder<'_, Self> {
+        PostOrder {
+            stack: vec![(self, false)],
+        }
+    }
+
+    fn free_symbols(&self) -> BTreeSet<&str> {
+        self.pre_order().filter_map(Node::as_symbol).collect()
+    }
+
+    fn contains(&self, needle: &Self) -> bool
+    where
+        Self: PartialEq,
+    {
+        self.pre_order().any(|x| x == needle)
+    }
+
+    // a leaf has a depth of 1
+    fn depth(&self) -> usize {
+        1 + self
+            .children()
+            .into_iter()
+            .map(Node::depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn node_count(&self) -> usize {
+        self.pre_order().count()
+    }
+}
+
+pub struct PreOrder<'a, T> {
+    stack: Vec<&'a T>,
+}
+
+impl<'a, T: Node> Iterator for PreOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.stack.pop()?;
+        self.stack.extend(next.children().into_iter().rev());
+        Some(next)
+    }
+}
+
+pub struct PostOrder<'a, T> {
+    // the flag is set once the children of a node have been pushed
+    stack: Vec<(&'a T, bool)>,
+}
+
+impl<'a, T: Node> Iterator for PostOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, expanded) = self.stack.pop()?;
+            if expanded {
+                return Some(node);
+            }
+            self.stack.push((node, true));
+            self.stack
+                .extend(node.children().into_iter().rev().map(|x| (x, false)));
+        }
+    }
+}
+
+/// Read-only traversal. Override `visit` and call [`walk`] to descend into children.
+pub trait Visitor<T: Node> {
+    fn visit(&mut self, expr: &T) {
+        walk(self, expr)
+    }
+}
+
+pub fn walk<T: Node, V: Visitor<T> + ?Sized>(visitor: &mut V, expr: &T) {
+    for child in expr.children() {
+        visitor.visit(child);
+    }
+}
+
+/// Owning bottom-up transformation. Override `fold` and call [`fold_children`] to descend into children.
+pub trait Folder<T: Node> {
+    fn fold(&mut self, expr: T) -> T {
+        fold_children(self, expr)
+    }
+}
+
+pub fn fold_children<T: Node, F: Folder<T> + ?Sized>(folder: &mut F, expr: T) -> T {
+    expr.map_children(|x| folder.fold(x))
+}
+
+macro_rules! impl_node {
+    ($Ty:ident { $($Unary:ident),* }) => {
+        impl Node for $Ty {
+            fn children(&self) -> SmallVec<[&Self; 2]> {
+                use $Ty::*;
+                match self {
+                    Const(_) | Symbol(_) => SmallVec::new(),
+                    Product(x) | Sum(x) | Function(_, x) | List(x) => x.iter().collect(),
+                    Pow(x) | Relation(_, x) => smallvec::smallvec![&x.0, &x.1],
+                    Factorial(x) $(| $Unary(x))* => smallvec::smallvec![&**x],
+                }
+            }
+
+            fn try_map_children<E>(
+                self,
+                mut f: impl FnMut(Self) -> Result<Self, E>,
+            ) -> Result<Self, E> {
+                use $Ty::*;
+                Ok(match self {
+                    x @ (Const(_) | Symbol(_)) => x,
+                    Product(x) => Product(x.into_iter().map(f).collect::<Result<_, _>>()?),
+                    Sum(x) => Sum(x.into_iter().map(f).collect::<Result<_, _>>()?),
+                    List(x) => List(x.into_iter().map(f).collect::<Result<_, _>>()?),
+                    Function(name, x) => {
+                        Function(name, x.into_iter().map(f).collect::<Result<_, _>>()?)
+                    }
+                    Pow(x) => {
+                        let (a, b) = *x;
+                        Pow(Box::new((f(a)?, f(b)?)))
+                    }
+                    Relation(op, x) => {
+                        let (a, b) = *x;
+                        Relation(op, Box::new((f(a)?, f(b)?)))
+                    }
+                    Factorial(x) => Factorial(Box::new(f(*x)?)),
+                    $($Unary(x) => $Unary(Box::new(f(*x)?)),)*
+                })
+            }
+
+            fn as_symbol(&self) -> Option<&str> {
+                match self {
+                    $Ty::Symbol(x) => Some(x),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_node!(SimpleExpr {});
+impl_node!(BasicAlgebraicExpr { Neg });